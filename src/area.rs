@@ -1,4 +1,5 @@
 use crate::{Float, Pt};
+use geo_types::{LineString, MultiLineString, MultiPolygon, Polygon, Rect};
 
 #[allow(clippy::unnecessary_cast)]
 // Note that we need to disable the clippy warning about unnecessary casts
@@ -32,22 +33,19 @@ pub fn contains(ring: &[Pt], hole: &[Pt]) -> i32 {
 }
 
 fn ring_contains(ring: &[Pt], point: &Pt) -> i32 {
-    let x = point.x;
     let y = point.y;
     let n = ring.len();
     let mut contains = -1;
     let mut j = n - 1;
     for i in 0..n {
         let pi = &ring[i];
-        let xi = pi.x;
         let yi = pi.y;
         let pj = &ring[j];
-        let xj = pj.x;
         let yj = pj.y;
         if segment_contains(pi, pj, point) {
             return 0;
         }
-        if ((yi > y) != (yj > y)) && (x < (xj - xi) * (y - yi) / (yj - yi) + xi) {
+        if ((yi > y) != (yj > y)) && crosses(pi, pj, point) {
             contains = -contains;
         }
         j = i;
@@ -55,22 +53,183 @@ fn ring_contains(ring: &[Pt], point: &Pt) -> i32 {
     contains
 }
 
-fn segment_contains(a: &Pt, b: &Pt, c: &Pt) -> bool {
-    if collinear(a, b, c) {
-        if (a.x - b.x).abs() < Float::EPSILON {
-            within(a.y, c.y, b.y)
-        } else {
-            within(a.x, c.x, b.x)
-        }
+// Returns whether the horizontal ray starting at `c` towards the positive x
+// crosses the segment from `a` to `b` (knowing that it spans the ordinate of `c`).
+#[cfg(not(feature = "robust"))]
+fn crosses(a: &Pt, b: &Pt, c: &Pt) -> bool {
+    c.x < (b.x - a.x) * (c.y - a.y) / (b.y - a.y) + a.x
+}
+
+#[cfg(feature = "robust")]
+fn crosses(a: &Pt, b: &Pt, c: &Pt) -> bool {
+    // `c` is on the left of the segment walked upwards
+    let o = orient2d(a, b, c);
+    if b.y > a.y {
+        o > 0.
     } else {
-        false
+        o < 0.
     }
 }
 
+fn segment_contains(a: &Pt, b: &Pt, c: &Pt) -> bool {
+    // Both coordinates are checked, as the segment may be degenerate
+    // (such as the one closing a ring whose last point repeats the first one)
+    collinear(a, b, c) && within(a.x, c.x, b.x) && within(a.y, c.y, b.y)
+}
+
+#[cfg(not(feature = "robust"))]
 fn collinear(a: &Pt, b: &Pt, c: &Pt) -> bool {
     ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() < Float::EPSILON
 }
 
+#[cfg(feature = "robust")]
+fn collinear(a: &Pt, b: &Pt, c: &Pt) -> bool {
+    orient2d(a, b, c) == 0.
+}
+
+// Exact orientation of `c` relative to the segment from `a` to `b`
+// (positive when `c` is on its left in a y-up coordinate system).
+#[cfg(feature = "robust")]
+#[allow(clippy::unnecessary_cast)]
+fn orient2d(a: &Pt, b: &Pt, c: &Pt) -> f64 {
+    let coord = |p: &Pt| robust::Coord {
+        x: p.x as f64,
+        y: p.y as f64,
+    };
+    robust::orient2d(coord(a), coord(b), coord(c))
+}
+
 fn within(p: Float, q: Float, r: Float) -> bool {
     p <= q && q <= r || r <= q && q <= p
 }
+
+/// The bounding box of a ring, used to cheaply discard impossible containments.
+#[derive(Clone, Copy, Debug)]
+pub struct Bbox {
+    pub min: Pt,
+    pub max: Pt,
+}
+
+impl Bbox {
+    pub fn new(ring: &[Pt]) -> Self {
+        let mut min = ring[0];
+        let mut max = ring[0];
+        for p in &ring[1..] {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        }
+        Bbox { min, max }
+    }
+
+    /// Returns whether `other` lies within (or on the border of) this bounding box.
+    pub fn contains(&self, other: &Bbox) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && other.max.x <= self.max.x
+            && other.max.y <= self.max.y
+    }
+    /// Returns whether `other` intersects (or touches) this bounding box.
+    pub fn intersects(&self, other: &Bbox) -> bool {
+        self.min.x <= other.max.x
+            && self.min.y <= other.max.y
+            && other.min.x <= self.max.x
+            && other.min.y <= self.max.y
+    }
+}
+
+/// Assigns each hole to the first of the `polygons` whose exterior ring contains it
+/// (the holes enclosed by none of them being dropped), comparing the bounding boxes
+/// of the rings (computed once) before testing their points.
+pub fn assign_holes(polygons: &mut [Polygon<Float>], holes: Vec<LineString<Float>>) {
+    let bboxes = polygons
+        .iter()
+        .map(|polygon| Bbox::new(&polygon.exterior().0))
+        .collect::<Vec<_>>();
+    for hole in holes {
+        let bbox = Bbox::new(&hole.0);
+        let polygon = polygons
+            .iter_mut()
+            .zip(&bboxes)
+            .find(|(polygon, polygon_bbox)| {
+                polygon_bbox.contains(&bbox) && contains(&polygon.exterior().0, &hole.0) != -1
+            })
+            .map(|(polygon, _)| polygon);
+        if let Some(polygon) = polygon {
+            polygon.interiors_push(hole);
+        }
+    }
+}
+
+/// Returns the bounding rectangle of the given points (`None` if there is none).
+pub fn bounding_rect<'a>(points: impl IntoIterator<Item = &'a Pt>) -> Option<Rect<Float>> {
+    let mut points = points.into_iter();
+    let first = points.next()?;
+    let Bbox { min, max } = points.fold(Bbox::new(&[*first]), |mut bbox, p| {
+        bbox.min.x = bbox.min.x.min(p.x);
+        bbox.min.y = bbox.min.y.min(p.y);
+        bbox.max.x = bbox.max.x.max(p.x);
+        bbox.max.y = bbox.max.y.max(p.y);
+        bbox
+    });
+    Some(Rect::new(min, max))
+}
+
+/// Returns a point strictly inside the polygon of the given `rings` (an exterior ring
+/// and its holes), at the middle of its widest span along the horizontal line through
+/// the middle of the ordinates of its exterior ring (`None` if the polygon is degenerate).
+pub fn interior_point(rings: &[&[Pt]]) -> Option<Pt> {
+    let mut ys = rings.first()?.iter().map(|p| p.y).collect::<Vec<_>>();
+    ys.sort_by(|a, b| a.total_cmp(b));
+    ys.dedup();
+    if ys.len() < 2 {
+        return None;
+    }
+    let k = ys.len() / 2;
+    let y = (ys[k - 1] + ys[k]) / 2.;
+    let mut xs = Vec::new();
+    for ring in rings.iter().filter(|ring| !ring.is_empty()) {
+        let mut j = ring.len() - 1;
+        for i in 0..ring.len() {
+            let (a, b) = (&ring[i], &ring[j]);
+            if (a.y > y) != (b.y > y) {
+                xs.push(a.x + (y - a.y) * (b.x - a.x) / (b.y - a.y));
+            }
+            j = i;
+        }
+    }
+    xs.sort_by(|a, b| a.total_cmp(b));
+    // The spans between the crossings of even and odd ranks are inside the polygon
+    xs.chunks_exact(2)
+        .filter(|span| span[1] > span[0])
+        .max_by(|a, b| (a[1] - a[0]).total_cmp(&(b[1] - b[0])))
+        .map(|span| Pt {
+            x: (span[0] + span[1]) / 2.,
+            y,
+        })
+}
+
+/// Returns a point strictly inside each of the polygons (see [`interior_point`]).
+pub fn polygons_interior_points(geometry: &MultiPolygon<Float>) -> Vec<Option<Pt>> {
+    geometry
+        .iter()
+        .map(|polygon| {
+            let rings = std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .map(|ring| &ring.0[..])
+                .collect::<Vec<_>>();
+            interior_point(&rings)
+        })
+        .collect()
+}
+
+/// Returns the bounding rectangle of the polygons (given by their exterior rings).
+pub fn polygons_bbox(geometry: &MultiPolygon<Float>) -> Option<Rect<Float>> {
+    bounding_rect(geometry.iter().flat_map(|polygon| &polygon.exterior().0))
+}
+
+/// Returns the bounding rectangle of the linestrings.
+pub fn lines_bbox(geometry: &MultiLineString<Float>) -> Option<Rect<Float>> {
+    bounding_rect(geometry.iter().flat_map(|linestring| &linestring.0))
+}
@@ -1,5 +1,19 @@
-use crate::Float;
-use geo_types::MultiPolygon;
+use crate::area::{polygons_bbox, polygons_interior_points};
+use crate::{Float, Pt};
+use geo_types::{MultiPolygon, Rect};
+
+/// Whether the values of a [`Band`] computed by
+/// [`ContourBuilder::difference_contours`](crate::ContourBuilder::difference_contours)
+/// increased or decreased between the two grids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// The band is below `0` (its maximum value is lower than or equal to `0`).
+    Loss,
+    /// The band spans `0`.
+    Stable,
+    /// The band is above `0` (its minimum value is greater than or equal to `0`).
+    Gain,
+}
 
 /// An isoband has the geometry and min / max values of a contour ring, built by [ContourBuilder](`crate::contourbuilder::ContourBuilder`).
 #[derive(Debug, Clone)]
@@ -7,9 +21,25 @@ pub struct Band {
     pub(crate) geometry: MultiPolygon<Float>,
     pub(crate) min_v: Float,
     pub(crate) max_v: Float,
+    pub(crate) bbox: Option<Rect<Float>>,
+    pub(crate) interior_points: Vec<Option<Pt>>,
+    pub(crate) threshold_indices: (usize, usize),
 }
 
 impl Band {
+    pub(crate) fn new(geometry: MultiPolygon<Float>, min_v: Float, max_v: Float) -> Self {
+        let bbox = polygons_bbox(&geometry);
+        let interior_points = polygons_interior_points(&geometry);
+        Band {
+            geometry,
+            min_v,
+            max_v,
+            bbox,
+            interior_points,
+            threshold_indices: (0, 1),
+        }
+    }
+
     /// Borrow the [`MultiPolygon`](geo_types::MultiPolygon) geometry of this contour.
     pub fn geometry(&self) -> &MultiPolygon<Float> {
         &self.geometry
@@ -30,6 +60,107 @@ impl Band {
         self.max_v
     }
 
+    /// Get the indices of the thresholds [`min_v`](Self::min_v) and [`max_v`](Self::max_v)
+    /// of this band in the slice of thresholds given to
+    /// [`ContourBuilder::isobands`](crate::ContourBuilder::isobands), to map the bands
+    /// (returned in ascending order) back to unsorted thresholds.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, false);
+    /// let values = [0., 1., 0., 1., 2., 1., 0., 1., 0.];
+    /// let bands = builder.isobands(&values, &[1.5, 0.5, 2.5]).unwrap();
+    /// assert_eq!((bands[0].min_v(), bands[0].max_v()), (0.5, 1.5));
+    /// assert_eq!(bands[0].threshold_indices(), (1, 0));
+    /// assert_eq!(bands[1].threshold_indices(), (0, 2));
+    /// ```
+    pub fn threshold_indices(&self) -> (usize, usize) {
+        self.threshold_indices
+    }
+
+    /// Get the bounding box of this band (`None` if it has no polygon),
+    /// computed along with its geometry.
+    pub fn bbox(&self) -> Option<Rect<Float>> {
+        self.bbox
+    }
+
+    /// Get a point strictly inside each polygon of this band (`None` for a degenerate
+    /// polygon), such as to place a label or to test the containment of the polygon
+    /// in another one, computed along with its geometry.
+    pub fn interior_points(&self) -> &[Option<Pt>] {
+        &self.interior_points
+    }
+
+    /// Tessellates the polygons of this band into a triangle [`Mesh`](crate::Mesh)
+    /// (requires the `mesh` feature).
+    #[cfg(feature = "mesh")]
+    pub fn to_mesh(&self) -> crate::Result<crate::Mesh> {
+        crate::Mesh::from_polygons(&self.geometry)
+    }
+
+    /// Converts the polygons of this band to a [`lyon_path::Path`] of a closed subpath per ring
+    /// (requires the `lyon` feature), to be filled with the even-odd rule
+    /// (`FillRule::EvenOdd`) so that the holes are left empty.
+    #[cfg(feature = "lyon")]
+    pub fn to_path(&self) -> lyon_path::Path {
+        crate::path::polygons_to_path(&self.geometry)
+    }
+
+    /// Get the label of this band for a legend, such as `100–150 m` for the given `unit`
+    /// (appended after a space, unless it is empty), the open bands being labelled
+    /// `< 150 m` or `≥ 100 m`.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, Float};
+    ///
+    /// let builder = ContourBuilder::new(3, 3, false);
+    /// let values = [0., 100., 0., 100., 200., 100., 0., 100., 0.];
+    /// let thresholds = [Float::NEG_INFINITY, 100., 150., Float::INFINITY];
+    /// let bands = builder.isobands(&values, &thresholds).unwrap();
+    /// let labels = bands.iter().map(|b| b.label("m")).collect::<Vec<_>>();
+    /// assert_eq!(labels, ["< 100 m", "100–150 m", "≥ 150 m"]);
+    /// ```
+    pub fn label(&self, unit: &str) -> String {
+        let range = match (self.min_v.is_infinite(), self.max_v.is_infinite()) {
+            (true, false) => format!("< {}", self.max_v),
+            (false, true) => format!("≥ {}", self.min_v),
+            _ => format!("{}–{}", self.min_v, self.max_v),
+        };
+        if unit.is_empty() {
+            range
+        } else {
+            format!("{} {}", range, unit)
+        }
+    }
+
+    #[cfg(feature = "geojson")]
+    /// Convert the band to a struct from the `geojson` crate, as [`to_geojson`](Self::to_geojson)
+    /// does, adding the index of its `class` (`class` property) and its [`label`](Self::label)
+    /// for the given `unit` (`label` property) to its properties, for legends and data-driven
+    /// styling.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, false);
+    /// let values = [0., 1., 0., 1., 2., 1., 0., 1., 0.];
+    /// let bands = builder.isobands(&values, &[0., 1., 2.5]).unwrap();
+    /// let features = bands
+    ///     .iter()
+    ///     .enumerate()
+    ///     .map(|(class, band)| band.to_geojson_with_class(class, "m"))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(features[1].property("class"), Some(&1.into()));
+    /// assert_eq!(features[1].property("label"), Some(&"1–2.5 m".into()));
+    /// ```
+    pub fn to_geojson_with_class(&self, class: usize, unit: &str) -> geojson::Feature {
+        let mut feature = self.to_geojson();
+        feature.set_property("class", class);
+        feature.set_property("label", self.label(unit));
+        feature
+    }
+
     #[cfg(feature = "geojson")]
     /// Convert the band to a struct from the `geojson` crate.
     ///
@@ -71,3 +202,9 @@ impl Band {
         }
     }
 }
+
+impl From<Band> for MultiPolygon<Float> {
+    fn from(band: Band) -> Self {
+        band.geometry
+    }
+}
@@ -0,0 +1,170 @@
+use crate::area::{contains, Bbox};
+use crate::{Band, Float, Pt};
+use geo_types::Polygon;
+
+// The largest number of polygons of a leaf of the hierarchy.
+const LEAF_SIZE: usize = 4;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    band: usize,
+    // The index of the polygon in the geometry of its band
+    polygon: usize,
+    geometry: Polygon<Float>,
+    bbox: Bbox,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bbox: Bbox,
+    // The range of the entries of a leaf, or the indices of the children of a node
+    start: usize,
+    end: usize,
+    leaf: bool,
+}
+
+/// An index of the polygons of the [`Band`]s computed by
+/// [`ContourBuilder::isobands`](crate::ContourBuilder::isobands), to find the band
+/// containing a given point (such as the location of a click) without testing
+/// every polygon.
+///
+/// The polygons are organized in a hierarchy of bounding boxes, so that only the
+/// polygons whose bounding box contains the point are tested, which are about
+/// `log n` of the `n` polygons (as the polygons of the bands don't overlap).
+///
+/// ```
+/// use contour::{BandIndex, ContourBuilder};
+/// use geo_types::coord;
+///
+/// let builder = ContourBuilder::new(3, 3, false);
+/// let values = [0., 0., 0., 0., 2., 0., 0., 0., 0.];
+/// let bands = builder.isobands(&values, &[0., 1., 3.]).unwrap();
+/// let index = BandIndex::new(&bands);
+/// assert_eq!(index.classify(coord! { x: 1.5, y: 1.5 }), Some(1));
+/// assert_eq!(index.classify(coord! { x: 0.6, y: 0.6 }), Some(0));
+/// assert_eq!(index.classify(coord! { x: 5., y: 5. }), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BandIndex {
+    entries: Vec<Entry>,
+    nodes: Vec<Node>,
+}
+
+impl BandIndex {
+    /// Constructs the index of the polygons of the given `bands`.
+    pub fn new(bands: &[Band]) -> Self {
+        let mut entries = bands
+            .iter()
+            .enumerate()
+            .flat_map(|(band, b)| {
+                b.geometry()
+                    .0
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| !p.exterior().0.is_empty())
+                    .map(move |(polygon, p)| Entry {
+                        band,
+                        polygon,
+                        geometry: p.clone(),
+                        bbox: Bbox::new(&p.exterior().0),
+                    })
+            })
+            .collect::<Vec<_>>();
+        let mut nodes = Vec::new();
+        if !entries.is_empty() {
+            let len = entries.len();
+            build(&mut entries, 0, len, &mut nodes);
+        }
+        BandIndex { entries, nodes }
+    }
+
+    /// Returns the index (in the slice of bands the index was built from) of the band
+    /// containing the given `point`, if any.
+    ///
+    /// A point on the boundary between two bands is classified in either of them.
+    pub fn classify(&self, point: Pt) -> Option<usize> {
+        self.classify_polygon(point).map(|(band, _)| band)
+    }
+
+    /// Returns the index of the band containing the given `point`, if any, and the index
+    /// of the polygon containing it in the geometry of this band.
+    pub fn classify_polygon(&self, point: Pt) -> Option<(usize, usize)> {
+        let target = Bbox {
+            min: point,
+            max: point,
+        };
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if !node.bbox.intersects(&target) {
+                continue;
+            }
+            if !node.leaf {
+                stack.extend([node.start, node.end]);
+                continue;
+            }
+            for entry in &self.entries[node.start..node.end] {
+                if entry.bbox.intersects(&target) && polygon_contains(&entry.geometry, point) {
+                    return Some((entry.band, entry.polygon));
+                }
+            }
+        }
+        None
+    }
+}
+
+// Returns whether the `point` is in the `polygon` (or on its boundary).
+fn polygon_contains(polygon: &Polygon<Float>, point: Pt) -> bool {
+    let point = std::slice::from_ref(&point);
+    contains(&polygon.exterior().0, point) >= 0
+        && polygon
+            .interiors()
+            .iter()
+            .all(|hole| contains(&hole.0, point) <= 0)
+}
+
+// Builds the node of the `entries` from `start` to `end` (splitting them along the
+// largest dimension of their bounding box), returning its index.
+fn build(entries: &mut [Entry], start: usize, end: usize, nodes: &mut Vec<Node>) -> usize {
+    let bbox = entries[start..end]
+        .iter()
+        .map(|e| e.bbox)
+        .reduce(|a, b| Bbox {
+            min: Pt {
+                x: a.min.x.min(b.min.x),
+                y: a.min.y.min(b.min.y),
+            },
+            max: Pt {
+                x: a.max.x.max(b.max.x),
+                y: a.max.y.max(b.max.y),
+            },
+        })
+        .unwrap();
+    let index = nodes.len();
+    nodes.push(Node {
+        bbox,
+        start,
+        end,
+        leaf: end - start <= LEAF_SIZE,
+    });
+    if end - start > LEAF_SIZE {
+        let horizontal = bbox.max.x - bbox.min.x >= bbox.max.y - bbox.min.y;
+        let center = |e: &Entry| {
+            if horizontal {
+                e.bbox.min.x + e.bbox.max.x
+            } else {
+                e.bbox.min.y + e.bbox.max.y
+            }
+        };
+        entries[start..end].sort_by(|a, b| center(a).total_cmp(&center(b)));
+        let middle = start + (end - start) / 2;
+        let left = build(entries, start, middle, nodes);
+        let right = build(entries, middle, end, nodes);
+        nodes[index].start = left;
+        nodes[index].end = right;
+    }
+    index
+}
@@ -0,0 +1,139 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::Float;
+use geo_types::Rect;
+
+/// A rectangular grid of values, stored row by row,
+/// that can be passed to a [`ContourBuilder`](crate::ContourBuilder)
+/// with the same dimensions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Buffer<T = Float> {
+    dx: usize,
+    dy: usize,
+    data: Vec<T>,
+}
+
+impl<T> Buffer<T> {
+    /// Constructs a new buffer for a grid with `dx` * `dy` dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The values of the grid, row by row.
+    /// * `dx` - The number of columns in the grid.
+    /// * `dy` - The number of rows in the grid.
+    pub fn new(data: Vec<T>, dx: usize, dy: usize) -> Result<Self> {
+        check_len(data.len(), dx, dy)?;
+        Ok(Buffer { dx, dy, data })
+    }
+
+    /// Returns the number of columns in the grid.
+    pub fn dx(&self) -> usize {
+        self.dx
+    }
+
+    /// Returns the number of rows in the grid.
+    pub fn dy(&self) -> usize {
+        self.dy
+    }
+
+    /// Borrow the values of the grid, row by row.
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Mutably borrow the values of the grid, row by row.
+    pub fn data_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Returns the value at column `x` and row `y`, if any.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.dx && y < self.dy {
+            self.data.get(y * self.dx + x)
+        } else {
+            None
+        }
+    }
+
+    /// Get the owned values of the grid.
+    pub fn into_inner(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Constructs a new buffer of the same dimension by applying `f` to each value.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Buffer<U> {
+        Buffer {
+            dx: self.dx,
+            dy: self.dy,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Buffer<Float> {
+    /// Constructs a new buffer from a JSON document of the form
+    /// `{"width": 3, "height": 2, "data": [0, 1, 0, 1, 2, 1]}`
+    /// (the values being stored row by row, as in the fixtures of this crate).
+    ///
+    /// Returns an error if the document can't be read or parsed,
+    /// or if its dimensions don't match the number of values.
+    ///
+    /// ```
+    /// use contour::Buffer;
+    ///
+    /// let json = r#"{"width": 3, "height": 2, "data": [0, 1, 0, 1, 2, 1]}"#;
+    /// let buffer = Buffer::from_json_reader(json.as_bytes()).unwrap();
+    /// assert_eq!(buffer.get(1, 1), Some(&2.));
+    /// ```
+    pub fn from_json_reader<R: std::io::Read>(reader: R) -> Result<Self> {
+        #[derive(serde::Deserialize)]
+        struct Grid {
+            width: usize,
+            height: usize,
+            data: Vec<Float>,
+        }
+
+        let grid: Grid = serde_json::from_reader(reader)?;
+        Buffer::new(grid.data, grid.width, grid.height)
+    }
+}
+
+impl<T: Clone> Buffer<T> {
+    /// Constructs a new buffer for a grid with `dx` * `dy` dimension,
+    /// filled with `value`.
+    ///
+    /// Returns an [`ErrorKind::Overflow`] error if the number of cells of the grid
+    /// exceeds the number of values addressable on the target.
+    pub fn filled(value: T, dx: usize, dy: usize) -> Result<Self> {
+        let size = grid_size(dx, dy)?;
+        Ok(Buffer {
+            dx,
+            dy,
+            data: vec![value; size],
+        })
+    }
+}
+
+// Checks that `len` values fill a grid with `dx` * `dy` dimension.
+pub(crate) fn check_len(len: usize, dx: usize, dy: usize) -> Result<()> {
+    if grid_size(dx, dy)? != len {
+        return Err(new_error(ErrorKind::BadDimension));
+    }
+    Ok(())
+}
+
+// Returns the number of cells of a grid with `dx` * `dy` dimension, without letting
+// it wrap around (which may happen on 32-bit targets).
+pub(crate) fn grid_size(dx: usize, dy: usize) -> Result<usize> {
+    dx.checked_mul(dy)
+        .ok_or_else(|| new_error(ErrorKind::Overflow))
+}
+
+// Returns the number of columns and rows of a grid covering `extent`
+// with square cells of `cell_size`.
+pub(crate) fn grid_dimensions(extent: &Rect<Float>, cell_size: Float) -> (usize, usize) {
+    (
+        (extent.width() / cell_size).ceil().max(1.) as usize,
+        (extent.height() / cell_size).ceil().max(1.) as usize,
+    )
+}
@@ -0,0 +1,111 @@
+use crate::ring::Orientation;
+use crate::{Float, Registration, SelfIntersections};
+use geo_types::Rect;
+use std::time::Duration;
+
+/// The configuration of a [`ContourBuilder`](crate::ContourBuilder), that a builder
+/// can be constructed from and can report back (see [`ContourBuilder::config`](crate::ContourBuilder::config)),
+/// making it possible to persist (with the `serde` feature), log or programmatically
+/// tweak configurations.
+///
+/// ```
+/// use contour::{ContourBuilder, ContourConfig};
+///
+/// let mut config = ContourConfig::new(10, 10, true);
+/// config.x_origin = 100.;
+/// config.x_step = 2.;
+/// let builder = ContourBuilder::from(config);
+/// assert_eq!(builder.config(), config);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct ContourConfig {
+    /// The number of columns in the grid
+    pub dx: usize,
+    /// The number of rows in the grid
+    pub dy: usize,
+    /// Whether to smooth the contours
+    pub smooth: bool,
+    /// The horizontal coordinate for the origin of the grid
+    pub x_origin: Float,
+    /// The vertical coordinate for the origin of the grid
+    pub y_origin: Float,
+    /// The horizontal step for the grid
+    pub x_step: Float,
+    /// The vertical step for the grid
+    pub y_step: Float,
+    /// Whether the values are the values of cells or of points
+    pub registration: Registration,
+    /// The number of subdivisions of each cell (using bilinear interpolation)
+    pub supersample: usize,
+    /// The maximum depth of the adaptive refinement of the cells crossed by the contours
+    pub refine: usize,
+    /// The number of iterations of the rounding of the corners of the contours
+    pub round_corners: usize,
+    /// Whether the rounding of the corners keeps the contours of different thresholds apart
+    pub non_crossing: bool,
+    /// Whether to assign the holes to the polygons enclosing them
+    pub nest_holes: bool,
+    /// The orientation of the holes of the polygons (as computed if `None`)
+    pub hole_orientation: Option<Orientation>,
+    /// Whether to compute the gradient of the values at each vertex of the isolines
+    pub gradients: bool,
+    /// The maximum length of the output segments (`0` to disable the densification)
+    pub densify: Float,
+    /// The size of the grid the output vertices are snapped to (`0` to disable the snapping)
+    pub snap: Float,
+    /// The extent and the resolution of the integer coordinates of the output vertices
+    pub quantize: Option<(Rect<Float>, u32)>,
+    /// The maximum number of vertices of the output rings and linestrings (`0` for no maximum)
+    pub max_vertices: usize,
+    /// The envelope outside of which the rings are discarded
+    pub envelope: Option<Rect<Float>>,
+    /// How the self-intersecting rings are handled
+    pub self_intersections: SelfIntersections,
+    /// The maximum number of rings computed for a threshold (`0` for no maximum)
+    pub max_rings: usize,
+    /// The maximum number of vertices of all the rings computed (`0` for no maximum)
+    pub max_total_vertices: usize,
+    /// The maximum duration of a computation
+    pub timeout: Option<Duration>,
+}
+
+impl ContourConfig {
+    /// Constructs the default configuration for a grid with `dx` * `dy` dimension
+    /// (the same as [`ContourBuilder::new`](crate::ContourBuilder::new)).
+    ///
+    /// # Arguments
+    ///
+    /// * `dx` - The number of columns in the grid.
+    /// * `dy` - The number of rows in the grid.
+    /// * `smooth` - Whether or not the generated rings will be smoothed using linear interpolation.
+    pub fn new(dx: usize, dy: usize, smooth: bool) -> Self {
+        ContourConfig {
+            dx,
+            dy,
+            smooth,
+            x_origin: 0.,
+            y_origin: 0.,
+            x_step: 1.,
+            y_step: 1.,
+            registration: Registration::Area,
+            supersample: 1,
+            refine: 0,
+            round_corners: 0,
+            non_crossing: false,
+            nest_holes: true,
+            hole_orientation: None,
+            gradients: false,
+            densify: 0.,
+            snap: 0.,
+            quantize: None,
+            max_vertices: 0,
+            envelope: None,
+            self_intersections: SelfIntersections::Ignore,
+            max_rings: 0,
+            max_total_vertices: 0,
+            timeout: None,
+        }
+    }
+}
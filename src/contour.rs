@@ -1,14 +1,28 @@
-use crate::Float;
-use geo_types::MultiPolygon;
+use crate::area::{polygons_bbox, polygons_interior_points};
+use crate::{Float, Pt};
+use geo_types::{MultiPolygon, Rect};
 
 /// A contour has the geometry and threshold of a contour ring, built by [ContourBuilder](`crate::contourbuilder::ContourBuilder`).
 #[derive(Debug, Clone)]
 pub struct Contour {
     pub(crate) geometry: MultiPolygon<Float>,
     pub(crate) threshold: Float,
+    pub(crate) bbox: Option<Rect<Float>>,
+    pub(crate) interior_points: Vec<Option<Pt>>,
 }
 
 impl Contour {
+    pub(crate) fn new(geometry: MultiPolygon<Float>, threshold: Float) -> Self {
+        let bbox = polygons_bbox(&geometry);
+        let interior_points = polygons_interior_points(&geometry);
+        Contour {
+            geometry,
+            threshold,
+            bbox,
+            interior_points,
+        }
+    }
+
     /// Borrow the [`MultiPolygon`](geo_types::MultiPolygon) geometry of this contour.
     pub fn geometry(&self) -> &MultiPolygon<Float> {
         &self.geometry
@@ -24,6 +38,34 @@ impl Contour {
         self.threshold
     }
 
+    /// Get the bounding box of this contour (`None` if it has no polygon),
+    /// computed along with its geometry.
+    pub fn bbox(&self) -> Option<Rect<Float>> {
+        self.bbox
+    }
+
+    /// Get a point strictly inside each polygon of this contour (`None` for a degenerate
+    /// polygon), such as to place a label or to test the containment of the polygon
+    /// in another one, computed along with its geometry.
+    pub fn interior_points(&self) -> &[Option<Pt>] {
+        &self.interior_points
+    }
+
+    /// Tessellates the polygons of this contour into a triangle [`Mesh`](crate::Mesh)
+    /// (requires the `mesh` feature).
+    #[cfg(feature = "mesh")]
+    pub fn to_mesh(&self) -> crate::Result<crate::Mesh> {
+        crate::Mesh::from_polygons(&self.geometry)
+    }
+
+    /// Converts the polygons of this contour to a [`lyon_path::Path`] of a closed subpath per ring
+    /// (requires the `lyon` feature), to be filled with the even-odd rule
+    /// (`FillRule::EvenOdd`) so that the holes are left empty.
+    #[cfg(feature = "lyon")]
+    pub fn to_path(&self) -> lyon_path::Path {
+        crate::path::polygons_to_path(&self.geometry)
+    }
+
     #[cfg(feature = "geojson")]
     /// Convert the contour to a struct from the `geojson` crate.
     ///
@@ -64,3 +106,9 @@ impl Contour {
         }
     }
 }
+
+impl From<Contour> for MultiPolygon<Float> {
+    fn from(contour: Contour) -> Self {
+        contour.geometry
+    }
+}
@@ -1,9 +1,41 @@
-use crate::area::{area, contains};
+use crate::area::{
+    area, assign_holes, contains, interior_point, lines_bbox, polygons_bbox,
+    polygons_interior_points, Bbox,
+};
+use crate::buffer::check_len;
+use crate::error::Error;
 use crate::error::{new_error, ErrorKind, Result};
-use crate::isoringbuilder::IsoRingBuilder;
-use crate::{Band, Contour, Float, Line, Ring};
-use geo_types::{LineString, MultiLineString, MultiPolygon, Polygon};
-use rustc_hash::FxHashMap;
+use crate::isoringbuilder::{cell_case, contour_segments, IsoRingBuilder};
+use crate::polygonize::{polygonize, trace};
+use crate::ring::{self, Orientation};
+use crate::split::{split_lines, split_polygons};
+use crate::validation::{self_intersection, validate_bands};
+use crate::{
+    Band, BandIssue, Buffer, Change, Contour, ContourConfig, Float, Gradient, Line, Pt, Ring,
+    RingStream, Segment, SelfIntersections, ThresholdGenerator, ZonalStatistics,
+};
+use geo_types::{LineString, MultiLineString, MultiPolygon, Polygon, Rect};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "rayon")]
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How the values of the grid are registered to its coordinates
+/// (see [`ContourBuilder::registration`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Registration {
+    /// Each value is the value of a cell (pixel-is-area): the first value is at the center
+    /// of the first cell, whose corner is at the origin of the grid, and the contours
+    /// extend to the outer edge of the cells of the border.
+    #[default]
+    Area,
+    /// Each value is the value at a point (pixel-is-point): the first value is at the origin
+    /// of the grid, and the contours stop at the outermost values.
+    Point,
+}
 
 /// Contours generator, using builder pattern, to
 /// be used on a rectangular `Slice` of values to
@@ -25,6 +57,76 @@ pub struct ContourBuilder {
     x_step: Float,
     /// The vertical step for the grid
     y_step: Float,
+    /// Whether the values are the values of cells or of points
+    registration: Registration,
+    /// The number of subdivisions of each cell (using bilinear interpolation)
+    supersample: usize,
+    /// The maximum depth of the adaptive refinement of the cells crossed by the contours
+    refine: usize,
+    /// The number of iterations of the rounding of the corners of the contours
+    round_corners: usize,
+    /// Whether the rounding of the corners keeps the contours of different thresholds apart
+    non_crossing: bool,
+    /// Whether to assign the holes to the polygons enclosing them
+    nest_holes: bool,
+    /// The orientation of the holes of the polygons (as computed if `None`)
+    hole_orientation: Option<Orientation>,
+    /// Whether to compute the gradient of the values at each vertex of the isolines
+    gradients: bool,
+    /// The maximum length of the output segments (`0` to disable the densification)
+    densify: Float,
+    /// The size of the grid the output vertices are snapped to (`0` to disable the snapping)
+    snap: Float,
+    /// The extent and the resolution of the integer coordinates of the output vertices
+    quantize: Option<(Rect<Float>, u32)>,
+    /// The maximum number of vertices of the output rings and linestrings (`0` for no maximum)
+    max_vertices: usize,
+    /// The envelope outside of which the rings are discarded
+    envelope: Option<Rect<Float>>,
+    /// How the self-intersecting rings are handled
+    self_intersections: SelfIntersections,
+    /// The maximum number of rings computed for a threshold (`0` for no maximum)
+    max_rings: usize,
+    /// The maximum number of vertices of all the rings computed (`0` for no maximum)
+    max_total_vertices: usize,
+    /// The maximum duration of a computation
+    timeout: Option<Duration>,
+    /// The thread pool the thresholds are computed in (the global one if `None`)
+    #[cfg(feature = "rayon")]
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+}
+
+impl From<ContourConfig> for ContourBuilder {
+    fn from(config: ContourConfig) -> Self {
+        ContourBuilder {
+            dx: config.dx,
+            dy: config.dy,
+            smooth: config.smooth,
+            x_origin: config.x_origin,
+            y_origin: config.y_origin,
+            x_step: config.x_step,
+            y_step: config.y_step,
+            registration: config.registration,
+            supersample: config.supersample.max(1),
+            refine: config.refine,
+            round_corners: config.round_corners,
+            non_crossing: config.non_crossing,
+            nest_holes: config.nest_holes,
+            hole_orientation: config.hole_orientation,
+            gradients: config.gradients,
+            densify: config.densify,
+            snap: config.snap,
+            quantize: config.quantize,
+            max_vertices: config.max_vertices,
+            envelope: config.envelope,
+            self_intersections: config.self_intersections,
+            max_rings: config.max_rings,
+            max_total_vertices: config.max_total_vertices,
+            timeout: config.timeout,
+            #[cfg(feature = "rayon")]
+            thread_pool: None,
+        }
+    }
 }
 
 impl ContourBuilder {
@@ -39,14 +141,36 @@ impl ContourBuilder {
     /// * `dy` - The number of rows in the grid.
     /// * `smooth` - Whether or not the generated rings will be smoothed using linear interpolation.
     pub fn new(dx: usize, dy: usize, smooth: bool) -> Self {
-        ContourBuilder {
-            dx,
-            dy,
-            smooth,
-            x_origin: 0.,
-            y_origin: 0.,
-            x_step: 1.,
-            y_step: 1.,
+        ContourConfig::new(dx, dy, smooth).into()
+    }
+
+    /// Returns the configuration of this builder.
+    pub fn config(&self) -> ContourConfig {
+        ContourConfig {
+            dx: self.dx,
+            dy: self.dy,
+            smooth: self.smooth,
+            x_origin: self.x_origin,
+            y_origin: self.y_origin,
+            x_step: self.x_step,
+            y_step: self.y_step,
+            registration: self.registration,
+            supersample: self.supersample,
+            refine: self.refine,
+            round_corners: self.round_corners,
+            non_crossing: self.non_crossing,
+            nest_holes: self.nest_holes,
+            hole_orientation: self.hole_orientation,
+            gradients: self.gradients,
+            densify: self.densify,
+            snap: self.snap,
+            quantize: self.quantize,
+            max_vertices: self.max_vertices,
+            envelope: self.envelope,
+            self_intersections: self.self_intersections,
+            max_rings: self.max_rings,
+            max_total_vertices: self.max_total_vertices,
+            timeout: self.timeout,
         }
     }
 
@@ -74,6 +198,984 @@ impl ContourBuilder {
         self
     }
 
+    /// Sets whether the values are the values of cells ([`Registration::Area`], the default,
+    /// as for most images) or of points ([`Registration::Point`], as for most elevation models
+    /// and model outputs), matching the convention of the other tools handling the same grid.
+    ///
+    /// With the point registration, the output coordinates are moved by half a cell towards the
+    /// origin, and the contours are clamped to the extent of the values instead of extending
+    /// half a cell beyond them.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, Registration};
+    ///
+    /// let values = [1., 1., 1., 1., 1., 1., 1., 1., 1.];
+    /// let builder = ContourBuilder::new(3, 3, false).x_step(10.).y_step(10.);
+    /// let area = builder.contours(&values, &[0.5]).unwrap();
+    /// let bbox = area[0].bbox().unwrap();
+    /// assert_eq!((bbox.min().x, bbox.max().x), (0., 30.));
+    /// let builder = builder.registration(Registration::Point);
+    /// let point = builder.contours(&values, &[0.5]).unwrap();
+    /// let bbox = point[0].bbox().unwrap();
+    /// assert_eq!((bbox.min().x, bbox.max().x), (0., 20.));
+    /// ```
+    pub fn registration(mut self, registration: Registration) -> Self {
+        self.registration = registration;
+        self
+    }
+
+    /// Sets the origin and the steps of the grid so that it covers the given extent (in world
+    /// coordinates), according to the registration set so far (see
+    /// [`registration`](Self::registration)): the values are the values of cells covering the
+    /// extent, or the values at points spanning it from border to border.
+    ///
+    /// The first row of the grid is the top one (at `max_y`, the y step being negative),
+    /// as in most raster formats; swapping `min_y` and `max_y` puts it at the bottom.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, Registration};
+    ///
+    /// let builder = ContourBuilder::new(4, 2, false).extent(100., 10., 140., 30.);
+    /// let config = builder.config();
+    /// assert_eq!((config.x_origin, config.y_origin), (100., 30.));
+    /// assert_eq!((config.x_step, config.y_step), (10., -10.));
+    ///
+    /// let builder = ContourBuilder::new(5, 3, false)
+    ///     .registration(Registration::Point)
+    ///     .extent(100., 10., 140., 30.);
+    /// let config = builder.config();
+    /// assert_eq!((config.x_step, config.y_step), (10., -10.));
+    /// ```
+    pub fn extent(
+        mut self,
+        min_x: impl Into<Float>,
+        min_y: impl Into<Float>,
+        max_x: impl Into<Float>,
+        max_y: impl Into<Float>,
+    ) -> Self {
+        let (min_x, min_y) = (min_x.into(), min_y.into());
+        let (max_x, max_y) = (max_x.into(), max_y.into());
+        let (nx, ny) = match self.registration {
+            Registration::Area => (self.dx, self.dy),
+            Registration::Point => (self.dx.saturating_sub(1), self.dy.saturating_sub(1)),
+        };
+        self.x_origin = min_x;
+        self.y_origin = max_y;
+        self.x_step = (max_x - min_x) / nx.max(1) as Float;
+        self.y_step = (min_y - max_y) / ny.max(1) as Float;
+        self
+    }
+
+    // Returns the world coordinates of the point of the grid at column 0 and row 0
+    // (the corner of the first cell, half a cell before the first value).
+    fn origin(&self) -> Pt {
+        match self.registration {
+            Registration::Area => Pt {
+                x: self.x_origin,
+                y: self.y_origin,
+            },
+            Registration::Point => Pt {
+                x: self.x_origin - 0.5 * self.x_step,
+                y: self.y_origin - 0.5 * self.y_step,
+            },
+        }
+    }
+
+    // Returns whether the coordinates of the grid have to be transformed to world coordinates.
+    fn is_transformed(&self) -> bool {
+        (self.origin().x, self.origin().y) != (0.0, 0.0) || (self.x_step, self.y_step) != (1.0, 1.0)
+    }
+
+    // Maps a point from the coordinates of the grid to world coordinates.
+    fn to_world(&self, point: &mut Pt) {
+        let origin = self.origin();
+        point.x = point.x * self.x_step + origin.x;
+        point.y = point.y * self.y_step + origin.y;
+    }
+
+    // Maps a point from world coordinates to the coordinates of the grid.
+    fn to_grid(&self, point: Pt) -> Pt {
+        let origin = self.origin();
+        Pt {
+            x: (point.x - origin.x) / self.x_step,
+            y: (point.y - origin.y) / self.y_step,
+        }
+    }
+
+    // Maps the vertices of the ring (or linestring) to world coordinates if needed,
+    // clamping them to the extent of the values with the point registration.
+    fn ring_to_world(&self, ring: &mut Ring) {
+        if self.registration == Registration::Point {
+            let (dx, dy) = (self.dx as Float, self.dy as Float);
+            ring.iter_mut().for_each(|point| {
+                point.x = point.x.clamp(0.5, (dx - 0.5).max(0.5));
+                point.y = point.y.clamp(0.5, (dy - 0.5).max(0.5));
+            });
+            ring.dedup();
+        }
+        if self.is_transformed() {
+            ring.iter_mut().for_each(|point| self.to_world(point));
+        }
+    }
+
+    /// Returns a copy of this builder with the smoothing overridden by `smooth`, so that the
+    /// same configured builder can serve, for instance, both a fast non-smoothed preview
+    /// and a final smoothed rendering.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, true).x_step(10.).y_step(10.);
+    /// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+    /// let preview = builder.with_smoothing(false).contours(&values, &[0.25]).unwrap();
+    /// let rendering = builder.contours(&values, &[0.25]).unwrap();
+    /// assert_ne!(preview[0].geometry(), rendering[0].geometry());
+    /// ```
+    pub fn with_smoothing(&self, smooth: bool) -> Self {
+        ContourBuilder {
+            smooth,
+            #[cfg(feature = "rayon")]
+            thread_pool: self.thread_pool.clone(),
+            ..*self
+        }
+    }
+
+    /// Sets the number of subdivisions of each cell (in both directions) used to compute
+    /// the contours: the values are virtually resampled on a `n` times finer grid, using
+    /// bilinear interpolation of the values of the corners of each cell, which produces
+    /// smoother and more accurate contours on coarse grids.
+    ///
+    /// The contours of a supersampled empty grid (whose `dx` or `dy` is `0`) can't be computed:
+    /// an [`ErrorKind::BadDimension`] error is returned.
+    ///
+    /// By default, `n` is `1` (the cells are not subdivided).
+    pub fn supersample(mut self, n: usize) -> Self {
+        self.supersample = n.max(1);
+        self
+    }
+
+    /// Sets whether the holes are assigned to the polygons enclosing them (the default).
+    ///
+    /// When set to `false`, this costly step is skipped and every ring is returned as its own
+    /// polygon by [`contours`](Self::contours) and [`isobands`](Self::isobands): exterior rings
+    /// are oriented clockwise and holes counterclockwise (as expected by renderers
+    /// using the even-odd fill rule, the orientation allowing to tell them apart).
+    pub fn nest_holes(mut self, nest_holes: bool) -> Self {
+        self.nest_holes = nest_holes;
+        self
+    }
+
+    /// Sets the `orientation` of the holes (the interior rings) of the polygons of
+    /// [`contours`](Self::contours) and [`isobands`](Self::isobands), independently of their
+    /// exterior rings (which are oriented clockwise), for the consumers expecting the holes to be
+    /// oriented like the exterior rings (such as some tessellators or the ESRI formats).
+    ///
+    /// By default, the holes of the contours are oriented counterclockwise, while the holes of
+    /// the isobands keep the orientation of the isolines they come from (counterclockwise for the
+    /// holes of the lower threshold, clockwise for the exterior rings of the upper threshold).
+    /// The orientations are given for axes whose y axis points up (see [`ring`](crate::ring)).
+    ///
+    /// ```
+    /// use contour::ring::{self, Orientation};
+    /// use contour::ContourBuilder;
+    ///
+    /// let values = [1., 1., 1., 1., 0., 1., 1., 1., 1.];
+    /// let builder = ContourBuilder::new(3, 3, false).hole_orientation(Orientation::Clockwise);
+    /// let contours = builder.contours(&values, &[0.5]).unwrap();
+    /// let polygon = &contours[0].geometry().0[0];
+    /// assert_eq!(ring::orientation(&polygon.exterior().0), Some(Orientation::Clockwise));
+    /// assert_eq!(ring::orientation(&polygon.interiors()[0].0), Some(Orientation::Clockwise));
+    /// ```
+    pub fn hole_orientation(mut self, orientation: Orientation) -> Self {
+        self.hole_orientation = Some(orientation);
+        self
+    }
+
+    /// Sets whether the gradient of the values is computed at each vertex of the isolines
+    /// returned by [`lines`](Self::lines) (see [`Line::gradients`]), from the bilinear
+    /// interpolation of the values of the corners of the cell containing the vertex.
+    ///
+    /// By default, the gradients are not computed.
+    pub fn gradients(mut self, gradients: bool) -> Self {
+        self.gradients = gradients;
+        self
+    }
+
+    /// Sets the size of the grid the output vertices of [`lines`](Self::lines),
+    /// [`contours`](Self::contours) and [`isobands`](Self::isobands) are snapped to
+    /// (such as `1e-6` world units), so that the nearly coincident boundaries of adjacent
+    /// contours or bands become exactly coincident and don't produce slivers when unioned.
+    ///
+    /// The consecutive vertices snapped to the same location are merged, and the rings
+    /// collapsing to less than three distinct vertices are dropped.
+    /// By default, `grid` is `0` (the vertices are not snapped).
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, true).x_step(0.1).snap(0.25);
+    /// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+    /// let contours = builder.contours(&values, &[0.3]).unwrap();
+    /// let exterior = contours[0].geometry().0[0].exterior();
+    /// assert!(exterior.0.iter().all(|p| (p.x * 4.).fract() == 0.));
+    /// ```
+    pub fn snap(mut self, grid: impl Into<Float>) -> Self {
+        self.snap = grid.into();
+        self
+    }
+
+    /// Sets the `extent` (in world coordinates) and the `resolution` of the integer coordinates
+    /// the output vertices of [`lines`](Self::lines), [`contours`](Self::contours) and
+    /// [`isobands`](Self::isobands) are quantized to (such as `4096` for vector tiles):
+    /// the minimum corner of the extent is mapped to `(0, 0)` and its maximum corner to
+    /// `(resolution, resolution)`, the coordinates being rounded to the nearest integer.
+    ///
+    /// The quantization is performed along with the finalization of the rings, which avoids a
+    /// second pass over all the vertices, and the coordinates can be converted exactly to integers.
+    /// The consecutive vertices quantized to the same location are merged, and the rings
+    /// collapsing to less than three distinct vertices are dropped.
+    /// By default, the vertices are not quantized.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    /// use geo_types::{coord, Rect};
+    ///
+    /// let extent = Rect::new(coord! { x: 100., y: 200. }, coord! { x: 130., y: 230. });
+    /// let builder = ContourBuilder::new(3, 3, true)
+    ///     .x_origin(100.)
+    ///     .y_origin(200.)
+    ///     .x_step(10.)
+    ///     .y_step(10.)
+    ///     .quantize(extent, 4096);
+    /// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+    /// let contours = builder.contours(&values, &[0.3]).unwrap();
+    /// let exterior = contours[0].geometry().0[0].exterior();
+    /// let integers = exterior.0.iter().map(|p| (p.x as i32, p.y as i32)).collect::<Vec<_>>();
+    /// assert!(integers.iter().all(|p| (0..=4096).contains(&p.0)));
+    /// assert!(exterior.0.iter().all(|p| p.x.fract() == 0. && p.y.fract() == 0.));
+    /// ```
+    pub fn quantize(mut self, extent: Rect<Float>, resolution: u32) -> Self {
+        self.quantize = Some((extent, resolution));
+        self
+    }
+
+    /// Sets the maximum length (in world coordinates) of the segments of the output rings and
+    /// linestrings of [`lines`](Self::lines), [`contours`](Self::contours) and
+    /// [`isobands`](Self::isobands): the longer segments are divided in equal parts by
+    /// interpolated vertices, so that the vertices are roughly evenly spaced along the isolines
+    /// (such as to place labels or to animate markers along them).
+    ///
+    /// The densification is performed before the snapping (see [`snap`](Self::snap))
+    /// and the quantization (see [`quantize`](Self::quantize)) of the vertices.
+    /// By default, `max_length` is `0` (the segments are not divided).
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(4, 4, false).x_step(10.).y_step(10.).densify(2.);
+    /// let values = [0., 0., 0., 0., 0., 1., 1., 0., 0., 1., 1., 0., 0., 0., 0., 0.];
+    /// let lines = builder.lines(&values, &[0.5]).unwrap();
+    /// let linestring = &lines[0].geometry().0[0];
+    /// assert!(linestring.lines().all(|segment| {
+    ///     let d = segment.delta();
+    ///     (d.x * d.x + d.y * d.y).sqrt() <= 2. + 1e-9
+    /// }));
+    /// ```
+    pub fn densify(mut self, max_length: impl Into<Float>) -> Self {
+        self.densify = max_length.into();
+        self
+    }
+
+    // Densifies, snaps or quantizes the vertices of the ring if needed, merging the consecutive
+    // duplicated ones.
+    fn snap_ring(&self, ring: &mut Ring) {
+        if self.densify > 0. && ring.len() > 1 {
+            let max_length = self.densify;
+            let mut densified = Vec::with_capacity(ring.len());
+            for segment in ring.windows(2) {
+                let (a, b) = (segment[0], segment[1]);
+                let length = ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt();
+                let n = (length / max_length).ceil().max(1.) as usize;
+                densified.extend((0..n).map(|k| {
+                    let t = k as Float / n as Float;
+                    Pt {
+                        x: a.x + (b.x - a.x) * t,
+                        y: a.y + (b.y - a.y) * t,
+                    }
+                }));
+            }
+            densified.push(ring[ring.len() - 1]);
+            *ring = densified;
+        }
+        if self.snap > 0. {
+            let grid = self.snap;
+            ring.iter_mut().for_each(|point| {
+                point.x = (point.x / grid).round() * grid;
+                point.y = (point.y / grid).round() * grid;
+            });
+            ring.dedup();
+        }
+        if let Some((extent, resolution)) = self.quantize {
+            let (min, resolution) = (extent.min(), resolution as Float);
+            let (sx, sy) = (resolution / extent.width(), resolution / extent.height());
+            ring.iter_mut().for_each(|point| {
+                point.x = ((point.x - min.x) * sx).round();
+                point.y = ((point.y - min.y) * sy).round();
+            });
+            ring.dedup();
+        }
+    }
+
+    /// Sets the maximum number of vertices of the rings of [`contours`](Self::contours) and
+    /// [`isobands`](Self::isobands) and of the linestrings of [`lines`](Self::lines), for the
+    /// consumers that can't handle very long rings (such as some shapefile tools or GPU buffers).
+    ///
+    /// The polygons having longer rings are split into pieces (closed along the splitting lines)
+    /// by recursively cutting them in two halves; the pieces that can't be shortened further
+    /// (which only happens for caps lower than a few vertices) are kept as is.
+    /// The longer linestrings are split into successive parts sharing their end vertices.
+    ///
+    /// By default, `max_vertices` is `0` (the rings and linestrings are not split).
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let values = [0., 0., 0., 0., 0., 1., 1., 0., 0., 1., 1., 0., 0., 0., 0., 0.];
+    /// let builder = ContourBuilder::new(4, 4, false).max_vertices(8);
+    /// let contours = builder.contours(&values, &[0.5]).unwrap();
+    /// assert_eq!(contours[0].geometry().0.len(), 2);
+    /// assert!(contours[0].geometry().iter().all(|p| p.exterior().0.len() <= 8));
+    /// ```
+    pub fn max_vertices(mut self, max_vertices: usize) -> Self {
+        self.max_vertices = max_vertices;
+        self
+    }
+
+    // Splits the polygons having too long rings and orients their holes if needed.
+    fn finish_polygons(&self, geometry: &mut MultiPolygon<Float>) {
+        if self.max_vertices > 0 {
+            let polygons = std::mem::replace(geometry, MultiPolygon(vec![]));
+            *geometry = split_polygons(polygons, self.max_vertices);
+        }
+        if let Some(orientation) = self.hole_orientation {
+            geometry.iter_mut().for_each(|polygon| {
+                polygon.interiors_mut(|interiors| {
+                    interiors
+                        .iter_mut()
+                        .filter(|hole| ring::orientation(&hole.0) != Some(orientation))
+                        .for_each(|hole| hole.0.reverse());
+                });
+            });
+        }
+    }
+
+    // Splits the too long linestrings if needed.
+    fn split_lines(&self, geometry: &mut MultiLineString<Float>) {
+        if self.max_vertices > 0 {
+            let lines = std::mem::replace(geometry, MultiLineString(vec![]));
+            *geometry = split_lines(lines, self.max_vertices);
+        }
+    }
+
+    /// Sets the `envelope` (in the coordinates of the output) outside of which the rings of
+    /// [`lines`](Self::lines), [`contours`](Self::contours) and [`isobands`](Self::isobands)
+    /// are discarded, before their smoothing and their assembly into polygons, so that
+    /// the geometries of a tile can be computed without building the ones of the whole grid.
+    ///
+    /// The rings are discarded when their bounding box (before smoothing) doesn't intersect
+    /// the envelope expanded by one cell, so the kept rings can lie slightly outside of it
+    /// (they are not clipped). By default, no ring is discarded.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    /// use geo_types::{coord, Rect};
+    ///
+    /// let values = [1., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1.];
+    /// let envelope = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 1., y: 1. });
+    /// let builder = ContourBuilder::new(4, 4, true).envelope(envelope);
+    /// let contours = builder.contours(&values, &[0.5]).unwrap();
+    /// assert_eq!(contours[0].geometry().0.len(), 1);
+    /// ```
+    pub fn envelope(mut self, envelope: Rect<Float>) -> Self {
+        self.envelope = Some(envelope);
+        self
+    }
+
+    // Returns the envelope in the coordinates of the grid, expanded by one cell.
+    fn grid_envelope(&self) -> Option<Bbox> {
+        let envelope = self.envelope?;
+        let (a, b) = (self.to_grid(envelope.min()), self.to_grid(envelope.max()));
+        Some(Bbox {
+            min: Pt {
+                x: a.x.min(b.x) - 1.,
+                y: a.y.min(b.y) - 1.,
+            },
+            max: Pt {
+                x: a.x.max(b.x) + 1.,
+                y: a.y.max(b.y) + 1.,
+            },
+        })
+    }
+
+    /// Sets how the rings (of [`lines`](Self::lines), [`contours`](Self::contours) and
+    /// [`isobands`](Self::isobands)) that self-intersect after being smoothed, refined or rounded
+    /// (which can occasionally happen on features one cell wide) are handled:
+    /// they can be repaired (by skipping their smoothing), dropped or reported as an
+    /// [`ErrorKind::SelfIntersection`] error giving the coordinates of the intersection.
+    ///
+    /// By default, the rings are not checked ([`SelfIntersections::Ignore`]).
+    pub fn self_intersections(mut self, policy: SelfIntersections) -> Self {
+        self.self_intersections = policy;
+        self
+    }
+
+    /// Sets the maximum number of rings computed for a threshold by [`lines`](Self::lines),
+    /// [`contours`](Self::contours) and [`isobands`](Self::isobands), beyond which they return
+    /// an [`ErrorKind::TooManyRings`] error (before smoothing the rings and assembling them),
+    /// protecting services from pathological inputs (such as noisy grids with thresholds
+    /// through the noise) rather than exhausting their memory.
+    ///
+    /// By default, `max_rings` is `0` (no maximum).
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, ErrorKind};
+    ///
+    /// let values = [1., 0., 1., 0., 0., 0., 1., 0., 1.];
+    /// let builder = ContourBuilder::new(3, 3, true).max_rings(3);
+    /// let err = builder.contours(&values, &[0.5]).unwrap_err();
+    /// assert!(matches!(err.kind(), ErrorKind::TooManyRings { .. }));
+    /// ```
+    pub fn max_rings(mut self, max_rings: usize) -> Self {
+        self.max_rings = max_rings;
+        self
+    }
+
+    /// Sets the maximum number of vertices of all the rings computed (for all the thresholds)
+    /// by [`lines`](Self::lines), [`contours`](Self::contours) and [`isobands`](Self::isobands),
+    /// beyond which they return an [`ErrorKind::TooManyVertices`] error.
+    /// The vertices are counted before the smoothing and the refinement of the rings.
+    ///
+    /// By default, `max_total_vertices` is `0` (no maximum).
+    pub fn max_total_vertices(mut self, max_total_vertices: usize) -> Self {
+        self.max_total_vertices = max_total_vertices;
+        self
+    }
+
+    /// Sets the maximum duration of the computations of [`lines`](Self::lines),
+    /// [`contours`](Self::contours) and [`isobands`](Self::isobands), after which they return
+    /// an [`ErrorKind::Timeout`] error, so that interactive applications can degrade gracefully
+    /// on unexpectedly heavy inputs. The deadline is checked before each threshold
+    /// and each row of the grid, so the computation can slightly exceed it.
+    ///
+    /// By default, there is no timeout.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, ErrorKind};
+    /// use std::time::Duration;
+    ///
+    /// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+    /// let builder = ContourBuilder::new(3, 3, true).timeout(Duration::ZERO);
+    /// let err = builder.contours(&values, &[0.5]).unwrap_err();
+    /// assert!(matches!(err.kind(), ErrorKind::Timeout));
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `thread_pool` the thresholds of [`lines`](Self::lines),
+    /// [`contours`](Self::contours) and [`isobands`](Self::isobands) are computed in
+    /// (requires the `rayon` feature), so that the applications embedding this crate
+    /// can control its CPU usage (for instance by limiting the number of threads of the pool).
+    ///
+    /// By default, the thresholds are computed in the global thread pool of `rayon`.
+    /// The thread pool is not part of the [`ContourConfig`].
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    /// use std::sync::Arc;
+    ///
+    /// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    /// let builder = ContourBuilder::new(3, 3, true).thread_pool(Arc::new(pool));
+    /// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+    /// let contours = builder.contours(&values, &[0.25, 0.5, 0.75]).unwrap();
+    /// assert_eq!(contours.len(), 3);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn thread_pool(mut self, thread_pool: Arc<rayon::ThreadPool>) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
+    // Returns the deadline of a computation starting now.
+    fn deadline(&self) -> Option<Instant> {
+        self.timeout.map(|timeout| Instant::now() + timeout)
+    }
+
+    // Returns a ring generator aborting the computation at the given deadline (if any).
+    fn isoring(&self, deadline: Option<Instant>) -> IsoRingBuilder {
+        let mut isoring = IsoRingBuilder::new(self.dx, self.dy);
+        isoring.deadline = deadline;
+        isoring
+    }
+
+    // Maps each threshold with `f`, given a ring generator of its thread
+    // (the thresholds being computed in parallel with the `rayon` feature).
+    #[cfg(feature = "rayon")]
+    fn map_thresholds<T, F>(&self, thresholds: &[Float], f: F) -> Result<Vec<T>>
+    where
+        T: Send,
+        F: Fn(&mut IsoRingBuilder, Float) -> Result<T> + Sync + Send,
+    {
+        let deadline = self.deadline();
+        let map = || {
+            thresholds
+                .par_iter()
+                .map_init(|| self.isoring(deadline), |isoring, t| f(isoring, *t))
+                .collect()
+        };
+        match &self.thread_pool {
+            Some(pool) => pool.install(map),
+            None => map(),
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn map_thresholds<T, F>(&self, thresholds: &[Float], f: F) -> Result<Vec<T>>
+    where
+        F: Fn(&mut IsoRingBuilder, Float) -> Result<T>,
+    {
+        let mut isoring = self.isoring(self.deadline());
+        thresholds.iter().map(|t| f(&mut isoring, *t)).collect()
+    }
+
+    // Counts the rings enclosing each of the given rings (sorted by ascending area),
+    // the rings being processed in parallel with the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    fn nesting_depths(&self, rings: &[RingSummary]) -> Vec<usize> {
+        let depths = || {
+            (0..rings.len())
+                .into_par_iter()
+                .map(|i| nesting_depth(rings, i))
+                .collect()
+        };
+        match &self.thread_pool {
+            Some(pool) => pool.install(depths),
+            None => depths(),
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn nesting_depths(&self, rings: &[RingSummary]) -> Vec<usize> {
+        (0..rings.len()).map(|i| nesting_depth(rings, i)).collect()
+    }
+
+    // Computes the rings of the given threshold, checking them against the complexity limits
+    // (`vertices` being the number of vertices of the rings computed so far).
+    fn compute_rings(
+        &self,
+        isoring: &mut IsoRingBuilder,
+        values: &[Float],
+        threshold: Float,
+        vertices: &AtomicUsize,
+    ) -> Result<Vec<Ring>> {
+        let rings = isoring.compute(values, threshold)?;
+        if self.max_rings > 0 && rings.len() > self.max_rings {
+            return Err(new_error(ErrorKind::TooManyRings {
+                threshold,
+                max: self.max_rings,
+            }));
+        }
+        let count = rings.iter().map(Vec::len).sum::<usize>();
+        let total = vertices.fetch_add(count, Ordering::Relaxed) + count;
+        if self.max_total_vertices > 0 && total > self.max_total_vertices {
+            return Err(new_error(ErrorKind::TooManyVertices {
+                max: self.max_total_vertices,
+            }));
+        }
+        Ok(rings)
+    }
+
+    // Returns a copy of the ring before its smoothing if it has to be repaired.
+    fn unsmoothed(&self, ring: &Ring) -> Option<Ring> {
+        (self.self_intersections == SelfIntersections::Repair
+            && (self.smooth || self.refine > 0 || self.round_corners > 0))
+            .then(|| ring.clone())
+    }
+
+    // Checks whether the (smoothed and refined) ring self-intersects, returning whether it is kept
+    // (and restoring its `unsmoothed` copy if it has to be repaired).
+    fn check_self_intersections(&self, ring: &mut Ring, unsmoothed: Option<Ring>) -> Result<bool> {
+        if self.self_intersections == SelfIntersections::Ignore {
+            return Ok(true);
+        }
+        let Some(point) = self_intersection(ring) else {
+            return Ok(true);
+        };
+        match self.self_intersections {
+            SelfIntersections::Error => {
+                let mut point = point;
+                self.to_world(&mut point);
+                Err(new_error(ErrorKind::SelfIntersection(point)))
+            }
+            SelfIntersections::Drop => Ok(false),
+            _ => {
+                if let Some(unsmoothed) = unsmoothed {
+                    *ring = unsmoothed;
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    /// Sets the maximum depth of the adaptive refinement of the contours: in each cell
+    /// crossed by a contour, points are recursively inserted on the bilinear interpolation
+    /// of the values of the corners of the cell (halving each segment at each level)
+    /// as long as they deviate noticeably from the straight segment.
+    ///
+    /// As opposed to [`supersample`](Self::supersample), only the cells crossed by the contours
+    /// are refined. This is best used along with smoothing.
+    /// By default, `max_depth` is `0` (the contours are not refined).
+    pub fn refine(mut self, max_depth: usize) -> Self {
+        self.refine = max_depth;
+        self
+    }
+
+    // Inserts points on the bilinear interpolation of the values between the
+    // successive points of the ring (each segment of the ring lying in a single cell).
+    fn refine_ring(&self, ring: &mut Ring, values: &[Float], value: Float) {
+        let mut refined = Vec::with_capacity(ring.len());
+        for w in ring.windows(2) {
+            refined.push(w[0]);
+            self.refine_segment(&w[0], &w[1], values, value, self.refine, &mut refined);
+        }
+        if let Some(last) = ring.last() {
+            refined.push(*last);
+        }
+        *ring = refined;
+    }
+
+    fn refine_segment(
+        &self,
+        p: &Pt,
+        q: &Pt,
+        values: &[Float],
+        value: Float,
+        depth: usize,
+        result: &mut Ring,
+    ) {
+        // Tolerance (in cells) under which the segment is not refined further
+        const TOLERANCE: Float = 1e-3;
+        if depth == 0 {
+            return;
+        }
+        let (dx, dy) = (self.dx, self.dy);
+        let m = Pt {
+            x: (p.x + q.x) / 2.,
+            y: (p.y + q.y) / 2.,
+        };
+        // The cell containing the segment, between the values of 4 adjacent points of the grid
+        let (cx, cy) = ((m.x - 0.5).floor(), (m.y - 0.5).floor());
+        if cx < 0. || cy < 0. || cx >= (dx - 1) as Float || cy >= (dy - 1) as Float {
+            return;
+        }
+        let (i, j) = (cx as usize, cy as usize);
+        let (a, b) = (values[j * dx + i], values[j * dx + i + 1]);
+        let (c, d) = (values[(j + 1) * dx + i], values[(j + 1) * dx + i + 1]);
+        let bilinear = |u: Float, v: Float| {
+            a * (1. - u) * (1. - v) + b * u * (1. - v) + c * (1. - u) * v + d * u * v
+        };
+        // Move the middle of the segment along its normal until it reaches the isoline
+        let len = ((q.x - p.x).powi(2) + (q.y - p.y).powi(2)).sqrt();
+        if len < TOLERANCE {
+            return;
+        }
+        let (nx, ny) = (-(q.y - p.y) / len, (q.x - p.x) / len);
+        let (mut u, mut v) = (m.x - 0.5 - cx, m.y - 0.5 - cy);
+        let mut s = 0.;
+        for _ in 0..8 {
+            let g = bilinear(u, v) - value;
+            let gu = (b - a) * (1. - v) + (d - c) * v;
+            let gv = (c - a) * (1. - u) + (d - b) * u;
+            let slope = gu * nx + gv * ny;
+            if slope.abs() < Float::EPSILON {
+                return;
+            }
+            let step = g / slope;
+            s -= step;
+            u -= step * nx;
+            v -= step * ny;
+            if !(0. ..=1.).contains(&u) || !(0. ..=1.).contains(&v) {
+                return;
+            }
+            if step.abs() < TOLERANCE * TOLERANCE {
+                break;
+            }
+        }
+        if s.abs() < TOLERANCE {
+            return;
+        }
+        let r = Pt {
+            x: m.x + s * nx,
+            y: m.y + s * ny,
+        };
+        self.refine_segment(p, &r, values, value, depth - 1, result);
+        result.push(r);
+        self.refine_segment(&r, q, values, value, depth - 1, result);
+    }
+
+    /// Sets the number of `iterations` of the rounding of the corners of the contours, after
+    /// their smoothing and refinement, using Chaikin's corner cutting algorithm: at each iteration,
+    /// each corner is replaced by two points at a quarter of its adjacent segments.
+    ///
+    /// A corner is only cut when the cut off triangle contains no point of the grid,
+    /// so that the rounded contours never pass on the wrong side of a value.
+    /// By default, `iterations` is `0` (the corners are not rounded).
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let values = [0., 0., 0., 0., 0., 1., 1., 0., 0., 1., 1., 0., 0., 0., 0., 0.];
+    /// let builder = ContourBuilder::new(4, 4, true).round_corners(2);
+    /// let contours = builder.contours(&values, &[0.5]).unwrap();
+    /// assert!(contours[0].geometry().0[0].exterior().0.len() > 9);
+    /// ```
+    pub fn round_corners(mut self, iterations: usize) -> Self {
+        self.round_corners = iterations;
+        self
+    }
+
+    /// Sets whether the rounding of the corners (see [`round_corners`](Self::round_corners))
+    /// is constrained so that the contours of different thresholds never cross each other,
+    /// as required to render the filled contours (or the isobands) consistently.
+    ///
+    /// The displacement of each corner is limited to half the distance to the isolines
+    /// of the nearest other threshold, estimated from the gradient of the values at the corner
+    /// (the corners being cut less, or not at all, where the isolines are close).
+    /// By default, the rounding is not constrained.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let values = [0., 0., 0., 0., 0., 1., 1., 0., 0., 1., 1., 0., 0., 0., 0., 0.];
+    /// let builder = ContourBuilder::new(4, 4, true).round_corners(3).non_crossing(true);
+    /// let bands = builder.isobands(&values, &[0., 0.45, 0.5, 1.]).unwrap();
+    /// assert_eq!(bands.len(), 3);
+    /// ```
+    pub fn non_crossing(mut self, non_crossing: bool) -> Self {
+        self.non_crossing = non_crossing;
+        self
+    }
+
+    // Returns the difference between the `threshold` and the nearest other of the `thresholds`
+    // (infinite if there is none), which bounds the displacement of the rounded corners.
+    fn level_spacing(&self, thresholds: &[Float], threshold: Float) -> Float {
+        thresholds
+            .iter()
+            .filter(|t| **t != threshold)
+            .map(|t| (t - threshold).abs())
+            .fold(Float::INFINITY, Float::min)
+    }
+
+    // Cuts the corners of the ring (in the coordinates of the grid) that can be cut
+    // without moving any point of the grid to the other side of the ring (nor, when the
+    // rounding is constrained, further than half the distance to the isolines `spacing` apart).
+    fn round_ring(&self, ring: &mut Ring, values: &[Float], spacing: Float) {
+        for _ in 0..self.round_corners {
+            let n = ring.len().saturating_sub(1);
+            if n < 3 {
+                return;
+            }
+            let mut rounded = Vec::with_capacity(2 * n + 1);
+            for i in 0..n {
+                let (p, c, q) = (ring[(i + n - 1) % n], ring[i], ring[i + 1]);
+                let f = if self.non_crossing {
+                    self.corner_cut(&p, &c, &q, values, spacing)
+                } else {
+                    0.25
+                };
+                let (a, b) = (
+                    Pt {
+                        x: (1. - f) * c.x + f * p.x,
+                        y: (1. - f) * c.y + f * p.y,
+                    },
+                    Pt {
+                        x: (1. - f) * c.x + f * q.x,
+                        y: (1. - f) * c.y + f * q.y,
+                    },
+                );
+                if f < Float::EPSILON || self.contains_grid_point(&a, &c, &b) {
+                    rounded.push(c);
+                } else {
+                    rounded.extend([a, b]);
+                }
+            }
+            if rounded.len() == n {
+                return;
+            }
+            rounded.push(rounded[0]);
+            *ring = rounded;
+        }
+    }
+
+    // Returns the fraction of the adjacent segments of the corner `c` (between `p` and `q`)
+    // to cut, so that the corner moves by at most half the distance to the isolines
+    // `spacing` apart, spread over the iterations of the rounding.
+    fn corner_cut(&self, p: &Pt, c: &Pt, q: &Pt, values: &[Float], spacing: Float) -> Float {
+        // The isolines of all the thresholds meet on the border of the grid
+        let (dx, dy) = (self.dx as Float, self.dy as Float);
+        if c.x <= 0. || c.y <= 0. || c.x >= dx || c.y >= dy {
+            return 0.;
+        }
+        let mut world = *c;
+        self.to_world(&mut world);
+        let gradient = self.gradient(&world, values);
+        let slope = (gradient.x * self.x_step).hypot(gradient.y * self.y_step);
+        let allowed = 0.5 * spacing / slope / self.round_corners as Float;
+        // The distance from the corner to the cut when cutting a quarter of its segments
+        let (u, v) = ((q.x - p.x) / 4., (q.y - p.y) / 4.);
+        let (wx, wy) = ((p.x - c.x) / 4., (p.y - c.y) / 4.);
+        let distance = (u * wy - v * wx).abs() / u.hypot(v);
+        if allowed.is_nan() {
+            // Next to a NODATA value, the isolines may meet as well
+            0.
+        } else if distance > allowed {
+            0.25 * allowed / distance
+        } else {
+            0.25
+        }
+    }
+
+    // Returns whether the triangle (including its edges) contains a point of the grid.
+    fn contains_grid_point(&self, a: &Pt, b: &Pt, c: &Pt) -> bool {
+        let bbox = Bbox::new(&[*a, *b, *c]);
+        let range = |min: Float, max: Float, len: usize| {
+            let start = (min - 0.5).ceil().max(0.);
+            let end = (max - 0.5).floor().min(len as Float - 1.);
+            (start as usize)..=(end as usize)
+        };
+        if bbox.max.x < 0.5 || bbox.max.y < 0.5 {
+            return false;
+        }
+        let orientation =
+            |p: &Pt, q: &Pt, r: &Pt| (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x);
+        range(bbox.min.y, bbox.max.y, self.dy).any(|j| {
+            range(bbox.min.x, bbox.max.x, self.dx).any(|i| {
+                let s = Pt {
+                    x: i as Float + 0.5,
+                    y: j as Float + 0.5,
+                };
+                let (u, v, w) = (
+                    orientation(a, b, &s),
+                    orientation(b, c, &s),
+                    orientation(c, a, &s),
+                );
+                (u >= 0. && v >= 0. && w >= 0.) || (u <= 0. && v <= 0. && w <= 0.)
+            })
+        })
+    }
+
+    // Returns a builder (without any transformation) for the supersampled grid,
+    // along with the supersampled values.
+    fn supersampled(&self, values: &[Float]) -> Result<(ContourBuilder, Vec<Float>)> {
+        let n = self.supersample;
+        let (dx, dy) = (self.dx, self.dy);
+        let overflow = || new_error(ErrorKind::Overflow);
+        let empty = || new_error(ErrorKind::BadDimension);
+        let rdx = dx.checked_sub(1).ok_or_else(empty)?;
+        let rdy = dy.checked_sub(1).ok_or_else(empty)?;
+        let rdx = rdx.checked_mul(n).ok_or_else(overflow)? + 1;
+        let rdy = rdy.checked_mul(n).ok_or_else(overflow)? + 1;
+        let mut refined = Vec::with_capacity(rdx.checked_mul(rdy).ok_or_else(overflow)?);
+        for j in 0..rdy {
+            let (y0, ty) = ((j / n).min(dy.saturating_sub(2)), j as Float / n as Float);
+            let ty = ty - y0 as Float;
+            let y1 = (y0 + 1).min(dy - 1);
+            for i in 0..rdx {
+                let (x0, tx) = ((i / n).min(dx.saturating_sub(2)), i as Float / n as Float);
+                let tx = tx - x0 as Float;
+                let x1 = (x0 + 1).min(dx - 1);
+                let top = values[y0 * dx + x0] * (1. - tx) + values[y0 * dx + x1] * tx;
+                let bottom = values[y1 * dx + x0] * (1. - tx) + values[y1 * dx + x1] * tx;
+                refined.push(top * (1. - ty) + bottom * ty);
+            }
+        }
+        let mut builder = ContourBuilder::new(rdx, rdy, self.smooth)
+            .refine(self.refine)
+            .round_corners(self.round_corners)
+            .non_crossing(self.non_crossing)
+            .nest_holes(self.nest_holes)
+            .self_intersections(self.self_intersections)
+            .max_rings(self.max_rings)
+            .max_total_vertices(self.max_total_vertices);
+        builder.timeout = self.timeout;
+        #[cfg(feature = "rayon")]
+        {
+            builder.thread_pool = self.thread_pool.clone();
+        }
+        // The envelope is mapped to the coordinates of the supersampled grid
+        builder.envelope = self.grid_envelope().map(|envelope| {
+            let map = |p: Pt| Pt {
+                x: (p.x - 0.5) * n as Float + 0.5,
+                y: (p.y - 0.5) * n as Float + 0.5,
+            };
+            Rect::new(map(envelope.min), map(envelope.max))
+        });
+        Ok((builder, refined))
+    }
+
+    // Maps a point computed on the supersampled grid to the coordinates of this grid,
+    // applying the transformation of this grid.
+    fn map_supersampled(&self, point: &mut Pt) {
+        let n = self.supersample as Float;
+        let map = |u: Float, len: usize| {
+            let rlen = ((len - 1) * self.supersample + 1) as Float;
+            if u <= 0. {
+                0.
+            } else if u >= rlen {
+                len as Float
+            } else {
+                (u - 0.5) / n + 0.5
+            }
+        };
+        point.x = map(point.x, self.dx);
+        point.y = map(point.y, self.dy);
+        if self.registration == Registration::Point {
+            point.x = point.x.clamp(0.5, (self.dx as Float - 0.5).max(0.5));
+            point.y = point.y.clamp(0.5, (self.dy as Float - 0.5).max(0.5));
+        }
+        self.to_world(point);
+    }
+
+    // Maps the location of a self-intersection found on the supersampled grid.
+    fn map_supersampled_error(&self, err: Error) -> Error {
+        match err.into_kind() {
+            ErrorKind::SelfIntersection(mut point) => {
+                self.map_supersampled(&mut point);
+                new_error(ErrorKind::SelfIntersection(point))
+            }
+            kind => new_error(kind),
+        }
+    }
+
+    // Maps the polygons computed on the supersampled grid, snapping their vertices if needed
+    // (and dropping the rings collapsed by the snapping).
+    fn map_supersampled_polygons(&self, geometry: &mut MultiPolygon<Float>) {
+        let map = |mut ring: LineString<Float>| {
+            ring.0.iter_mut().for_each(|p| self.map_supersampled(p));
+            self.snap_ring(&mut ring.0);
+            (ring.0.len() > 3).then_some(ring)
+        };
+        geometry.0 = std::mem::take(&mut geometry.0)
+            .into_iter()
+            .filter_map(|polygon| {
+                let (exterior, interiors) = polygon.into_inner();
+                let interiors = interiors.into_iter().filter_map(map).collect();
+                Some(Polygon::new(map(exterior)?, interiors))
+            })
+            .collect();
+    }
+
     fn smoooth_linear(&self, ring: &mut Ring, values: &[Float], value: Float) {
         let dx = self.dx;
         let dy = self.dy;
@@ -89,13 +1191,19 @@ impl ContourBuilder {
                 let ix = yt * dx + xt;
                 if ix < len_values {
                     let v1 = values[ix];
+                    // The points next to a NODATA (NaN) value are left in the middle of
+                    // the edge, so that the NODATA regions are outlined cell by cell
                     if x > 0.0 && x < (dx as Float) && (xt as Float - x).abs() < Float::EPSILON {
                         v0 = values[yt * dx + xt - 1];
-                        point.x = x + (value - v0) / (v1 - v0) - 0.5;
+                        if !v0.is_nan() && !v1.is_nan() {
+                            point.x = x + (value - v0) / (v1 - v0) - 0.5;
+                        }
                     }
                     if y > 0.0 && y < (dy as Float) && (yt as Float - y).abs() < Float::EPSILON {
                         v0 = values[(yt - 1) * dx + xt];
-                        point.y = y + (value - v0) / (v1 - v0) - 0.5;
+                        if !v0.is_nan() && !v1.is_nan() {
+                            point.y = y + (value - v0) / (v1 - v0) - 0.5;
+                        }
                     }
                 }
             })
@@ -107,50 +1215,282 @@ impl ContourBuilder {
     /// to GeoJSON Features of MultiLineString).
     /// The threshold value of each Feature is stored in its `value` property.
     ///
+    /// Returns an [`ErrorKind::BadThreshold`] error if a threshold is NaN, infinite or duplicated.
+    ///
     /// # Arguments
     ///
     /// * `values` - The slice of values to be used.
     /// * `thresholds` - The slice of thresholds values to be used.
     pub fn lines(&self, values: &[Float], thresholds: &[Float]) -> Result<Vec<Line>> {
-        if values.len() != self.dx * self.dy {
-            return Err(new_error(ErrorKind::BadDimension));
+        span!("lines", thresholds = thresholds.len());
+        check_len(values.len(), self.dx, self.dy)?;
+        validate_thresholds(thresholds, false)?;
+        if self.supersample > 1 {
+            let (builder, supersampled) = self.supersampled(values)?;
+            let mut lines = builder
+                .lines(&supersampled, thresholds)
+                .map_err(|err| self.map_supersampled_error(err))?;
+            lines.iter_mut().for_each(|line| {
+                line.geometry.iter_mut().for_each(|linestring| {
+                    linestring
+                        .0
+                        .iter_mut()
+                        .for_each(|p| self.map_supersampled(p));
+                    self.snap_ring(&mut linestring.0);
+                });
+                self.split_lines(&mut line.geometry);
+                line.bbox = lines_bbox(&line.geometry);
+            });
+            self.set_gradients(&mut lines, values);
+            return Ok(lines);
+        }
+        let vertices = AtomicUsize::new(0);
+        let mut lines = self.map_thresholds(thresholds, |isoring, threshold| {
+            let spacing = self.level_spacing(thresholds, threshold);
+            self.line(values, threshold, spacing, isoring, &vertices)
+        })?;
+        self.set_gradients(&mut lines, values);
+        Ok(lines)
+    }
+
+    // Computes the gradients at the vertices of the lines if needed.
+    fn set_gradients(&self, lines: &mut [Line], values: &[Float]) {
+        if !self.gradients {
+            return;
+        }
+        lines.iter_mut().for_each(|line| {
+            let gradients = line
+                .geometry
+                .iter()
+                .map(|linestring| {
+                    linestring
+                        .0
+                        .iter()
+                        .map(|point| self.gradient(point, values))
+                        .collect()
+                })
+                .collect();
+            line.gradients = Some(gradients);
+        });
+    }
+
+    // Returns the gradient, at the given (transformed and quantized) point, of the bilinear
+    // interpolation of the values of the corners of the cell containing this point.
+    fn gradient(&self, point: &Pt, values: &[Float]) -> Gradient {
+        let (dx, dy) = (self.dx, self.dy);
+        let mut point = *point;
+        // The quantized vertices are brought back to world coordinates
+        if let Some((extent, resolution)) = self.quantize {
+            let (min, resolution) = (extent.min(), resolution as Float);
+            point.x = min.x + point.x * extent.width() / resolution;
+            point.y = min.y + point.y * extent.height() / resolution;
+        }
+        let grid = self.to_grid(point);
+        let (u, v) = (grid.x - 0.5, grid.y - 0.5);
+        let cell = |t: Float, len: usize| {
+            let c = t.floor().clamp(0., len.saturating_sub(2) as Float);
+            (c as usize, (t - c).clamp(0., 1.))
+        };
+        let ((x0, fx), (y0, fy)) = (cell(u, dx), cell(v, dy));
+        let (x1, y1) = ((x0 + 1).min(dx - 1), (y0 + 1).min(dy - 1));
+        let (v00, v10) = (values[y0 * dx + x0], values[y0 * dx + x1]);
+        let (v01, v11) = (values[y1 * dx + x0], values[y1 * dx + x1]);
+        let du = if x1 > x0 {
+            (v10 - v00) * (1. - fy) + (v11 - v01) * fy
+        } else {
+            0.
+        };
+        let dv = if y1 > y0 {
+            (v01 - v00) * (1. - fx) + (v11 - v10) * fx
+        } else {
+            0.
+        };
+        Gradient {
+            x: du / self.x_step,
+            y: dv / self.y_step,
         }
-        let mut isoring = IsoRingBuilder::new(self.dx, self.dy);
-        thresholds
-            .iter()
-            .map(|threshold| self.line(values, *threshold, &mut isoring))
-            .collect()
     }
 
     fn line(
         &self,
         values: &[Float],
         threshold: Float,
+        spacing: Float,
         isoring: &mut IsoRingBuilder,
+        vertices: &AtomicUsize,
     ) -> Result<Line> {
-        let mut result = isoring.compute(values, threshold)?;
+        let mut result = self.compute_rings(isoring, values, threshold, vertices)?;
         let mut linestrings = Vec::new();
+        let envelope = self.grid_envelope();
 
-        result.drain(..).for_each(|mut ring| {
+        span!("smoothing", threshold);
+        for mut ring in result.drain(..) {
+            if envelope.is_some_and(|envelope| !envelope.intersects(&Bbox::new(&ring))) {
+                continue;
+            }
+            let unsmoothed = self.unsmoothed(&ring);
             // Smooth the ring if needed
             if self.smooth {
                 self.smoooth_linear(&mut ring, values, threshold);
             }
-            // Compute the polygon coordinates according to the grid properties if needed
-            if (self.x_origin, self.y_origin) != (0.0, 0.0)
-                || (self.x_step, self.y_step) != (1.0, 1.0)
-            {
-                ring.iter_mut().for_each(|point| {
-                    point.x = point.x * self.x_step + self.x_origin;
-                    point.y = point.y * self.y_step + self.y_origin;
-                });
+            // Refine the ring if needed
+            if self.refine > 0 {
+                self.refine_ring(&mut ring, values, threshold);
+            }
+            // Round the corners of the ring if needed
+            if self.round_corners > 0 {
+                self.round_ring(&mut ring, values, spacing);
             }
+            if !self.check_self_intersections(&mut ring, unsmoothed)? {
+                continue;
+            }
+            // Compute the polygon coordinates according to the grid properties if needed
+            self.ring_to_world(&mut ring);
+            self.snap_ring(&mut ring);
             linestrings.push(LineString(ring));
-        });
-        Ok(Line {
-            geometry: MultiLineString::<Float>(linestrings),
-            threshold,
-        })
+        }
+        let mut geometry = MultiLineString::<Float>(linestrings);
+        self.split_lines(&mut geometry);
+        Ok(Line::new(geometry, threshold))
+    }
+
+    /// Computes the raw marching squares segments according the given input `values`
+    /// and the given `thresholds`, skipping their assembly into rings.
+    /// Returns a `Vec` of [`Segment`] for each threshold.
+    ///
+    /// The segments are smoothed (if enabled) and transformed according to the registration,
+    /// the origin and the step of the builder, but the `supersample` and `refine` options
+    /// are not applied.
+    ///
+    /// Returns an [`ErrorKind::BadThreshold`] error if a threshold is NaN, infinite or duplicated.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `thresholds` - The slice of thresholds values to be used.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, false);
+    /// let segments = builder
+    ///     .segments(&[0., 0., 0., 0., 1., 0., 0., 0., 0.], &[0.5])
+    ///     .unwrap();
+    /// assert_eq!(segments[0].len(), 4);
+    /// ```
+    pub fn segments(&self, values: &[Float], thresholds: &[Float]) -> Result<Vec<Vec<Segment>>> {
+        span!("segments", thresholds = thresholds.len());
+        check_len(values.len(), self.dx, self.dy)?;
+        validate_thresholds(thresholds, false)?;
+        thresholds
+            .iter()
+            .map(|threshold| {
+                let segments = contour_segments(values, *threshold, self.dx, self.dy)?;
+                Ok(segments
+                    .into_iter()
+                    .filter_map(|mut segment| {
+                        let mut points = vec![segment.start, segment.end];
+                        if self.smooth {
+                            self.smoooth_linear(&mut points, values, *threshold);
+                        }
+                        // The segments clamped to a point with the point registration are dropped
+                        self.ring_to_world(&mut points);
+                        (points.len() == 2).then(|| {
+                            (segment.start, segment.end) = (points[0], points[1]);
+                            segment
+                        })
+                    })
+                    .collect())
+            })
+            .collect()
+    }
+
+    /// Computes the marching squares case (from `0` to `15`) of each cell for the given
+    /// `threshold`, to help visualizing and debugging the topology of the contours
+    /// (such as the saddle cells, of cases `5` and `10`).
+    ///
+    /// The bit `8`, `4`, `2` and `1` of the case are respectively set when the upper left,
+    /// upper right, lower right and lower left samples of the cell are greater than or equal
+    /// to the threshold. The returned [`Buffer`] has `dx + 1` columns and `dy + 1` rows,
+    /// as the cells of the border added around the grid (whose outer samples are considered
+    /// below the threshold) are included: the cell at column `i` and row `j` of the buffer
+    /// lies between the samples of columns `i - 1` and `i` and of rows `j - 1` and `j` of the grid.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(2, 2, false);
+    /// let cases = builder.cases(&[1., 0., 0., 1.], 0.5).unwrap();
+    /// assert_eq!(cases.get(1, 1), Some(&10));
+    /// ```
+    pub fn cases(&self, values: &[Float], threshold: Float) -> Result<Buffer<u8>> {
+        check_len(values.len(), self.dx, self.dy)?;
+        let (dx, dy) = (self.dx, self.dy);
+        let cases = (-1..dy as i64)
+            .flat_map(|y| {
+                (-1..dx as i64).map(move |x| cell_case(values, threshold, dx, dy, x, y) as u8)
+            })
+            .collect();
+        Buffer::new(cases, dx + 1, dy + 1)
+    }
+
+    /// Checks a request without computing its geometry, such as to reject bad requests cheaply
+    /// before committing CPU: checks the dimensions of the grid, the transformation of the
+    /// builder (whose origin and steps must be finite, and steps non-zero) and the `thresholds`
+    /// (as for [`lines`](Self::lines) and [`contours`](Self::contours)).
+    /// Returns the estimated number of vertices of the output (before any smoothing,
+    /// supersampling or refinement): the number of marching squares segments of all
+    /// the thresholds.
+    ///
+    /// Returns an [`ErrorKind::BadTransform`] error if the transformation is invalid,
+    /// and an [`ErrorKind::TooManyVertices`] error if the estimate exceeds the
+    /// [`max_total_vertices`](Self::max_total_vertices) of the builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `thresholds` - The slice of thresholds values to be used.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, ErrorKind};
+    ///
+    /// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+    /// let builder = ContourBuilder::new(3, 3, true);
+    /// assert_eq!(builder.validate(&values, &[0.5]).unwrap(), 4);
+    /// let builder = builder.max_total_vertices(3);
+    /// let err = builder.validate(&values, &[0.5]).unwrap_err();
+    /// assert!(matches!(err.kind(), ErrorKind::TooManyVertices { max: 3 }));
+    /// let builder = ContourBuilder::new(3, 3, true).x_step(0.);
+    /// let err = builder.validate(&values, &[0.5]).unwrap_err();
+    /// assert!(matches!(err.kind(), ErrorKind::BadTransform));
+    /// ```
+    pub fn validate(&self, values: &[Float], thresholds: &[Float]) -> Result<usize> {
+        check_len(values.len(), self.dx, self.dy)?;
+        let transform = [self.x_origin, self.y_origin, self.x_step, self.y_step];
+        if transform.iter().any(|t| !t.is_finite()) || self.x_step == 0. || self.y_step == 0. {
+            return Err(new_error(ErrorKind::BadTransform));
+        }
+        validate_thresholds(thresholds, false)?;
+        let (dx, dy) = (self.dx, self.dy);
+        let vertices = thresholds
+            .iter()
+            .map(|threshold| {
+                (-1..dy as i64)
+                    .flat_map(|y| (-1..dx as i64).map(move |x| (x, y)))
+                    .map(|(x, y)| match cell_case(values, *threshold, dx, dy, x, y) {
+                        0 | 15 => 0,
+                        5 | 10 => 2,
+                        _ => 1,
+                    })
+                    .sum::<usize>()
+            })
+            .sum();
+        if self.max_total_vertices > 0 && vertices > self.max_total_vertices {
+            return Err(new_error(ErrorKind::TooManyVertices {
+                max: self.max_total_vertices,
+            }));
+        }
+        Ok(vertices)
     }
 
     /// Computes contours according the given input `values` and the given `thresholds`.
@@ -158,64 +1498,210 @@ impl ContourBuilder {
     /// to GeoJSON Features of MultiPolygon).
     /// The threshold value of each Feature is stored in its `value` property.
     ///
+    /// The NODATA values (NaN) are left outside of the contours: a NODATA region lying
+    /// inside a contour is emitted as a hole of its polygon, following the edges of its cells.
+    ///
+    /// Returns an [`ErrorKind::BadThreshold`] error if a threshold is NaN, infinite or duplicated.
+    ///
     /// # Arguments
     ///
     /// * `values` - The slice of values to be used.
     /// * `thresholds` - The slice of thresholds values to be used.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, Float};
+    ///
+    /// let mut values = [2.; 25];
+    /// values[12] = Float::NAN;
+    /// let contours = ContourBuilder::new(5, 5, true).contours(&values, &[1.]).unwrap();
+    /// let polygon = &contours[0].geometry().0[0];
+    /// assert_eq!(polygon.interiors().len(), 1);
+    /// assert!(polygon.interiors()[0].0.iter().all(|p| p.x >= 2. && p.x <= 3.));
+    /// ```
     pub fn contours(&self, values: &[Float], thresholds: &[Float]) -> Result<Vec<Contour>> {
-        if values.len() != self.dx * self.dy {
-            return Err(new_error(ErrorKind::BadDimension));
+        span!("contours", thresholds = thresholds.len());
+        check_len(values.len(), self.dx, self.dy)?;
+        validate_thresholds(thresholds, false)?;
+        if self.supersample > 1 {
+            let (builder, values) = self.supersampled(values)?;
+            let mut contours = builder
+                .contours(&values, thresholds)
+                .map_err(|err| self.map_supersampled_error(err))?;
+            contours.iter_mut().for_each(|contour| {
+                self.map_supersampled_polygons(&mut contour.geometry);
+                self.finish_polygons(&mut contour.geometry);
+                contour.bbox = polygons_bbox(&contour.geometry);
+                contour.interior_points = polygons_interior_points(&contour.geometry);
+            });
+            return Ok(contours);
         }
-        let mut isoring = IsoRingBuilder::new(self.dx, self.dy);
-        thresholds
-            .iter()
-            .map(|threshold| self.contour(values, *threshold, &mut isoring))
-            .collect()
+        let vertices = AtomicUsize::new(0);
+        self.map_thresholds(thresholds, |isoring, threshold| {
+            let spacing = self.level_spacing(thresholds, threshold);
+            self.contour(values, threshold, spacing, isoring, &vertices)
+        })
     }
 
     fn contour(
         &self,
         values: &[Float],
         threshold: Float,
+        spacing: Float,
         isoring: &mut IsoRingBuilder,
+        vertices: &AtomicUsize,
     ) -> Result<Contour> {
         let (mut polygons, mut holes) = (Vec::new(), Vec::new());
-        let mut result = isoring.compute(values, threshold)?;
+        let mut result = self.compute_rings(isoring, values, threshold, vertices)?;
+        let envelope = self.grid_envelope();
 
-        result.drain(..).for_each(|mut ring| {
-            // Smooth the ring if needed
-            if self.smooth {
-                self.smoooth_linear(&mut ring, values, threshold);
-            }
-            // Compute the polygon coordinates according to the grid properties if needed
-            if (self.x_origin, self.y_origin) != (0.0, 0.0)
-                || (self.x_step, self.y_step) != (1.0, 1.0)
-            {
-                ring.iter_mut().for_each(|point| {
-                    point.x = point.x * self.x_step + self.x_origin;
-                    point.y = point.y * self.y_step + self.y_origin;
-                });
-            }
-            if area(&ring) > 0.0 {
-                polygons.push(Polygon::<Float>::new(LineString::new(ring), vec![]))
-            } else {
-                holes.push(LineString::new(ring));
-            }
-        });
-
-        holes.drain(..).for_each(|hole| {
-            for polygon in &mut polygons {
-                if contains(&polygon.exterior().0, &hole.0) != -1 {
-                    polygon.interiors_push(hole);
-                    return;
+        {
+            span!("smoothing", threshold);
+            for mut ring in result.drain(..) {
+                if envelope.is_some_and(|envelope| !envelope.intersects(&Bbox::new(&ring))) {
+                    continue;
+                }
+                let unsmoothed = self.unsmoothed(&ring);
+                // Smooth the ring if needed
+                if self.smooth {
+                    self.smoooth_linear(&mut ring, values, threshold);
+                }
+                // Refine the ring if needed
+                if self.refine > 0 {
+                    self.refine_ring(&mut ring, values, threshold);
+                }
+                // Round the corners of the ring if needed
+                if self.round_corners > 0 {
+                    self.round_ring(&mut ring, values, spacing);
+                }
+                if !self.check_self_intersections(&mut ring, unsmoothed)? {
+                    continue;
+                }
+                // Classify the ring before its transform, which mirrors it if one of the
+                // steps is negative (such as for the north-up grids)
+                let exterior = area(&ring) > 0.0;
+                // Compute the polygon coordinates according to the grid properties if needed
+                self.ring_to_world(&mut ring);
+                self.snap_ring(&mut ring);
+                if ring.len() < 4 {
+                    continue;
+                }
+                if exterior {
+                    polygons.push(Polygon::<Float>::new(LineString::new(ring), vec![]))
+                } else {
+                    holes.push(LineString::new(ring));
                 }
             }
-        });
+        }
+
+        if !self.nest_holes {
+            polygons.extend(
+                holes
+                    .into_iter()
+                    .map(|hole| Polygon::<Float>::new(hole, vec![])),
+            );
+            let mut geometry = MultiPolygon::<Float>(polygons);
+            self.finish_polygons(&mut geometry);
+            return Ok(Contour::new(geometry, threshold));
+        }
+
+        span!("nesting", threshold);
+        assign_holes(&mut polygons, holes);
+
+        let mut geometry = MultiPolygon::<Float>(polygons);
+        self.finish_polygons(&mut geometry);
+        Ok(Contour::new(geometry, threshold))
+    }
+
+    /// Computes an intermediate contour between the contour of `from` values for
+    /// the `from_threshold` and the contour of `to` values for the `to_threshold`
+    /// (`t` ranging from `0` to `1`), for instance to smoothly animate the transition
+    /// between two adjacent thresholds (using the same values twice) or between two time steps.
+    ///
+    /// The intermediate contour is the contour of the linear blend of the values
+    /// (offset by their threshold) so that it keeps a consistent topology, polygons
+    /// progressively shrinking, growing, splitting or merging.
+    /// The threshold of the resulting [`Contour`] is interpolated as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The slice of values at `t = 0`.
+    /// * `to` - The slice of values at `t = 1`.
+    /// * `from_threshold` - The threshold at `t = 0`.
+    /// * `to_threshold` - The threshold at `t = 1`.
+    /// * `t` - The interpolation parameter.
+    pub fn interpolated_contour(
+        &self,
+        from: &[Float],
+        to: &[Float],
+        from_threshold: Float,
+        to_threshold: Float,
+        t: Float,
+    ) -> Result<Contour> {
+        check_len(from.len(), self.dx, self.dy)?;
+        check_len(to.len(), self.dx, self.dy)?;
+        let values = from
+            .iter()
+            .zip(to)
+            .map(|(a, b)| (a - from_threshold) * (1. - t) + (b - to_threshold) * t)
+            .collect::<Vec<Float>>();
+        let mut contour = self
+            .contours(&values, &[0.])?
+            .pop()
+            .ok_or_else(|| new_error(ErrorKind::Unexpected))?;
+        contour.threshold = from_threshold + (to_threshold - from_threshold) * t;
+        Ok(contour)
+    }
+
+    /// Computes deterministic identifiers for the polygons of a `geometry` computed by this builder
+    /// for the given `threshold` (or the lower threshold of an isoband).
+    ///
+    /// Each identifier is a hash of the threshold and of the cell of the grid containing
+    /// the lowest vertex of the exterior ring of the polygon, so that identifiers are stable
+    /// across recomputations on the same data, and mostly stable on slightly changed data.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, true);
+    /// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+    /// let contour = builder.contours(&values, &[0.5]).unwrap().remove(0);
+    /// let ids = builder.polygon_ids(contour.geometry(), 0.5);
+    /// let other = builder.contours(&values, &[0.5]).unwrap().remove(0);
+    /// assert_eq!(ids, builder.polygon_ids(other.geometry(), 0.5));
+    /// ```
+    #[allow(clippy::unnecessary_cast)]
+    pub fn polygon_ids(&self, geometry: &MultiPolygon<Float>, threshold: Float) -> Vec<u64> {
+        geometry
+            .0
+            .iter()
+            .map(|polygon| {
+                let seed = polygon
+                    .exterior()
+                    .0
+                    .iter()
+                    .min_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+                    .map(|p| {
+                        let p = self.to_grid(*p);
+                        (p.x.floor() as i64, p.y.floor() as i64)
+                    })
+                    .unwrap_or_default();
+                fnv1a(&[(threshold as f64).to_bits(), seed.0 as u64, seed.1 as u64])
+            })
+            .collect()
+    }
 
-        Ok(Contour {
-            geometry: MultiPolygon::<Float>(polygons),
+    /// Constructs a [`RingStream`] computing the isorings for the given `threshold`
+    /// from the values of the grid fed row by row, so that the memory used doesn't
+    /// depend on the number of rows of the grid.
+    ///
+    /// The `supersample` and `refine` options are not applied to the streamed rings.
+    pub fn ring_stream(&self, threshold: Float) -> RingStream {
+        RingStream::new(
+            self.dx,
+            self.smooth,
+            (self.origin().x, self.origin().y, self.x_step, self.y_step),
             threshold,
-        })
+        )
     }
 
     /// Computes isobands according the given input `values` and the given `thresholds`.
@@ -223,52 +1709,122 @@ impl ContourBuilder {
     /// to GeoJSON Features of MultiPolygon).
     /// The threshold value of each Feature is stored in its `value` property.
     ///
+    /// Returns an [`ErrorKind::BadThreshold`] error if a threshold is NaN or duplicated
+    /// (infinite thresholds being allowed to get open bands).
+    ///
+    /// The thresholds don't have to be sorted: the bands are computed between
+    /// successive thresholds in ascending order, and returned in ascending order
+    /// (or in descending order if so were the thresholds). The thresholds of each
+    /// band are given by its [`min_v`](Band::min_v) and [`max_v`](Band::max_v),
+    /// and their indices in the given `thresholds` by its
+    /// [`threshold_indices`](Band::threshold_indices).
+    ///
     /// # Arguments
     ///
     /// * `values` - The slice of values to be used.
     /// * `thresholds` - The slice of thresholds values to be used
-    ///                  (have to be equal to or greater than 2).
+    ///   (have to be equal to or greater than 2).
     pub fn isobands(&self, values: &[Float], thresholds: &[Float]) -> Result<Vec<Band>> {
         // We will compute rings as previously, but we will
         // iterate over the contours in pairs and use the paths from the lower threshold
         // and the path from the upper threshold to create the isoband.
-        if values.len() != self.dx * self.dy {
-            return Err(new_error(ErrorKind::BadDimension));
-        }
+        span!("isobands", thresholds = thresholds.len());
+        check_len(values.len(), self.dx, self.dy)?;
         if thresholds.len() < 2 {
             return Err(new_error(ErrorKind::Unexpected));
         }
-        let mut isoring = IsoRingBuilder::new(self.dx, self.dy);
+        validate_thresholds(thresholds, true)?;
+        // Thresholds are sorted in ascending order to compute the bands
+        // (which are returned in descending order if so were the thresholds)
+        let descending = thresholds.windows(2).all(|w| w[0] > w[1]);
+        let mut sorted = thresholds.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let (original, thresholds) = (thresholds, &sorted[..]);
+        if self.supersample > 1 {
+            let (builder, values) = self.supersampled(values)?;
+            let mut bands = builder
+                .isobands(&values, thresholds)
+                .map_err(|err| self.map_supersampled_error(err))?;
+            bands.iter_mut().for_each(|band| {
+                self.map_supersampled_polygons(&mut band.geometry);
+                self.finish_polygons(&mut band.geometry);
+                band.bbox = polygons_bbox(&band.geometry);
+                band.interior_points = polygons_interior_points(&band.geometry);
+            });
+            index_bands(&mut bands, original);
+            if descending {
+                bands.reverse();
+            }
+            return Ok(bands);
+        }
+        let envelope = self.grid_envelope();
+        let vertices = AtomicUsize::new(0);
 
-        let rings = thresholds
-            .iter()
-            .map(|threshold| {
-                // Compute the rings for the current threshold
-                let rings = isoring.compute(values, *threshold)?;
-                let rings = rings
-                    .into_iter()
-                    .map(|mut ring| {
-                        // Smooth the ring if needed
-                        if self.smooth {
-                            self.smoooth_linear(&mut ring, values, *threshold);
-                        }
-                        ring.dedup();
-                        // Compute the polygon coordinates according to the grid properties if needed
-                        if (self.x_origin, self.y_origin) != (0.0, 0.0)
-                            || (self.x_step, self.y_step) != (1.0, 1.0)
-                        {
-                            ring.iter_mut().for_each(|point| {
-                                point.x = point.x * self.x_step + self.x_origin;
-                                point.y = point.y * self.y_step + self.y_origin;
-                            });
-                        }
-                        ring
-                    })
-                    .filter(|ring| ring.len() > 3)
-                    .collect::<Vec<Ring>>();
-                Ok((rings, *threshold))
-            })
-            .collect::<Result<Vec<(Vec<Ring>, Float)>>>()?;
+        let rings = self.map_thresholds(thresholds, |isoring, threshold| {
+            // Compute the rings for the current threshold
+            let rings = self.compute_rings(isoring, values, threshold, &vertices)?;
+            let spacing = self.level_spacing(thresholds, threshold);
+            span!("smoothing", threshold);
+            let mut smoothed = Vec::with_capacity(rings.len());
+            for mut ring in rings {
+                if envelope.is_some_and(|envelope| !envelope.intersects(&Bbox::new(&ring))) {
+                    continue;
+                }
+                let unsmoothed = self.unsmoothed(&ring);
+                // Smooth the ring if needed
+                if self.smooth {
+                    self.smoooth_linear(&mut ring, values, threshold);
+                }
+                // Refine the ring if needed
+                if self.refine > 0 {
+                    self.refine_ring(&mut ring, values, threshold);
+                }
+                // Round the corners of the ring if needed
+                if self.round_corners > 0 {
+                    self.round_ring(&mut ring, values, spacing);
+                }
+                if !self.check_self_intersections(&mut ring, unsmoothed)? {
+                    continue;
+                }
+                ring.dedup();
+                // Compute the polygon coordinates according to the grid properties if needed
+                self.ring_to_world(&mut ring);
+                self.snap_ring(&mut ring);
+                if ring.len() > 3 {
+                    smoothed.push(ring);
+                }
+            }
+            Ok((smoothed, threshold))
+        })?;
+
+        // Without nesting, the exterior rings of a band are the rings of the lower threshold
+        // oriented clockwise and the rings of the upper threshold oriented counterclockwise.
+        if !self.nest_holes {
+            let mut bands = rings
+                .windows(2)
+                .map(|rings| {
+                    let ((lower_path, min_v), (upper_path, max_v)) = (&rings[0], &rings[1]);
+                    let polygons = lower_path
+                        .iter()
+                        .cloned()
+                        .chain(upper_path.iter().map(|ring| {
+                            let mut ring = ring.clone();
+                            ring.reverse();
+                            ring
+                        }))
+                        .map(|ring| Polygon::<Float>::new(ring.into(), vec![]))
+                        .collect();
+                    let mut geometry = MultiPolygon::<Float>(polygons);
+                    self.finish_polygons(&mut geometry);
+                    Band::new(geometry, *min_v, *max_v)
+                })
+                .collect::<Vec<_>>();
+            index_bands(&mut bands, original);
+            if descending {
+                bands.reverse();
+            }
+            return Ok(bands);
+        }
 
         // We now have the rings for each isolines for all the given thresholds,
         // we can iterate over them in pairs to compute the isobands.
@@ -284,59 +1840,676 @@ impl ContourBuilder {
         let mut bands: Vec<Band> = Vec::new();
         // Reconstruction of the polygons
         b.into_iter().for_each(|(rings, min_v, max_v)| {
+            span!("nesting", min_v, max_v);
+            // The area, the bounding box and an interior point of each ring are computed
+            // once: the first two skip the containment tests that can't succeed, and the
+            // last one tests the containment of a ring in another with a single point.
             let mut rings_and_area = rings
                 .into_iter()
                 .map(|ring| {
-                    let area = area(&ring);
-                    (ring, area)
+                    let area = area(&ring).abs();
+                    let bbox = Bbox::new(&ring);
+                    let point = interior_point(&[&ring]);
+                    (ring, area, bbox, point)
                 })
                 .collect::<Vec<_>>();
 
-            rings_and_area.sort_by_key(|(_, area)| area.abs() as u64);
+            rings_and_area.sort_by(|(_, a, _, _), (_, b, _, _)| a.total_cmp(b));
 
-            let mut enclosed_by_n = FxHashMap::default();
-
-            for (i, (ring, _)) in rings_and_area.iter().enumerate() {
-                let mut enclosed_by_j = 0;
-                for (j, (ring_test, _)) in rings_and_area.iter().enumerate() {
-                    if i == j {
-                        continue;
-                    }
-                    if contains(ring_test, ring) != -1 {
-                        enclosed_by_j += 1;
-                    }
-                }
-                enclosed_by_n.insert(i, enclosed_by_j);
-            }
+            let enclosed_by_n = self.nesting_depths(&rings_and_area);
 
-            let mut polygons: Vec<Polygon<Float>> = Vec::new();
-            let mut interior_rings: Vec<LineString<Float>> = Vec::new();
+            let mut polygons: Vec<(Polygon<Float>, Bbox)> = Vec::new();
+            let mut interior_rings: Vec<(LineString<Float>, Bbox, Option<Pt>)> = Vec::new();
 
-            for (i, (ring, _)) in rings_and_area.into_iter().enumerate() {
-                if *enclosed_by_n.get(&i).unwrap() % 2 == 0 {
-                    polygons.push(Polygon::<Float>::new(ring.into(), vec![]));
+            for (i, (ring, _, bbox, point)) in rings_and_area.into_iter().enumerate() {
+                if enclosed_by_n[i].is_multiple_of(2) {
+                    polygons.push((Polygon::<Float>::new(ring.into(), vec![]), bbox));
                 } else {
-                    interior_rings.push(ring.into());
+                    interior_rings.push((ring.into(), bbox, point));
                 }
             }
-            for interior_ring in interior_rings.into_iter() {
-                for polygon in polygons.iter_mut() {
-                    if contains(&polygon.exterior().0, &interior_ring.0) != -1 {
+            for (interior_ring, bbox, point) in interior_rings.into_iter() {
+                for (polygon, polygon_bbox) in polygons.iter_mut() {
+                    if polygon_bbox.contains(&bbox)
+                        && ring_contains(&polygon.exterior().0, &interior_ring.0, point)
+                    {
                         polygon.interiors_push(interior_ring);
                         break;
                     }
                 }
             }
 
+            let mut polygons = polygons
+                .into_iter()
+                .map(|(polygon, _)| polygon)
+                .collect::<Vec<_>>();
             polygons.reverse();
 
-            bands.push(Band {
-                geometry: MultiPolygon::<Float>(polygons),
-                min_v: *min_v,
-                max_v: *max_v,
-            });
+            let mut geometry = MultiPolygon::<Float>(polygons);
+            self.finish_polygons(&mut geometry);
+            bands.push(Band::new(geometry, *min_v, *max_v));
         });
 
+        index_bands(&mut bands, original);
+        if descending {
+            bands.reverse();
+        }
+        Ok(bands)
+    }
+
+    /// Computes isobands as [`isobands`](Self::isobands) does, each band being the difference
+    /// between the filled [`contours`](Self::contours) of its lower and upper thresholds
+    /// (requires the `boolean-ops` feature, using the boolean operations of the
+    /// [geo](https://crates.io/crates/geo) crate), which guarantees valid band geometries
+    /// exactly nested in the contours, at the cost of speed.
+    ///
+    /// The band below an infinite lower threshold is the difference between the
+    /// [`footprint`](Self::footprint) of the valid data and the contour of its upper threshold.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, true);
+    /// let values = [0., 1., 0., 1., 2., 1., 0., 1., 0.];
+    /// let bands = builder.isobands_exact(&values, &[0.5, 1.5, 2.5]).unwrap();
+    /// assert_eq!(bands.len(), 2);
+    /// assert_eq!(bands[0].geometry().0[0].interiors().len(), 1);
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `thresholds` - The slice of thresholds values to be used
+    ///   (have to be equal to or greater than 2).
+    #[cfg(feature = "boolean-ops")]
+    pub fn isobands_exact(&self, values: &[Float], thresholds: &[Float]) -> Result<Vec<Band>> {
+        use geo::BooleanOps;
+
+        check_len(values.len(), self.dx, self.dy)?;
+        if thresholds.len() < 2 {
+            return Err(new_error(ErrorKind::Unexpected));
+        }
+        validate_thresholds(thresholds, true)?;
+        let descending = thresholds.windows(2).all(|w| w[0] > w[1]);
+        let mut sorted = thresholds.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let finite = sorted
+            .iter()
+            .copied()
+            .filter(|t| t.is_finite())
+            .collect::<Vec<_>>();
+        let mut contours = self.contours(values, &finite)?.into_iter();
+        let mut filled = Vec::with_capacity(sorted.len());
+        for threshold in &sorted {
+            filled.push(if *threshold == Float::NEG_INFINITY {
+                self.footprint(values, false)?
+            } else if *threshold == Float::INFINITY {
+                MultiPolygon(vec![])
+            } else {
+                let contour = contours
+                    .next()
+                    .ok_or_else(|| new_error(ErrorKind::Unexpected))?;
+                contour.into_inner().0
+            });
+        }
+        let sign = if self.x_step * self.y_step < 0. {
+            -1.
+        } else {
+            1.
+        };
+        let mut bands = filled
+            .windows(2)
+            .zip(sorted.windows(2))
+            .map(|(filled, thresholds)| {
+                let mut geometry = filled[0].difference(&filled[1]);
+                // The rings are oriented as the ones of the other polygons of this crate
+                // (whose orientation in the grid is reversed by a negative step)
+                for polygon in geometry.iter_mut() {
+                    polygon.exterior_mut(|ring| {
+                        if area(&ring.0) * sign < 0. {
+                            ring.0.reverse();
+                        }
+                    });
+                    polygon.interiors_mut(|holes| {
+                        holes
+                            .iter_mut()
+                            .filter(|hole| area(&hole.0) * sign > 0.)
+                            .for_each(|hole| hole.0.reverse());
+                    });
+                }
+                self.finish_polygons(&mut geometry);
+                Band::new(geometry, thresholds[0], thresholds[1])
+            })
+            .collect::<Vec<_>>();
+        index_bands(&mut bands, thresholds);
+        if descending {
+            bands.reverse();
+        }
         Ok(bands)
     }
+
+    /// Checks that the given `bands`, computed by [`isobands`](Self::isobands) with thresholds
+    /// spanning all the values of the grid (such as open thresholds), form a partition of the grid:
+    /// returns the pairs of rings of different bands that overlap (see [`BandIssue::Overlap`]),
+    /// and whether the area covered by the bands differs from the area of the grid by more than
+    /// `tolerance` (see [`BandIssue::Coverage`]).
+    ///
+    /// The area of the grid excludes its corners (cut by the marching squares),
+    /// but includes the cells with NaN values (that are left uncovered by the bands).
+    /// It is mapped as the bands are, according to the registration, the origin and the
+    /// steps of the grid, and to the quantization of the vertices.
+    /// Each pair of rings is compared exhaustively, which is best suited to sanity checks.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, Float};
+    ///
+    /// let builder = ContourBuilder::new(3, 3, true);
+    /// let values = [0., 1., 0., 1., 2., 1., 0., 1., 0.];
+    /// let thresholds = [Float::NEG_INFINITY, 0.5, 1.5, Float::INFINITY];
+    /// let bands = builder.isobands(&values, &thresholds).unwrap();
+    /// assert!(builder.validate_bands(&bands, 1e-9).is_empty());
+    /// assert!(!builder.validate_bands(&bands[1..], 1e-9).is_empty());
+    /// ```
+    #[allow(clippy::unnecessary_cast)]
+    pub fn validate_bands(&self, bands: &[Band], tolerance: Float) -> Vec<BandIssue> {
+        // The outline of the grid, whose four corners are cut by the marching squares,
+        // is mapped as the rings of the bands
+        let (dx, dy) = (self.dx as Float, self.dy as Float);
+        let mut outline = [
+            (0.5, 0.),
+            (dx - 0.5, 0.),
+            (dx, 0.5),
+            (dx, dy - 0.5),
+            (dx - 0.5, dy),
+            (0.5, dy),
+            (0., dy - 0.5),
+            (0., 0.5),
+            (0.5, 0.),
+        ]
+        .into_iter()
+        .map(|(x, y)| Pt { x, y })
+        .collect::<Ring>();
+        self.ring_to_world(&mut outline);
+        self.snap_ring(&mut outline);
+        let expected = if outline.len() > 3 {
+            area(&outline).abs() / 2.
+        } else {
+            0.
+        };
+        validate_bands(bands, expected as Float, tolerance)
+    }
+
+    /// Computes the statistics of the given input `values` within each polygon of each of the
+    /// `bands` (computed by [`isobands`](Self::isobands) from the same values), that is of the
+    /// samples of the polygon whose value is between the thresholds of its band, without
+    /// rasterizing the polygons back over the grid.
+    ///
+    /// The statistics are returned per band, and per polygon in the order of the polygons
+    /// of the band.
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(5, 3, true);
+    /// #[rustfmt::skip]
+    /// let values = [
+    ///     0., 0., 0., 0., 0.,
+    ///     0., 1., 0., 3., 0.,
+    ///     0., 0., 0., 0., 0.,
+    /// ];
+    /// let bands = builder.isobands(&values, &[0.5, 2., 4.]).unwrap();
+    /// let statistics = builder.band_statistics(&values, &bands).unwrap();
+    /// assert_eq!((statistics[0].len(), statistics[1].len()), (2, 1));
+    /// // The polygon around the sample of value `3` (a hole) has no sample in the first band
+    /// assert_eq!(statistics[0].iter().map(|s| s.count).sum::<usize>(), 1);
+    /// assert_eq!(statistics[1][0].mean(), Some(3.));
+    /// ```
+    pub fn band_statistics(
+        &self,
+        values: &[Float],
+        bands: &[Band],
+    ) -> Result<Vec<Vec<ZonalStatistics>>> {
+        check_len(values.len(), self.dx, self.dy)?;
+        Ok(bands
+            .iter()
+            .map(|band| {
+                let (min, max) = (band.min_v.min(band.max_v), band.min_v.max(band.max_v));
+                self.zonal_statistics(values, band.geometry(), |v| v >= min && v < max)
+            })
+            .collect())
+    }
+
+    /// Computes the statistics of the given input `values` within each polygon of each of the
+    /// `contours` (computed by [`contours`](Self::contours) from the same values), that is of
+    /// the samples of the polygon whose value is greater than or equal to its threshold.
+    ///
+    /// The statistics are returned per contour, and per polygon in the order of the polygons
+    /// of the contour.
+    pub fn contour_statistics(
+        &self,
+        values: &[Float],
+        contours: &[Contour],
+    ) -> Result<Vec<Vec<ZonalStatistics>>> {
+        check_len(values.len(), self.dx, self.dy)?;
+        Ok(contours
+            .iter()
+            .map(|contour| {
+                let threshold = contour.threshold();
+                self.zonal_statistics(values, contour.geometry(), |v| v >= threshold)
+            })
+            .collect())
+    }
+
+    // Computes the statistics of the samples within each polygon of the `geometry`
+    // whose value verifies `member` (the samples of the polygon, as traced).
+    #[allow(clippy::unnecessary_cast)]
+    fn zonal_statistics<F: Fn(Float) -> bool>(
+        &self,
+        values: &[Float],
+        geometry: &MultiPolygon<Float>,
+        member: F,
+    ) -> Vec<ZonalStatistics> {
+        geometry
+            .0
+            .iter()
+            .map(|polygon| {
+                let mut statistics = ZonalStatistics::default();
+                let Some(bbox) = polygons_bbox(&MultiPolygon(vec![polygon.clone()])) else {
+                    return statistics;
+                };
+                // The range of the samples covered by the bounding box of the polygon
+                let (a, b) = (self.to_grid(bbox.min()), self.to_grid(bbox.max()));
+                let range = |a: Float, b: Float, n: usize| {
+                    let start = (a.min(b) - 0.5).ceil().max(0.) as usize;
+                    let end = ((a.max(b) - 0.5).floor() + 1.).clamp(0., n as Float) as usize;
+                    start..end
+                };
+                for y in range(a.y, b.y, self.dy) {
+                    for x in range(a.x, b.x, self.dx) {
+                        let value = values[y * self.dx + x];
+                        if !member(value) {
+                            continue;
+                        }
+                        let mut point = Pt {
+                            x: x as Float + 0.5,
+                            y: y as Float + 0.5,
+                        };
+                        self.to_world(&mut point);
+                        if ring::contains(&polygon.exterior().0, &point) >= 0
+                            && polygon
+                                .interiors()
+                                .iter()
+                                .all(|hole| ring::contains(&hole.0, &point) <= 0)
+                        {
+                            statistics.add(value);
+                        }
+                    }
+                }
+                statistics
+            })
+            .collect()
+    }
+
+    /// Computes isolines as [`lines`](Self::lines) does, carrying along each [`Line`]
+    /// the user `metadata` (such as a label or a color) attached to its threshold.
+    ///
+    /// Returns an error if `metadata` and `thresholds` don't have the same length.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `thresholds` - The slice of thresholds values to be used.
+    /// * `metadata` - The metadata of each threshold.
+    pub fn lines_with_metadata<M: Clone>(
+        &self,
+        values: &[Float],
+        thresholds: &[Float],
+        metadata: &[M],
+    ) -> Result<Vec<(Line, M)>> {
+        if metadata.len() != thresholds.len() {
+            return Err(new_error(ErrorKind::BadDimension));
+        }
+        let lines = self.lines(values, thresholds)?;
+        Ok(lines.into_iter().zip(metadata.iter().cloned()).collect())
+    }
+
+    /// Computes contours as [`contours`](Self::contours) does, carrying along each [`Contour`]
+    /// the user `metadata` (such as a label or a color) attached to its threshold.
+    ///
+    /// Returns an error if `metadata` and `thresholds` don't have the same length.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `thresholds` - The slice of thresholds values to be used.
+    /// * `metadata` - The metadata of each threshold.
+    pub fn contours_with_metadata<M: Clone>(
+        &self,
+        values: &[Float],
+        thresholds: &[Float],
+        metadata: &[M],
+    ) -> Result<Vec<(Contour, M)>> {
+        if metadata.len() != thresholds.len() {
+            return Err(new_error(ErrorKind::BadDimension));
+        }
+        let contours = self.contours(values, thresholds)?;
+        Ok(contours.into_iter().zip(metadata.iter().cloned()).collect())
+    }
+
+    /// Computes isobands as [`isobands`](Self::isobands) does, carrying along each [`Band`]
+    /// the user `metadata` (such as a class name or a color) attached to it.
+    ///
+    /// The metadata `metadata[i]` is attached to the band between `thresholds[i]` and
+    /// `thresholds[i + 1]` (whatever the order of the thresholds), so the successive
+    /// thresholds have to bound the bands: an [`ErrorKind::BadThreshold`] error is
+    /// returned with the index of a threshold out of order otherwise.
+    ///
+    /// Returns an error if `metadata` doesn't have one element less than `thresholds`
+    /// (i.e. one element per band).
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `thresholds` - The slice of thresholds values to be used.
+    /// * `metadata` - The metadata of each band.
+    pub fn isobands_with_metadata<M: Clone>(
+        &self,
+        values: &[Float],
+        thresholds: &[Float],
+        metadata: &[M],
+    ) -> Result<Vec<(Band, M)>> {
+        if metadata.len() + 1 != thresholds.len() {
+            return Err(new_error(ErrorKind::BadDimension));
+        }
+        let bands = self.isobands(values, thresholds)?;
+        bands
+            .into_iter()
+            .map(|band| {
+                let (i, j) = band.threshold_indices();
+                if i.abs_diff(j) != 1 {
+                    return Err(new_error(ErrorKind::BadThreshold(i.max(j))));
+                }
+                let metadata = metadata[i.min(j)].clone();
+                Ok((band, metadata))
+            })
+            .collect()
+    }
+
+    /// Computes isolines as [`lines`](Self::lines) does, for the thresholds computed
+    /// from the given input `values` by the `generator`.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `generator` - The generator of the thresholds.
+    pub fn lines_auto<G: ThresholdGenerator + ?Sized>(
+        &self,
+        values: &[Float],
+        generator: &G,
+    ) -> Result<Vec<Line>> {
+        check_len(values.len(), self.dx, self.dy)?;
+        self.lines(values, &generator.thresholds(values))
+    }
+
+    /// Computes contours as [`contours`](Self::contours) does, for the thresholds computed
+    /// from the given input `values` by the `generator`.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `generator` - The generator of the thresholds.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, Relative};
+    ///
+    /// let values = [0., 1., 0., 1., 4., 1., 0., 1., 0.];
+    /// let generator = Relative { fractions: vec![0.1, 0.5], reference: None };
+    /// let contours = ContourBuilder::new(3, 3, true).contours_auto(&values, &generator).unwrap();
+    /// assert_eq!(contours[1].threshold(), 2.);
+    /// ```
+    pub fn contours_auto<G: ThresholdGenerator + ?Sized>(
+        &self,
+        values: &[Float],
+        generator: &G,
+    ) -> Result<Vec<Contour>> {
+        check_len(values.len(), self.dx, self.dy)?;
+        self.contours(values, &generator.thresholds(values))
+    }
+
+    /// Computes isobands as [`isobands`](Self::isobands) does, between the thresholds computed
+    /// from the given input `values` by the `generator`.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `generator` - The generator of the thresholds.
+    pub fn isobands_auto<G: ThresholdGenerator + ?Sized>(
+        &self,
+        values: &[Float],
+        generator: &G,
+    ) -> Result<Vec<Band>> {
+        check_len(values.len(), self.dx, self.dy)?;
+        self.isobands(values, &generator.thresholds(values))
+    }
+
+    /// Computes isobands (as [`isobands`](Self::isobands) does) of the difference `a - b`
+    /// of the given input grids `a` and `b`, such as two elevation models or flood extents
+    /// at different dates, labelling each band as a [`Change::Gain`] or a [`Change::Loss`]
+    /// (or as [`Change::Stable`] if it spans `0`).
+    ///
+    /// The cells whose value is NODATA (NaN) in either grid are left uncovered by the bands.
+    ///
+    /// Returns an [`ErrorKind::BadDimension`] error if the length of either grid
+    /// doesn't match the dimensions of the builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The slice of values of the grid after the change.
+    /// * `b` - The slice of values of the grid before the change.
+    /// * `thresholds` - The slice of thresholds values of the difference to be used.
+    ///
+    /// ```
+    /// use contour::{Change, ContourBuilder};
+    ///
+    /// let before = [1., 1., 1., 1., 1., 1., 1., 1., 1.];
+    /// let after = [1., 1., 1., 1., 3., 1., 0., 1., 1.];
+    /// let builder = ContourBuilder::new(3, 3, true);
+    /// let bands = builder
+    ///     .difference_contours(&after, &before, &[-2., -0.5, 0.5, 2.])
+    ///     .unwrap();
+    /// let changes = bands.iter().map(|(_, change)| *change).collect::<Vec<_>>();
+    /// assert_eq!(changes, [Change::Loss, Change::Stable, Change::Gain]);
+    /// ```
+    pub fn difference_contours(
+        &self,
+        a: &[Float],
+        b: &[Float],
+        thresholds: &[Float],
+    ) -> Result<Vec<(Band, Change)>> {
+        check_len(a.len(), self.dx, self.dy)?;
+        check_len(b.len(), self.dx, self.dy)?;
+        let difference = a.iter().zip(b).map(|(a, b)| a - b).collect::<Vec<_>>();
+        let bands = self.isobands(&difference, thresholds)?;
+        Ok(bands
+            .into_iter()
+            .map(|band| {
+                let change = if band.min_v() >= 0. {
+                    Change::Gain
+                } else if band.max_v() <= 0. {
+                    Change::Loss
+                } else {
+                    Change::Stable
+                };
+                (band, change)
+            })
+            .collect())
+    }
+
+    /// Computes the polygons covering the cells of each distinct value of the given input
+    /// `values` (such as the classes of a categorical or land-cover raster), following the
+    /// exact boundaries of the cells, without any interpolation nor smoothing.
+    /// Returns a `Vec` of each distinct value (in their order of first appearance)
+    /// along with the [`MultiPolygon`] covering its cells.
+    ///
+    /// Values that are not equal to themselves (such as NaN) are not polygonized.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    pub fn polygonize<T: PartialEq + Copy>(
+        &self,
+        values: &[T],
+    ) -> Result<Vec<(T, MultiPolygon<Float>)>> {
+        check_len(values.len(), self.dx, self.dy)?;
+        let mut result = polygonize(values, self.dx, self.dy);
+        if self.is_transformed() {
+            result.iter_mut().for_each(|(_, geometry)| {
+                geometry.iter_mut().for_each(|polygon| {
+                    polygon.exterior_mut(|ring| self.transform(ring));
+                    polygon.interiors_mut(|rings| rings.iter_mut().for_each(|r| self.transform(r)));
+                })
+            });
+        }
+        Ok(result)
+    }
+
+    /// Computes the polygons covering the cells of the given binary `mask` whose value
+    /// is `true` (or, for numeric types, non-zero), such as a tilemap or a segmentation mask.
+    ///
+    /// When `pixel_exact` is `true`, the polygons follow the exact boundaries of the cells.
+    /// Otherwise, they are traced using marching squares (cutting the corners of the cells).
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - The slice of values to be used.
+    /// * `pixel_exact` - Whether the polygons follow the exact boundaries of the cells.
+    pub fn mask<T: PartialEq + Default>(
+        &self,
+        mask: &[T],
+        pixel_exact: bool,
+    ) -> Result<MultiPolygon<Float>> {
+        check_len(mask.len(), self.dx, self.dy)?;
+        let zero = T::default();
+        if !pixel_exact {
+            let values = mask
+                .iter()
+                .map(|v| if *v != zero { 1. } else { 0. })
+                .collect::<Vec<Float>>();
+            let mut isoring = self.isoring(self.deadline());
+            let vertices = AtomicUsize::new(0);
+            return Ok(self
+                .contour(&values, 0.5, Float::INFINITY, &mut isoring, &vertices)?
+                .geometry);
+        }
+        let class_of = mask
+            .iter()
+            .map(|v| if *v != zero { Some(0) } else { None })
+            .collect::<Vec<_>>();
+        let mut geometry = trace(&class_of, 1, self.dx, self.dy).remove(0);
+        if self.is_transformed() {
+            geometry.iter_mut().for_each(|polygon| {
+                polygon.exterior_mut(|ring| self.transform(ring));
+                polygon.interiors_mut(|rings| rings.iter_mut().for_each(|r| self.transform(r)));
+            });
+        }
+        Ok(geometry)
+    }
+
+    /// Computes the footprint of the valid data of the given input `values`: the polygons
+    /// covering the cells whose value is not NODATA (NaN), such as for clipping or styling
+    /// the contours, or for the metadata of a dataset.
+    ///
+    /// When `pixel_exact` is `true`, the polygons follow the exact boundaries of the cells.
+    /// Otherwise, they are traced using marching squares, along the same NODATA boundaries
+    /// as the holes of the [`contours`](Self::contours).
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `pixel_exact` - Whether the polygons follow the exact boundaries of the cells.
+    ///
+    /// ```
+    /// use contour::{ContourBuilder, Float};
+    ///
+    /// let mut values = [1.; 16];
+    /// values[5] = Float::NAN;
+    /// let footprint = ContourBuilder::new(4, 4, false).footprint(&values, true).unwrap();
+    /// assert_eq!(footprint.0.len(), 1);
+    /// assert_eq!(footprint.0[0].interiors().len(), 1);
+    /// ```
+    pub fn footprint(&self, values: &[Float], pixel_exact: bool) -> Result<MultiPolygon<Float>> {
+        let valid = values.iter().map(|v| !v.is_nan()).collect::<Vec<_>>();
+        self.mask(&valid, pixel_exact)
+    }
+
+    fn transform(&self, ring: &mut LineString<Float>) {
+        ring.0.iter_mut().for_each(|point| self.to_world(point));
+    }
+}
+
+// Checks that the thresholds are neither NaN, nor infinite (unless `open` bands
+// are allowed), nor duplicated.
+fn validate_thresholds(thresholds: &[Float], open: bool) -> Result<()> {
+    for (i, t) in thresholds.iter().enumerate() {
+        if t.is_nan() || (t.is_infinite() && !open) {
+            return Err(new_error(ErrorKind::BadThreshold(i)));
+        }
+    }
+    let mut order = (0..thresholds.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| thresholds[*a].total_cmp(&thresholds[*b]).then(a.cmp(b)));
+    for w in order.windows(2) {
+        if thresholds[w[0]] == thresholds[w[1]] {
+            return Err(new_error(ErrorKind::BadThreshold(w[1])));
+        }
+    }
+    Ok(())
+}
+
+// Records in the `bands` the indices of their thresholds in the given (validated) `thresholds`.
+fn index_bands(bands: &mut [Band], thresholds: &[Float]) {
+    let index = |v: Float| thresholds.iter().position(|t| *t == v).unwrap_or_default();
+    for band in bands {
+        band.threshold_indices = (index(band.min_v), index(band.max_v));
+    }
+}
+
+// Hashes the given words using 64-bit FNV-1a, which (unlike the hashers of the
+// standard library) is guaranteed to give the same result on every platform and release.
+fn fnv1a(words: &[u64]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for word in words {
+        for byte in word.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+// A ring of a band, along with its area, its bounding box and an interior point.
+type RingSummary = (Ring, f64, Bbox, Option<Pt>);
+
+// Counts the rings enclosing the ring `i` among the given rings (sorted by ascending area):
+// only the rings whose area is not smaller can enclose it, so the search starts at the first
+// of them, and their bounding boxes are compared before their points.
+fn nesting_depth(rings: &[RingSummary], i: usize) -> usize {
+    let (ring, area, bbox, point) = &rings[i];
+    let start = rings.partition_point(|(_, area_test, _, _)| area_test < area);
+    rings[start..]
+        .iter()
+        .enumerate()
+        .filter(|(j, (ring_test, _, bbox_test, _))| {
+            start + j != i && bbox_test.contains(bbox) && ring_contains(ring_test, ring, *point)
+        })
+        .count()
+}
+
+// Whether `ring` is inside `ring_test` (or on its boundary), tested at its interior point
+// if any: the rings of the bands don't cross each other, so any point strictly inside a ring
+// is inside all the rings enclosing it, and outside all the others.
+fn ring_contains(ring_test: &[Pt], ring: &[Pt], point: Option<Pt>) -> bool {
+    match point {
+        Some(point) => contains(ring_test, &[point]) != -1,
+        None => contains(ring_test, ring) != -1,
+    }
 }
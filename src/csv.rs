@@ -0,0 +1,119 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::{Buffer, Float};
+use std::io::BufRead;
+
+/// Loader of grids stored as rectangular CSV (or TSV) files of numbers,
+/// one row of the grid per line, using builder pattern.
+///
+/// The values matching one of the NODATA tokens (such as `NA` or `-9999`) are loaded as NaN.
+///
+/// ```
+/// use contour::CsvReader;
+///
+/// let csv = "x;y;z\n1,5;2;NA\n0;1,5;3\n";
+/// let buffer = CsvReader::new()
+///     .delimiter(';')
+///     .decimal_separator(',')
+///     .header_rows(1)
+///     .nodata(&["NA"])
+///     .read(csv.as_bytes())
+///     .unwrap();
+/// assert_eq!((buffer.dx(), buffer.dy()), (3, 2));
+/// assert_eq!(buffer.get(0, 0), Some(&1.5));
+/// assert!(buffer.get(2, 0).unwrap().is_nan());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsvReader {
+    /// The character separating the values of a line
+    delimiter: char,
+    /// The character separating the integer and fractional parts of the values
+    decimal_separator: char,
+    /// The number of lines to skip at the start of the file
+    header_rows: usize,
+    /// The tokens of the missing values
+    nodata: Vec<String>,
+}
+
+impl Default for CsvReader {
+    fn default() -> Self {
+        CsvReader::new()
+    }
+}
+
+impl CsvReader {
+    /// Constructs a new loader.
+    ///
+    /// By default, the values are separated by commas, use a dot as decimal separator,
+    /// there is no header row and no NODATA token (empty values being always loaded as NaN).
+    pub fn new() -> Self {
+        CsvReader {
+            delimiter: ',',
+            decimal_separator: '.',
+            header_rows: 0,
+            nodata: Vec::new(),
+        }
+    }
+
+    /// Sets the character separating the values of a line (such as `'\t'` for TSV files).
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets the character separating the integer and fractional parts of the values.
+    pub fn decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    /// Sets the number of lines to skip at the start of the file.
+    pub fn header_rows(mut self, header_rows: usize) -> Self {
+        self.header_rows = header_rows;
+        self
+    }
+
+    /// Sets the tokens of the missing values, loaded as NaN.
+    pub fn nodata(mut self, tokens: &[&str]) -> Self {
+        self.nodata = tokens.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    /// Reads the grid from `reader`, the number of columns being the number of values
+    /// of the first line (blank lines being skipped).
+    ///
+    /// Returns an [`ErrorKind::Parse`] error if a value can't be parsed, or an
+    /// [`ErrorKind::BadDimension`] error if the lines don't have the same number of values.
+    pub fn read<R: BufRead>(&self, reader: R) -> Result<Buffer<Float>> {
+        let mut data = Vec::new();
+        let (mut dx, mut dy) = (None, 0);
+        for (i, line) in reader.lines().enumerate().skip(self.header_rows) {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let len = data.len();
+            for token in line.split(self.delimiter) {
+                data.push(
+                    self.parse(token.trim())
+                        .ok_or_else(|| new_error(ErrorKind::Parse(i + 1)))?,
+                );
+            }
+            if *dx.get_or_insert(data.len() - len) != data.len() - len {
+                return Err(new_error(ErrorKind::BadDimension));
+            }
+            dy += 1;
+        }
+        Buffer::new(data, dx.unwrap_or(0), dy)
+    }
+
+    fn parse(&self, token: &str) -> Option<Float> {
+        if token.is_empty() || self.nodata.iter().any(|t| t == token) {
+            return Some(Float::NAN);
+        }
+        if self.decimal_separator == '.' {
+            token.parse().ok()
+        } else {
+            token.replace(self.decimal_separator, ".").parse().ok()
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use crate::buffer::grid_dimensions;
+use crate::error::{new_error, ErrorKind, Result};
+use crate::{Buffer, Contour, ContourBuilder, Float, Pt};
+use geo_types::Rect;
+
+/// Density contours generator, using builder pattern, to compute
+/// contours of the estimated density of a set of points
+/// (ported from d3-contour's `contourDensity`).
+///
+/// The points are first splatted onto a grid of cells of `cell_size` pixels
+/// and this grid is then blurred (using three successive box blurs, approximating
+/// a gaussian kernel of the given `bandwidth`) before being contoured.
+///
+/// ```
+/// use contour::DensityBuilder;
+/// use geo_types::coord;
+///
+/// let points = vec![coord! { x: 50., y: 50. }, coord! { x: 52., y: 49. }, coord! { x: 48., y: 53. }];
+/// let res = DensityBuilder::new(100, 100)
+///     .bandwidth(10.)
+///     .contours(&points, &[0.001])
+///     .unwrap();
+/// assert_eq!(res[0].geometry().0.len(), 1);
+/// ```
+pub struct DensityBuilder {
+    /// The width of the area covered by the points
+    dx: usize,
+    /// The height of the area covered by the points
+    dy: usize,
+    /// The cell size, as a power of two exponent
+    k: u32,
+    /// The radius of the box blur
+    r: Float,
+}
+
+impl DensityBuilder {
+    /// Constructs a new density contours generator for points located
+    /// within `[0, dx]` * `[0, dy]`.
+    ///
+    /// By default, the `cell_size` is set to `4` and the `bandwidth` to `20.4939...`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dx` - The width of the area covered by the points.
+    /// * `dy` - The height of the area covered by the points.
+    pub fn new(dx: usize, dy: usize) -> Self {
+        DensityBuilder {
+            dx,
+            dy,
+            k: 2,
+            r: 20.,
+        }
+    }
+
+    /// Sets the size of the cells of the underlying grid (floored to the nearest power of two).
+    pub fn cell_size(mut self, cell_size: usize) -> Self {
+        self.k = cell_size.max(1).ilog2();
+        self
+    }
+
+    /// Sets the standard deviation of the gaussian kernel used to estimate the density.
+    pub fn bandwidth(mut self, bandwidth: impl Into<Float>) -> Self {
+        let bandwidth = bandwidth.into();
+        self.r = ((4. * bandwidth * bandwidth + 1.).sqrt() - 1.) / 2.;
+        self
+    }
+
+    /// Estimates a bandwidth suited to the given `points` using Scott's rule of thumb:
+    /// `σ * n^(-1/6)`, where `σ` is the standard deviation of the coordinates of the points
+    /// (pooled over both axes, as the kernel is isotropic) and `n` their number.
+    ///
+    /// Returns `0` if there are fewer than two points.
+    ///
+    /// ```
+    /// use contour::DensityBuilder;
+    /// use geo_types::coord;
+    ///
+    /// let points = vec![coord! { x: 40., y: 50. }, coord! { x: 60., y: 45. }, coord! { x: 50., y: 55. }];
+    /// let res = DensityBuilder::new(100, 100)
+    ///     .bandwidth(DensityBuilder::scott_bandwidth(&points))
+    ///     .contours(&points, &[0.001])
+    ///     .unwrap();
+    /// ```
+    pub fn scott_bandwidth(points: &[Pt]) -> Float {
+        if points.len() < 2 {
+            return 0.;
+        }
+        let n = points.len() as Float;
+        pooled_deviation(points) * n.powf(-1. / 6.)
+    }
+
+    /// Estimates a bandwidth suited to the given `points` using Silverman's rule of thumb:
+    /// `0.9 * min(σ, IQR / 1.34) * n^(-1/5)`, where `σ` is the standard deviation
+    /// of the coordinates of the points and `IQR` their interquartile range (both pooled
+    /// over both axes) and `n` their number, which is less sensitive to outliers
+    /// than [`scott_bandwidth`](Self::scott_bandwidth).
+    ///
+    /// Returns `0` if there are fewer than two points.
+    pub fn silverman_bandwidth(points: &[Pt]) -> Float {
+        if points.len() < 2 {
+            return 0.;
+        }
+        let n = points.len() as Float;
+        let iqr = (interquartile_range(points.iter().map(|p| p.x))
+            + interquartile_range(points.iter().map(|p| p.y)))
+            / 2.;
+        let spread = pooled_deviation(points);
+        let spread = if iqr > 0. {
+            spread.min(iqr / 1.34)
+        } else {
+            spread
+        };
+        0.9 * spread * n.powf(-0.2)
+    }
+
+    fn offset(&self) -> Float {
+        self.r * 3.
+    }
+
+    /// Returns the number of columns and rows of the underlying grid.
+    pub fn dimensions(&self) -> (usize, usize) {
+        let o = self.offset();
+        (
+            ((self.dx as Float + o * 2.) as usize) >> self.k,
+            ((self.dy as Float + o * 2.) as usize) >> self.k,
+        )
+    }
+
+    /// Computes the (unscaled) density grid for the given `points`, with the
+    /// dimensions returned by [`dimensions`](Self::dimensions).
+    pub fn grid(&self, points: &[Pt]) -> Vec<Float> {
+        self.splat(points.iter().map(|point| (point, 1.)))
+    }
+
+    /// Computes the (unscaled) density grid for the given `points`, each point
+    /// contributing to the density according to its weight (such as the magnitude
+    /// of an event or the population of a place) instead of being counted once.
+    ///
+    /// Returns an error if `points` and `weights` don't have the same length.
+    pub fn weighted_grid(&self, points: &[Pt], weights: &[Float]) -> Result<Vec<Float>> {
+        if points.len() != weights.len() {
+            return Err(new_error(ErrorKind::BadDimension));
+        }
+        Ok(self.splat(points.iter().zip(weights.iter().copied())))
+    }
+
+    fn splat<'a, I: Iterator<Item = (&'a Pt, Float)>>(&self, points: I) -> Vec<Float> {
+        let (n, m) = self.dimensions();
+        let mut values = vec![0.; n * m];
+        let o = self.offset();
+        let pow2k = (1u64 << self.k) as Float;
+
+        for (point, w) in points {
+            let xi = (point.x + o) / pow2k;
+            let yi = (point.y + o) / pow2k;
+            if xi >= 0. && xi < n as Float && yi >= 0. && yi < m as Float {
+                let x0 = xi.floor() as usize;
+                let y0 = yi.floor() as usize;
+                let xt = xi - x0 as Float - 0.5;
+                let yt = yi - y0 as Float - 0.5;
+                values[x0 + y0 * n] += w * (1. - xt) * (1. - yt);
+                if x0 + 1 < n {
+                    values[x0 + 1 + y0 * n] += w * xt * (1. - yt);
+                }
+                if y0 + 1 < m {
+                    values[x0 + (y0 + 1) * n] += w * (1. - xt) * yt;
+                    if x0 + 1 < n {
+                        values[x0 + 1 + (y0 + 1) * n] += w * xt * yt;
+                    }
+                }
+            }
+        }
+
+        blur2(&mut values, n, m, self.r / pow2k);
+        values
+    }
+
+    /// Computes density contours for the given `points` and `thresholds`.
+    /// Returns a `Vec` of [`Contour`] (that can easily be transformed
+    /// to GeoJSON Features of MultiPolygon).
+    ///
+    /// Thresholds are expressed in points per square pixel.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The points whose density is to be estimated.
+    /// * `thresholds` - The slice of thresholds values to be used.
+    pub fn contours(&self, points: &[Pt], thresholds: &[Float]) -> Result<Vec<Contour>> {
+        self.contour_grid(self.grid(points), thresholds)
+    }
+
+    /// Computes density contours for the given weighted `points` and `thresholds`
+    /// (see [`weighted_grid`](Self::weighted_grid)).
+    /// Returns a `Vec` of [`Contour`] (that can easily be transformed
+    /// to GeoJSON Features of MultiPolygon).
+    ///
+    /// Thresholds are expressed in weight per square pixel.
+    /// Returns an error if `points` and `weights` don't have the same length.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The points whose density is to be estimated.
+    /// * `weights` - The weight of each point.
+    /// * `thresholds` - The slice of thresholds values to be used.
+    pub fn weighted_contours(
+        &self,
+        points: &[Pt],
+        weights: &[Float],
+        thresholds: &[Float],
+    ) -> Result<Vec<Contour>> {
+        self.contour_grid(self.weighted_grid(points, weights)?, thresholds)
+    }
+
+    fn contour_grid(&self, values: Vec<Float>, thresholds: &[Float]) -> Result<Vec<Contour>> {
+        let (n, m) = self.dimensions();
+        let pow2k = (1u64 << self.k) as Float;
+        let pow4k = pow2k * pow2k;
+        let o = self.offset();
+        let scaled = thresholds.iter().map(|t| t * pow4k).collect::<Vec<_>>();
+        let contours = ContourBuilder::new(n, m, true)
+            .x_step(pow2k)
+            .y_step(pow2k)
+            .x_origin(-o)
+            .y_origin(-o)
+            .contours(&values, &scaled)?;
+        Ok(contours
+            .into_iter()
+            .map(|mut contour| {
+                contour.threshold /= pow4k;
+                contour
+            })
+            .collect())
+    }
+}
+
+// The standard deviation of the coordinates of the points, pooled over both axes.
+fn pooled_deviation(points: &[Pt]) -> Float {
+    let n = points.len() as Float;
+    let (mx, my) = points
+        .iter()
+        .fold((0., 0.), |(x, y), p| (x + p.x / n, y + p.y / n));
+    let ss = points
+        .iter()
+        .map(|p| (p.x - mx).powi(2) + (p.y - my).powi(2))
+        .sum::<Float>();
+    (ss / (2. * (n - 1.))).sqrt()
+}
+
+// The interquartile range of the values (using linear interpolation between the closest ranks).
+fn interquartile_range<I: Iterator<Item = Float>>(values: I) -> Float {
+    let mut values = values.collect::<Vec<_>>();
+    values.sort_unstable_by(|a, b| a.total_cmp(b));
+    let quantile = |q: Float| {
+        let i = q * (values.len() - 1) as Float;
+        let (i0, t) = (i.floor() as usize, i.fract());
+        let i1 = (i0 + 1).min(values.len() - 1);
+        values[i0] * (1. - t) + values[i1] * t
+    };
+    quantile(0.75) - quantile(0.25)
+}
+
+/// Counts the `points` falling in each cell of a grid covering `extent`
+/// with square cells of `cell_size`.
+///
+/// This is the non-smoothed counterpart of [`DensityBuilder`]: the resulting
+/// grid can be contoured using a [`ContourBuilder`] whose origin is the minimum
+/// coordinate of `extent` and whose steps are `cell_size`. Points outside
+/// of `extent` are ignored.
+///
+/// Returns an [`ErrorKind::Overflow`] error if the grid has more cells than
+/// the number of values addressable on the target.
+///
+/// ```
+/// use contour::{histogram, ContourBuilder, Float};
+/// use geo_types::{coord, Rect};
+///
+/// let points = vec![coord! { x: 1.5, y: 1.5 }, coord! { x: 1.6, y: 1.2 }, coord! { x: 8., y: 3. }];
+/// let extent = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 10., y: 5. });
+/// let counts = histogram(&points, extent, 1.).unwrap();
+/// assert_eq!(counts.get(1, 1), Some(&2));
+///
+/// let bands = ContourBuilder::new(counts.dx(), counts.dy(), false)
+///     .isobands(counts.map(|&c| c as Float).data(), &[0.5, 1.5, 2.5])
+///     .unwrap();
+/// ```
+pub fn histogram(points: &[Pt], extent: Rect<Float>, cell_size: Float) -> Result<Buffer<u32>> {
+    let (dx, dy) = grid_dimensions(&extent, cell_size);
+    let mut buffer = Buffer::filled(0, dx, dy)?;
+    let data = buffer.data_mut();
+    for point in points {
+        if let Some(ix) = histogram_index(point, &extent, cell_size, dx, dy) {
+            data[ix] += 1;
+        }
+    }
+    Ok(buffer)
+}
+
+/// Sums the `weights` of the `points` falling in each cell of a grid covering
+/// `extent` with square cells of `cell_size` (see [`histogram`]).
+///
+/// Returns an error if `points` and `weights` don't have the same length,
+/// or if the grid has more cells than the number of values addressable on the target.
+pub fn weighted_histogram(
+    points: &[Pt],
+    weights: &[Float],
+    extent: Rect<Float>,
+    cell_size: Float,
+) -> Result<Buffer<Float>> {
+    if points.len() != weights.len() {
+        return Err(new_error(ErrorKind::BadDimension));
+    }
+    let (dx, dy) = grid_dimensions(&extent, cell_size);
+    let mut buffer = Buffer::filled(0., dx, dy)?;
+    let data = buffer.data_mut();
+    for (point, weight) in points.iter().zip(weights) {
+        if let Some(ix) = histogram_index(point, &extent, cell_size, dx, dy) {
+            data[ix] += weight;
+        }
+    }
+    Ok(buffer)
+}
+
+fn histogram_index(
+    point: &Pt,
+    extent: &Rect<Float>,
+    cell_size: Float,
+    dx: usize,
+    dy: usize,
+) -> Option<usize> {
+    let (min, max) = (extent.min(), extent.max());
+    if point.x < min.x || point.x > max.x || point.y < min.y || point.y > max.y {
+        return None;
+    }
+    let x = (((point.x - min.x) / cell_size) as usize).min(dx - 1);
+    let y = (((point.y - min.y) / cell_size) as usize).min(dy - 1);
+    Some(y * dx + x)
+}
+
+// Blurs the values using three successive (horizontal, then vertical) box blurs
+// of the given (possibly fractional) `radius`, as done by d3-array's `blur2`.
+fn blur2(values: &mut [Float], width: usize, height: usize, radius: Float) {
+    if radius.is_nan() || radius <= 0. || width == 0 || height == 0 {
+        return;
+    }
+    let mut temp = values.to_vec();
+    for _ in 0..3 {
+        for y in 0..height {
+            let start = y * width;
+            blur(&mut temp, values, start, width, 1, radius);
+            values[start..start + width].copy_from_slice(&temp[start..start + width]);
+        }
+    }
+    for _ in 0..3 {
+        for x in 0..width {
+            blur(&mut temp, values, x, height, width, radius);
+            for y in 0..height {
+                values[x + y * width] = temp[x + y * width];
+            }
+        }
+    }
+}
+
+// Writes in `target` the box blur of the `len` values of `source` starting
+// at `start` and separated by `step` (the values past the edges being
+// clamped to the values on the edges).
+fn blur(
+    target: &mut [Float],
+    source: &[Float],
+    start: usize,
+    len: usize,
+    step: usize,
+    radius: Float,
+) {
+    let radius0 = radius.floor();
+    let t = radius - radius0;
+    let w = 2. * radius + 1.;
+    let r0 = radius0 as i64;
+    let last = len as i64 - 1;
+    let at = |i: i64| source[start + (i.clamp(0, last) as usize) * step];
+
+    let mut sum = radius0 * at(0);
+    for i in 0..r0 {
+        sum += at(i);
+    }
+    for i in 0..len as i64 {
+        sum += at(i + r0);
+        target[start + i as usize * step] = (sum + t * (at(i - r0 - 1) + at(i + r0 + 1))) / w;
+        sum -= at(i - r0);
+    }
+}
@@ -0,0 +1,392 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::{Buffer, Float, Pt};
+use geo_types::Rect;
+
+/// Computes the magnitude of the gradient of the values of the given `grid`, using finite
+/// differences (centered inside the grid, one-sided on its borders), such as to contour
+/// the steepness of a surface or the strength of the edges of an image.
+///
+/// The differences are divided by the horizontal and vertical steps of the grid
+/// (`x_step` and `y_step`, as given to the [`ContourBuilder`](crate::ContourBuilder)),
+/// and the NODATA values (NaN) propagate to their neighbours.
+///
+/// ```
+/// use contour::{gradient_magnitude, Buffer};
+///
+/// let grid = Buffer::new(vec![0., 1., 2., 0., 1., 2.], 3, 2).unwrap();
+/// let magnitude = gradient_magnitude(&grid, 0.5, 1.).unwrap();
+/// assert_eq!(magnitude.data(), &[2.; 6]);
+/// ```
+pub fn gradient_magnitude(
+    grid: &Buffer<Float>,
+    x_step: Float,
+    y_step: Float,
+) -> Result<Buffer<Float>> {
+    let (dx, dy) = (grid.dx(), grid.dy());
+    let values = grid.data();
+    let mut data = Vec::with_capacity(values.len());
+    for y in 0..dy {
+        for x in 0..dx {
+            let gx = difference(values, y * dx, 1, x, dx) / x_step;
+            let gy = difference(values, x, dx, y, dy) / y_step;
+            data.push((gx * gx + gy * gy).sqrt());
+        }
+    }
+    Buffer::new(data, dx, dy)
+}
+
+/// The unit of the slopes computed by [`slope`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlopeUnit {
+    /// The angle of the slope, in degrees (from `0` to `90`).
+    Degrees,
+    /// The rise over the run, in percent (`100` for an angle of 45 degrees).
+    Percent,
+}
+
+/// Computes the slope of the given digital elevation model `grid`, using the method of Horn
+/// (weighting the differences of the 8 neighbours of each cell, the values being extrapolated
+/// linearly beyond the borders of the grid), such as to contour the classes of slope of a terrain.
+///
+/// `x_step` and `y_step` are the horizontal and vertical steps of the grid
+/// (as given to the [`ContourBuilder`](crate::ContourBuilder)), in the unit of the elevations.
+///
+/// ```
+/// use contour::{slope, Buffer, SlopeUnit};
+///
+/// let grid = Buffer::new(vec![0., 10., 20., 0., 10., 20.], 3, 2).unwrap();
+/// let percent = slope(&grid, 10., 10., SlopeUnit::Percent).unwrap();
+/// assert_eq!(percent.get(1, 0), Some(&100.));
+/// let degrees = slope(&grid, 10., 10., SlopeUnit::Degrees).unwrap();
+/// assert!((degrees.get(1, 0).unwrap() - 45.).abs() < 1e-9);
+/// ```
+pub fn slope(
+    grid: &Buffer<Float>,
+    x_step: Float,
+    y_step: Float,
+    unit: SlopeUnit,
+) -> Result<Buffer<Float>> {
+    horn(grid, x_step, y_step, |gx, gy| {
+        let rise = (gx * gx + gy * gy).sqrt();
+        match unit {
+            SlopeUnit::Degrees => rise.atan().to_degrees(),
+            SlopeUnit::Percent => rise * 100.,
+        }
+    })
+}
+
+/// Computes the aspect of the given digital elevation model `grid` (the direction its slope
+/// faces), using the method of Horn (as [`slope`]), such as to polygonize the sectors of
+/// aspect of a terrain.
+///
+/// The aspect is given in degrees clockwise from the north (the direction of increasing `y`
+/// coordinates), from `0` to `360`, and is NaN for flat cells. `x_step` and `y_step` are the
+/// horizontal and vertical steps of the grid (as given to the
+/// [`ContourBuilder`](crate::ContourBuilder)), whose signs orient the aspect.
+///
+/// ```
+/// use contour::{aspect, Buffer};
+///
+/// // Rising towards the east, the terrain faces west
+/// let grid = Buffer::new(vec![0., 10., 20., 0., 10., 20.], 3, 2).unwrap();
+/// let aspect = aspect(&grid, 10., -10.).unwrap();
+/// assert_eq!(aspect.get(1, 1), Some(&270.));
+/// ```
+pub fn aspect(grid: &Buffer<Float>, x_step: Float, y_step: Float) -> Result<Buffer<Float>> {
+    horn(grid, x_step, y_step, |gx, gy| {
+        if gx == 0. && gy == 0. {
+            return Float::NAN;
+        }
+        // The direction of the steepest descent, clockwise from the north
+        let angle = (-gx).atan2(-gy).to_degrees();
+        if angle < 0. {
+            angle + 360.
+        } else {
+            angle
+        }
+    })
+}
+
+/// The interpolation of the values used by [`resample`] and [`warp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resampling {
+    /// Value of the nearest cell.
+    Nearest,
+    /// Bilinear interpolation of the 4 nearest values.
+    Bilinear,
+    /// Bicubic (Catmull-Rom) interpolation of the 16 nearest values,
+    /// which gives smoother contours than the bilinear one.
+    Bicubic,
+}
+
+/// Resamples the given `grid` to a grid with `dx` * `dy` dimension covering the same extent,
+/// such as to upscale a coarse model output before contouring it for presentation maps.
+///
+/// The center of each cell of the resampled grid is interpolated from the values of the centers
+/// of the cells of `grid` (the values being clamped beyond its borders). The steps of the
+/// resampled grid are the steps of `grid` multiplied by `grid.dx() / dx` and `grid.dy() / dy`,
+/// with the same origin.
+///
+/// Returns an [`ErrorKind::BadDimension`] error if a dimension of either grid is `0`.
+///
+/// ```
+/// use contour::{resample, Buffer, Resampling};
+///
+/// let grid = Buffer::new(vec![0., 1., 0., 1., 2., 1., 0., 1., 0.], 3, 3).unwrap();
+/// let resampled = resample(&grid, 9, 9, Resampling::Bicubic).unwrap();
+/// assert_eq!(resampled.get(4, 4), Some(&2.));
+/// ```
+pub fn resample(
+    grid: &Buffer<Float>,
+    dx: usize,
+    dy: usize,
+    method: Resampling,
+) -> Result<Buffer<Float>> {
+    let (sdx, sdy) = (grid.dx(), grid.dy());
+    if dx == 0 || dy == 0 || sdx == 0 || sdy == 0 {
+        return Err(new_error(ErrorKind::BadDimension));
+    }
+    let mut data = Vec::with_capacity(dx * dy);
+    for j in 0..dy {
+        let v = (j as Float + 0.5) * sdy as Float / dy as Float - 0.5;
+        for i in 0..dx {
+            let u = (i as Float + 0.5) * sdx as Float / dx as Float - 0.5;
+            data.push(sample(grid, u, v, method));
+        }
+    }
+    Buffer::new(data, dx, dy)
+}
+
+/// Warps the given `grid`, covering the `source` extent, to a grid with `dx` * `dy` dimension
+/// covering the `target` extent in another coordinate reference system, such as to contour
+/// longitude/latitude model data directly in the Web Mercator space of map tiles.
+///
+/// The center of each cell of the warped grid is mapped to the coordinate reference system of
+/// `grid` by the `inverse` projection (such as one of the [`proj`](https://docs.rs/proj) crate),
+/// then interpolated from the values of the centers of the cells of `grid`.
+/// The cells mapped outside of the `source` extent, or that can't be projected
+/// (`inverse` returning `None`), are set to NaN (NODATA).
+///
+/// The first row of both grids is along the minimum `y` coordinate of their extent.
+/// Returns an [`ErrorKind::BadDimension`] error if a dimension of either grid is `0`.
+///
+/// ```
+/// use contour::{warp, Buffer, Pt, Resampling};
+/// use geo_types::{coord, Rect};
+///
+/// let grid = Buffer::new(vec![0., 1., 2., 3.], 2, 2).unwrap();
+/// let source = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 2., y: 2. });
+/// // The target coordinates are twice the source ones
+/// let target = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 8., y: 4. });
+/// let inverse = |p: Pt| Some(Pt { x: p.x / 2., y: p.y / 2. });
+/// let warped = warp(&grid, source, target, 4, 2, Resampling::Nearest, inverse).unwrap();
+/// assert_eq!(warped.get(1, 1), Some(&3.));
+/// assert!(warped.get(2, 0).unwrap().is_nan());
+/// ```
+pub fn warp<F: Fn(Pt) -> Option<Pt>>(
+    grid: &Buffer<Float>,
+    source: Rect<Float>,
+    target: Rect<Float>,
+    dx: usize,
+    dy: usize,
+    method: Resampling,
+    inverse: F,
+) -> Result<Buffer<Float>> {
+    let (sdx, sdy) = (grid.dx(), grid.dy());
+    if dx == 0 || dy == 0 || sdx == 0 || sdy == 0 {
+        return Err(new_error(ErrorKind::BadDimension));
+    }
+    let (x_step, y_step) = (target.width() / dx as Float, target.height() / dy as Float);
+    let mut data = Vec::with_capacity(dx * dy);
+    for j in 0..dy {
+        for i in 0..dx {
+            let p = Pt {
+                x: target.min().x + (i as Float + 0.5) * x_step,
+                y: target.min().y + (j as Float + 0.5) * y_step,
+            };
+            data.push(match inverse(p) {
+                Some(q)
+                    if (source.min().x..=source.max().x).contains(&q.x)
+                        && (source.min().y..=source.max().y).contains(&q.y) =>
+                {
+                    let u = (q.x - source.min().x) / source.width() * sdx as Float - 0.5;
+                    let v = (q.y - source.min().y) / source.height() * sdy as Float - 0.5;
+                    sample(grid, u, v, method)
+                }
+                _ => Float::NAN,
+            });
+        }
+    }
+    Buffer::new(data, dx, dy)
+}
+
+/// How the values beyond the borders of a grid are given by [`pad`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Padding {
+    /// The values are the given constant (such as a background value,
+    /// or NaN for NODATA), which closes the contours along the border.
+    Constant(Float),
+    /// The values of the border are repeated.
+    Replicate,
+    /// The values are mirrored across the border (which is not repeated).
+    Mirror,
+}
+
+/// Extends the given `grid` by `n` cells beyond each of its borders, the values of the new
+/// cells being given by `mode`, such as to control how the contours behave at the edges of a
+/// dataset (forcing their closure at a background value or extrapolating the edges).
+///
+/// The padded grid has `grid.dx() + 2 * n` columns and `grid.dy() + 2 * n` rows, so its origin
+/// has to be moved by `n` steps (`x_origin - n * x_step` and `y_origin - n * y_step`) for the
+/// contours to keep their coordinates.
+///
+/// Returns an [`ErrorKind::BadDimension`] error if a dimension of `grid` is `0`.
+///
+/// ```
+/// use contour::{pad, Buffer, Padding};
+///
+/// let grid = Buffer::new(vec![1., 2., 3.], 3, 1).unwrap();
+/// let padded = pad(&grid, 2, Padding::Mirror).unwrap();
+/// assert_eq!((padded.dx(), padded.dy()), (7, 5));
+/// assert_eq!(&padded.data()[14..21], &[3., 2., 1., 2., 3., 2., 1.]);
+/// let padded = pad(&grid, 1, Padding::Replicate).unwrap();
+/// assert_eq!(&padded.data()[..5], &[1., 1., 2., 3., 3.]);
+/// let padded = pad(&grid, 1, Padding::Constant(0.)).unwrap();
+/// assert_eq!(&padded.data()[5..10], &[0., 1., 2., 3., 0.]);
+/// ```
+pub fn pad(grid: &Buffer<Float>, n: usize, mode: Padding) -> Result<Buffer<Float>> {
+    let (dx, dy) = (grid.dx(), grid.dy());
+    if dx == 0 || dy == 0 {
+        return Err(new_error(ErrorKind::BadDimension));
+    }
+    // The index of the value mirroring (or replicating) the index `i` of the `len` values
+    let index = |i: isize, len: usize| -> Option<usize> {
+        let len = len as isize;
+        match mode {
+            Padding::Constant(_) => (0..len).contains(&i).then_some(i as usize),
+            Padding::Replicate => Some(i.clamp(0, len - 1) as usize),
+            Padding::Mirror if len == 1 => Some(0),
+            Padding::Mirror => {
+                let period = 2 * (len - 1);
+                let i = i.rem_euclid(period);
+                Some(if i < len { i } else { period - i } as usize)
+            }
+        }
+    };
+    // Only the constant padding leaves the cells beyond the borders without an index
+    let fill = match mode {
+        Padding::Constant(value) => value,
+        _ => Float::NAN,
+    };
+    let (pdx, pdy) = (dx + 2 * n, dy + 2 * n);
+    let mut data = Vec::with_capacity(pdx * pdy);
+    for y in 0..pdy {
+        for x in 0..pdx {
+            let i = index(x as isize - n as isize, dx);
+            let j = index(y as isize - n as isize, dy);
+            data.push(match (i, j) {
+                (Some(i), Some(j)) => grid.data()[j * dx + i],
+                _ => fill,
+            });
+        }
+    }
+    Buffer::new(data, pdx, pdy)
+}
+
+// Interpolates the values of the grid at the (fractional) column `u` and row `v`,
+// the values being clamped beyond its borders.
+fn sample(grid: &Buffer<Float>, u: Float, v: Float, method: Resampling) -> Float {
+    let (dx, dy) = (grid.dx() as isize, grid.dy() as isize);
+    let value = |x: isize, y: isize| {
+        let (x, y) = (x.clamp(0, dx - 1), y.clamp(0, dy - 1));
+        grid.data()[(y * dx + x) as usize]
+    };
+    let (x, y) = (u.floor() as isize, v.floor() as isize);
+    let (tx, ty) = (u - u.floor(), v - v.floor());
+    match method {
+        Resampling::Nearest => value(u.round() as isize, v.round() as isize),
+        Resampling::Bilinear => {
+            let top = value(x, y) * (1. - tx) + value(x + 1, y) * tx;
+            let bottom = value(x, y + 1) * (1. - tx) + value(x + 1, y + 1) * tx;
+            top * (1. - ty) + bottom * ty
+        }
+        Resampling::Bicubic => {
+            let row = |y| {
+                cubic(
+                    [
+                        value(x - 1, y),
+                        value(x, y),
+                        value(x + 1, y),
+                        value(x + 2, y),
+                    ],
+                    tx,
+                )
+            };
+            cubic([row(y - 1), row(y), row(y + 1), row(y + 2)], ty)
+        }
+    }
+}
+
+// Interpolates between `p[1]` and `p[2]` at `t` using a Catmull-Rom spline.
+fn cubic(p: [Float; 4], t: Float) -> Float {
+    p[1] + 0.5
+        * t
+        * (p[2] - p[0]
+            + t * (2. * p[0] - 5. * p[1] + 4. * p[2] - p[3]
+                + t * (3. * (p[1] - p[2]) + p[3] - p[0])))
+}
+
+// Computes `f` of the gradient of the values of the grid (in world coordinates) using
+// the 3x3 weighted differences of Horn, the cells outside of the grid being extrapolated.
+fn horn<F: Fn(Float, Float) -> Float>(
+    grid: &Buffer<Float>,
+    x_step: Float,
+    y_step: Float,
+    f: F,
+) -> Result<Buffer<Float>> {
+    let (dx, dy) = (grid.dx(), grid.dy());
+    let mut data = Vec::with_capacity(dx * dy);
+    for y in 0..dy as isize {
+        for x in 0..dx as isize {
+            let v = |ox, oy| extrapolated(grid, x + ox, y + oy);
+            let gx = ((v(1, -1) + 2. * v(1, 0) + v(1, 1)) - (v(-1, -1) + 2. * v(-1, 0) + v(-1, 1)))
+                / (8. * x_step);
+            let gy = ((v(-1, 1) + 2. * v(0, 1) + v(1, 1)) - (v(-1, -1) + 2. * v(0, -1) + v(1, -1)))
+                / (8. * y_step);
+            data.push(f(gx, gy));
+        }
+    }
+    Buffer::new(data, dx, dy)
+}
+
+// Returns the value at column `x` and row `y` of the grid, extrapolated linearly
+// from the two nearest values (or repeated, if the grid is one cell wide)
+// one cell beyond the borders of the grid.
+fn extrapolated(grid: &Buffer<Float>, x: isize, y: isize) -> Float {
+    let (dx, dy) = (grid.dx() as isize, grid.dy() as isize);
+    let inner = |i: isize, len: isize| match i {
+        -1 if len > 1 => Some((0, 1)),
+        i if i == len && len > 1 => Some((len - 1, len - 2)),
+        _ => None,
+    };
+    if let Some((a, b)) = inner(x, dx) {
+        2. * extrapolated(grid, a, y) - extrapolated(grid, b, y)
+    } else if let Some((a, b)) = inner(y, dy) {
+        2. * extrapolated(grid, x, a) - extrapolated(grid, x, b)
+    } else {
+        let (x, y) = (x.clamp(0, dx - 1), y.clamp(0, dy - 1));
+        grid.data()[(y * dx + x) as usize]
+    }
+}
+
+// Returns the finite difference (per cell) at the index `i` of the `len` values
+// found from `start` every `stride` values.
+fn difference(values: &[Float], start: usize, stride: usize, i: usize, len: usize) -> Float {
+    let value = |i: usize| values[start + i * stride];
+    match (i.checked_sub(1), (i + 1 < len).then_some(i + 1)) {
+        (Some(prev), Some(next)) => (value(next) - value(prev)) / 2.,
+        (Some(prev), None) => value(i) - value(prev),
+        (None, Some(next)) => value(next) - value(i),
+        (None, None) => 0.,
+    }
+}
@@ -0,0 +1,159 @@
+use crate::buffer::grid_dimensions;
+use crate::error::Result;
+use crate::{Buffer, Contour, ContourBuilder, Float, Pt};
+use geo_types::{Geometry, Rect};
+
+/// Computes the distance from the center of each cell of a grid covering `extent`
+/// with square cells of `cell_size` to the nearest of the given `geometries`.
+///
+/// The distance is signed: it is negative inside the polygons (the opposite of the distance
+/// to their boundary), so that contouring the grid gives smooth buffers on both sides
+/// of their boundary. The cells are set to infinity when there is no geometry.
+///
+/// The distances are computed exhaustively (for each cell, to each segment of the geometries),
+/// which is best suited to moderate numbers of vertices.
+pub fn distance_grid(
+    geometries: &[Geometry<Float>],
+    extent: Rect<Float>,
+    cell_size: Float,
+) -> Result<Buffer<Float>> {
+    let mut shapes = Shapes::default();
+    geometries.iter().for_each(|g| shapes.add(g));
+    let (dx, dy) = grid_dimensions(&extent, cell_size);
+    let min = extent.min();
+    let mut data = Vec::with_capacity(dx * dy);
+    for y in 0..dy {
+        for x in 0..dx {
+            data.push(shapes.distance(&Pt {
+                x: min.x + (x as Float + 0.5) * cell_size,
+                y: min.y + (y as Float + 0.5) * cell_size,
+            }));
+        }
+    }
+    Buffer::new(data, dx, dy)
+}
+
+/// Computes the buffers of the given `geometries` at each of the given `distances`,
+/// by contouring their distance grid (see [`distance_grid`]).
+/// Returns a `Vec` of [`Contour`] whose threshold is the distance of the buffer.
+///
+/// The `extent` must cover the geometries along with the largest distance, otherwise the buffers
+/// are cut at its border. Negative distances give buffers shrunk inside the polygons.
+///
+/// ```
+/// use contour::distance_buffers;
+/// use geo_types::{coord, point, Geometry, Rect};
+///
+/// let geometries = vec![Geometry::Point(point! { x: 5., y: 5. })];
+/// let extent = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 10., y: 10. });
+/// let buffers = distance_buffers(&geometries, &[1., 2.], extent, 0.25).unwrap();
+/// assert_eq!(buffers[1].threshold(), 2.);
+/// ```
+pub fn distance_buffers(
+    geometries: &[Geometry<Float>],
+    distances: &[Float],
+    extent: Rect<Float>,
+    cell_size: Float,
+) -> Result<Vec<Contour>> {
+    let grid = distance_grid(geometries, extent, cell_size)?;
+    // The values lower than or equal to a distance are inside its buffer
+    let values = grid.data().iter().map(|d| -d).collect::<Vec<_>>();
+    let thresholds = distances.iter().map(|d| -d).collect::<Vec<_>>();
+    let min = extent.min();
+    let contours = ContourBuilder::new(grid.dx(), grid.dy(), true)
+        .x_origin(min.x)
+        .y_origin(min.y)
+        .x_step(cell_size)
+        .y_step(cell_size)
+        .contours(&values, &thresholds)?;
+    Ok(contours
+        .into_iter()
+        .zip(distances)
+        .map(|(contour, distance)| Contour {
+            threshold: *distance,
+            ..contour
+        })
+        .collect())
+}
+
+// The segments of the geometries, along with the rings of their polygons.
+#[derive(Default)]
+struct Shapes {
+    segments: Vec<(Pt, Pt)>,
+    rings: Vec<Vec<Pt>>,
+}
+
+impl Shapes {
+    fn add(&mut self, geometry: &Geometry<Float>) {
+        match geometry {
+            Geometry::Point(p) => self.segments.push((p.0, p.0)),
+            Geometry::MultiPoint(mp) => mp.iter().for_each(|p| self.segments.push((p.0, p.0))),
+            Geometry::Line(l) => self.segments.push((l.start, l.end)),
+            Geometry::LineString(ls) => self.add_line(&ls.0),
+            Geometry::MultiLineString(mls) => mls.iter().for_each(|ls| self.add_line(&ls.0)),
+            Geometry::Polygon(p) => self.add_polygon(p),
+            Geometry::MultiPolygon(mp) => mp.iter().for_each(|p| self.add_polygon(p)),
+            Geometry::Rect(r) => self.add_polygon(&r.to_polygon()),
+            Geometry::Triangle(t) => self.add_polygon(&t.to_polygon()),
+            Geometry::GeometryCollection(gc) => gc.iter().for_each(|g| self.add(g)),
+        }
+    }
+
+    fn add_line(&mut self, line: &[Pt]) {
+        match line {
+            [p] => self.segments.push((*p, *p)),
+            _ => line
+                .windows(2)
+                .for_each(|w| self.segments.push((w[0], w[1]))),
+        }
+    }
+
+    fn add_polygon(&mut self, polygon: &geo_types::Polygon<Float>) {
+        for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+            self.add_line(&ring.0);
+            self.rings.push(ring.0.clone());
+        }
+    }
+
+    fn distance(&self, point: &Pt) -> Float {
+        let d = self
+            .segments
+            .iter()
+            .map(|(a, b)| segment_distance(point, a, b))
+            .fold(Float::INFINITY, Float::min);
+        // Even-odd rule over all the rings, so that the holes are outside
+        let crossings = self
+            .rings
+            .iter()
+            .map(|ring| crosses_count(ring, point))
+            .sum::<usize>();
+        if crossings % 2 == 1 {
+            -d
+        } else {
+            d
+        }
+    }
+}
+
+// Returns the distance from the point `p` to the segment between `a` and `b`.
+pub(crate) fn segment_distance(p: &Pt, a: &Pt, b: &Pt) -> Float {
+    let (vx, vy) = (b.x - a.x, b.y - a.y);
+    let l2 = vx * vx + vy * vy;
+    let t = if l2 > 0. {
+        (((p.x - a.x) * vx + (p.y - a.y) * vy) / l2).clamp(0., 1.)
+    } else {
+        0.
+    };
+    (p.x - a.x - t * vx).hypot(p.y - a.y - t * vy)
+}
+
+// Counts the edges of the ring crossed by the horizontal ray starting at the point.
+fn crosses_count(ring: &[Pt], point: &Pt) -> usize {
+    ring.windows(2)
+        .filter(|w| {
+            let (a, b) = (w[0], w[1]);
+            (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        })
+        .count()
+}
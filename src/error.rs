@@ -1,3 +1,4 @@
+use crate::{Float, Pt};
 use std::error::Error as StdError;
 use std::fmt;
 use std::result;
@@ -31,12 +32,49 @@ impl Error {
 #[non_exhaustive]
 pub enum ErrorKind {
     BadDimension,
+    /// The dimensions of the grid exceed the number of values addressable on this target
+    /// (or the number of columns or rows whose coordinates are exactly representable).
+    Overflow,
+    /// The threshold at the given index is NaN, infinite or duplicated.
+    BadThreshold(usize),
     Unexpected,
-    #[cfg(feature = "geojson")]
+    /// The input isn't in the expected format (or uses an unsupported variant of it).
+    BadFormat,
+    /// The value at the given line (starting from 1) of the input can't be parsed.
+    Parse(usize),
+    /// An I/O error occurred while reading or writing data.
+    Io(std::io::Error),
+    /// A ring self-intersects at the given point
+    /// (see [`ContourBuilder::self_intersections`](crate::ContourBuilder::self_intersections)).
+    SelfIntersection(Pt),
+    /// More than `max` rings were computed for the given threshold
+    /// (see [`ContourBuilder::max_rings`](crate::ContourBuilder::max_rings)).
+    TooManyRings {
+        threshold: Float,
+        max: usize,
+    },
+    /// More than `max` vertices were computed
+    /// (see [`ContourBuilder::max_total_vertices`](crate::ContourBuilder::max_total_vertices)).
+    TooManyVertices {
+        max: usize,
+    },
+    /// The computation took longer than allowed
+    /// (see [`ContourBuilder::timeout`](crate::ContourBuilder::timeout)).
+    Timeout,
+    /// The origin or the steps of the grid aren't finite, or a step is zero
+    /// (see [`ContourBuilder::validate`](crate::ContourBuilder::validate)).
+    BadTransform,
+    #[cfg(any(feature = "geojson", feature = "serde"))]
     JsonError(serde_json::error::Error),
 }
 
-#[cfg(feature = "geojson")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        new_error(ErrorKind::Io(err))
+    }
+}
+
+#[cfg(any(feature = "geojson", feature = "serde"))]
 impl From<serde_json::error::Error> for Error {
     fn from(err: serde_json::error::Error) -> Error {
         new_error(ErrorKind::JsonError(err))
@@ -47,8 +85,18 @@ impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match *self.0 {
             ErrorKind::BadDimension => None,
+            ErrorKind::Overflow => None,
+            ErrorKind::BadThreshold(_) => None,
             ErrorKind::Unexpected => None,
-            #[cfg(feature = "geojson")]
+            ErrorKind::BadFormat => None,
+            ErrorKind::Parse(_) => None,
+            ErrorKind::Io(ref err) => Some(err),
+            ErrorKind::SelfIntersection(_) => None,
+            ErrorKind::TooManyRings { .. } => None,
+            ErrorKind::TooManyVertices { .. } => None,
+            ErrorKind::Timeout => None,
+            ErrorKind::BadTransform => None,
+            #[cfg(any(feature = "geojson", feature = "serde"))]
             ErrorKind::JsonError(ref err) => Some(err),
         }
     }
@@ -61,8 +109,36 @@ impl fmt::Display for Error {
                 f,
                 "The length of provided values doesn't match the (dx, dy) dimensions of the grid"
             ),
+            ErrorKind::Overflow => write!(
+                f,
+                "The (dx, dy) dimensions of the grid exceed the addressable number of values"
+            ),
+            ErrorKind::BadThreshold(index) => write!(
+                f,
+                "The threshold at index {} is NaN, infinite or duplicated",
+                index
+            ),
             ErrorKind::Unexpected => write!(f, "Unexpected error while computing contours"),
-            #[cfg(feature = "geojson")]
+            ErrorKind::BadFormat => write!(f, "The input isn't in the expected format"),
+            ErrorKind::Parse(line) => write!(f, "A value at line {} can't be parsed", line),
+            ErrorKind::Io(ref err) => err.fmt(f),
+            ErrorKind::SelfIntersection(point) => {
+                write!(f, "A ring self-intersects at ({}, {})", point.x, point.y)
+            }
+            ErrorKind::TooManyRings { threshold, max } => write!(
+                f,
+                "More than {} rings were computed for the threshold {}",
+                max, threshold
+            ),
+            ErrorKind::TooManyVertices { max } => {
+                write!(f, "More than {} vertices were computed", max)
+            }
+            ErrorKind::Timeout => write!(f, "The computation took longer than allowed"),
+            ErrorKind::BadTransform => write!(
+                f,
+                "The origin or the steps of the grid aren't finite, or a step is zero"
+            ),
+            #[cfg(any(feature = "geojson", feature = "serde"))]
             ErrorKind::JsonError(ref err) => err.fmt(f),
         }
     }
@@ -0,0 +1,104 @@
+use crate::buffer::grid_dimensions;
+use crate::error::{new_error, ErrorKind, Result};
+use crate::{Buffer, Float, Pt};
+use geo_types::Rect;
+
+/// Interpolates the `values` sampled at scattered `points` on a grid covering
+/// `extent` with square cells of `cell_size`, using inverse distance weighting.
+///
+/// The value of each cell is estimated at its center, as the mean of all the
+/// sampled values weighted by the inverse of their distance raised to `power`
+/// (a `power` of `2` is a common choice).
+/// The resulting grid can be contoured using a [`ContourBuilder`](crate::ContourBuilder)
+/// whose origin is the minimum coordinate of `extent` and whose steps are `cell_size`.
+///
+/// Returns an error if `points` and `values` don't have the same length or are empty.
+///
+/// ```
+/// use contour::{inverse_distance_weighting, ContourBuilder};
+/// use geo_types::{coord, Rect};
+///
+/// let points = vec![coord! { x: 1., y: 1. }, coord! { x: 9., y: 9. }, coord! { x: 1., y: 9. }];
+/// let extent = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 10., y: 10. });
+/// let grid = inverse_distance_weighting(&points, &[10., 20., 30.], extent, 0.5, 2.).unwrap();
+///
+/// let res = ContourBuilder::new(grid.dx(), grid.dy(), true)
+///     .x_step(0.5)
+///     .y_step(0.5)
+///     .contours(grid.data(), &[15., 25.])
+///     .unwrap();
+/// ```
+pub fn inverse_distance_weighting(
+    points: &[Pt],
+    values: &[Float],
+    extent: Rect<Float>,
+    cell_size: Float,
+    power: Float,
+) -> Result<Buffer<Float>> {
+    interpolate(points, values, extent, cell_size, |center| {
+        let mut sum = 0.;
+        let mut weights = 0.;
+        for (point, value) in points.iter().zip(values) {
+            let d2 = (point.x - center.x).powi(2) + (point.y - center.y).powi(2);
+            if d2 == 0. {
+                return *value;
+            }
+            let w = d2.powf(-power / 2.);
+            sum += w * value;
+            weights += w;
+        }
+        sum / weights
+    })
+}
+
+/// Interpolates the `values` sampled at scattered `points` on a grid covering
+/// `extent` with square cells of `cell_size`, using the value of the nearest sample
+/// of the center of each cell (see [`inverse_distance_weighting`]).
+///
+/// Returns an error if `points` and `values` don't have the same length or are empty.
+pub fn nearest_neighbor(
+    points: &[Pt],
+    values: &[Float],
+    extent: Rect<Float>,
+    cell_size: Float,
+) -> Result<Buffer<Float>> {
+    interpolate(points, values, extent, cell_size, |center| {
+        let mut nearest = 0;
+        let mut d_min = Float::INFINITY;
+        for (i, point) in points.iter().enumerate() {
+            let d2 = (point.x - center.x).powi(2) + (point.y - center.y).powi(2);
+            if d2 < d_min {
+                d_min = d2;
+                nearest = i;
+            }
+        }
+        values[nearest]
+    })
+}
+
+fn interpolate<F>(
+    points: &[Pt],
+    values: &[Float],
+    extent: Rect<Float>,
+    cell_size: Float,
+    mut estimate: F,
+) -> Result<Buffer<Float>>
+where
+    F: FnMut(Pt) -> Float,
+{
+    if points.len() != values.len() || points.is_empty() {
+        return Err(new_error(ErrorKind::BadDimension));
+    }
+    let (dx, dy) = grid_dimensions(&extent, cell_size);
+    let min = extent.min();
+    let mut data = Vec::with_capacity(dx * dy);
+    for y in 0..dy {
+        for x in 0..dx {
+            data.push(estimate(Pt {
+                x: min.x + (x as Float + 0.5) * cell_size,
+                y: min.y + (y as Float + 0.5) * cell_size,
+            }));
+        }
+    }
+    Buffer::new(data, dx, dy)
+}
@@ -1,8 +1,11 @@
+use crate::buffer::check_len;
 use crate::error::{new_error, ErrorKind, Result};
 use crate::{Float, Pt, Ring};
+use geo_types::Rect;
 use lazy_static::lazy_static;
 use rustc_hash::FxHashMap;
 use slab::Slab;
+use std::time::Instant;
 
 lazy_static! {
     #[rustfmt::skip]
@@ -32,10 +35,17 @@ lazy_static! {
     ];
 }
 
+// The largest number of columns or rows for which the coordinates of the points
+// of the rings (multiples of 0.5) are exactly representable, so that their keys are unique.
+pub(crate) const MAX_DIMENSION: usize = 1 << (Float::MANTISSA_DIGITS - 2);
+
+// A point of a ring, identified by its doubled coordinates.
+type Key = (i64, i64);
+
 #[derive(Clone, Debug)]
 struct Fragment {
-    start: usize,
-    end: usize,
+    start: Key,
+    end: Key,
     ring: Ring,
 }
 
@@ -59,14 +69,154 @@ pub fn contour_rings(
     isoring.compute(values, threshold)
 }
 
-/// Isoring generator to compute marching squares with isolines stitched into rings.
+/// Computes the isorings of the given `Slice` of `values` for each of the `thresholds`
+/// (as [`contour_rings`]), reusing the same [`IsoRingBuilder`] for all of them,
+/// without the assembly of the rings into polygons done by
+/// [`ContourBuilder`](crate::ContourBuilder).
+///
+/// The rings are grouped per threshold, in the order of the `thresholds`.
+///
+/// ```
+/// use contour::contour_rings_multi;
+///
+/// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+/// let rings = contour_rings_multi(&values, &[0.5, 2.], 3, 3).unwrap();
+/// assert_eq!(rings.len(), 2);
+/// assert_eq!((rings[0].len(), rings[1].len()), (1, 0));
+/// ```
+///
+/// # Arguments
+///
+/// * `values` - The slice of values to be used.
+/// * `thresholds` - The threshold values.
+/// * `dx` - The number of columns in the grid.
+/// * `dy` - The number of rows in the grid.
+pub fn contour_rings_multi(
+    values: &[Float],
+    thresholds: &[Float],
+    dx: usize,
+    dy: usize,
+) -> Result<Vec<Vec<Ring>>> {
+    check_len(values.len(), dx, dy)?;
+    let mut isoring = IsoRingBuilder::new(dx, dy);
+    thresholds
+        .iter()
+        .map(|threshold| isoring.compute(values, *threshold))
+        .collect()
+}
+
+/// A segment of an isoline, as computed by the marching squares in a single cell
+/// (before being stitched to the segments of the neighbouring cells).
+///
+/// The inside of the isoline (the values greater than or equal to the threshold)
+/// is on the right of the segment, going from `start` to `end`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    /// The cell of the segment, identified by the column and the row of its upper left sample
+    /// (from `-1` for the cells of the border added around the grid).
+    pub cell: (i64, i64),
+    /// The start of the segment.
+    pub start: Pt,
+    /// The end of the segment.
+    pub end: Pt,
+}
+
+/// Computes the segments of the isoline of the given `threshold` in each cell of the grid,
+/// without stitching them into rings.
+///
+/// This is faster than [`contour_rings`] when only the segments are needed
+/// (such as for rendering them or for stitching them with another strategy).
+///
+/// # Arguments
+///
+/// * `values` - The slice of values to be used.
+/// * `threshold` - The threshold value.
+/// * `dx` - The number of columns in the grid.
+/// * `dy` - The number of rows in the grid.
+pub fn contour_segments(
+    values: &[Float],
+    threshold: Float,
+    dx: usize,
+    dy: usize,
+) -> Result<Vec<Segment>> {
+    span!("marching_squares", threshold);
+    check_len(values.len(), dx, dy)?;
+    let mut result = Vec::new();
+    for y in -1..dy as i64 {
+        for x in -1..dx as i64 {
+            for line in CASES[cell_case(values, threshold, dx, dy, x, y)].iter() {
+                result.push(Segment {
+                    cell: (x, y),
+                    start: Pt {
+                        x: line[0][0] + x as Float,
+                        y: line[0][1] + y as Float,
+                    },
+                    end: Pt {
+                        x: line[1][0] + x as Float,
+                        y: line[1][1] + y as Float,
+                    },
+                });
+            }
+        }
+    }
+    event!(segments = result.len(), "segments computed");
+    Ok(result)
+}
+
+/// Stitches the given `segments` (such as the ones computed by [`contour_segments`],
+/// possibly filtered or moved) into rings, the ends of the segments being joined
+/// when their coordinates are exactly equal.
+///
+/// The segments that do not form a closed ring are dropped
+/// (see [`IsoRingBuilder::stitch_segment`] to retrieve them).
+///
+/// ```
+/// use contour::{contour_rings, contour_segments, stitch_segments};
+///
+/// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+/// let segments = contour_segments(&values, 0.5, 3, 3).unwrap();
+/// let rings = stitch_segments(&segments).unwrap();
+/// assert_eq!(rings.len(), 1);
+/// assert_eq!(rings[0].len(), contour_rings(&values, 0.5, 3, 3).unwrap()[0].len());
+/// ```
+pub fn stitch_segments(segments: &[Segment]) -> Result<Vec<Ring>> {
+    let mut isoring = IsoRingBuilder::new(0, 0);
+    let mut rings = Vec::new();
+    for segment in segments {
+        isoring.stitch_segment(segment.start, segment.end, &mut rings)?;
+    }
+    Ok(rings)
+}
+
+// Returns the marching squares case (from 0 to 15) of the cell whose upper left sample
+// is at column `x` and row `y`, the samples outside the grid being below the threshold.
+pub(crate) fn cell_case(
+    values: &[Float],
+    threshold: Float,
+    dx: usize,
+    dy: usize,
+    x: i64,
+    y: i64,
+) -> usize {
+    let (dx, dy) = (dx as i64, dy as i64);
+    let inside = |x: i64, y: i64| {
+        (x >= 0 && x < dx && y >= 0 && y < dy && values[(y * dx + x) as usize] >= threshold)
+            as usize
+    };
+    inside(x, y + 1) | inside(x + 1, y + 1) << 1 | inside(x + 1, y) << 2 | inside(x, y) << 3
+}
+
+/// Isoring generator to compute marching squares with isolines stitched into rings
+/// (or to stitch the segments computed by other means, see [`stitch_segment`](Self::stitch_segment)).
 pub struct IsoRingBuilder {
-    fragment_by_start: FxHashMap<usize, usize>,
-    fragment_by_end: FxHashMap<usize, usize>,
+    fragment_by_start: FxHashMap<Key, usize>,
+    fragment_by_end: FxHashMap<Key, usize>,
     f: Slab<Fragment>,
     dx: usize,
     dy: usize,
     is_empty: bool,
+    // The instant after which the computation is aborted
+    pub(crate) deadline: Option<Instant>,
 }
 
 impl IsoRingBuilder {
@@ -83,6 +233,15 @@ impl IsoRingBuilder {
             dx,
             dy,
             is_empty: true,
+            deadline: None,
+        }
+    }
+
+    // Returns an error if the deadline is passed.
+    fn check_deadline(&self) -> Result<()> {
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(new_error(ErrorKind::Timeout)),
+            _ => Ok(()),
         }
     }
 
@@ -90,6 +249,10 @@ impl IsoRingBuilder {
     /// (the inside of the isoring is the surface where input `values` are greater than or equal
     /// to the given threshold value).
     ///
+    /// Returns an [`ErrorKind::Overflow`] error if the grid is too large for the points of
+    /// the rings to be exactly represented (more than 2^51 columns or rows, or 2^22 with
+    /// the `f32` feature).
+    ///
     /// # Arguments
     ///
     /// * `values` - The slice of values to be used.
@@ -99,11 +262,17 @@ impl IsoRingBuilder {
             ($ix:expr, $x:ident, $y:ident, $result:expr) => {
                 CASES[$ix]
                     .iter()
-                    .map(|ring| self.stitch(&ring, $x, $y, $result))
+                    .map(|ring| self.stitch(&ring, $x, $y, $result, &|p| p))
                     .collect::<Result<Vec<()>>>()?;
             };
         }
 
+        span!("marching_squares", threshold);
+        if self.dx > MAX_DIMENSION || self.dy > MAX_DIMENSION {
+            return Err(new_error(ErrorKind::Overflow));
+        }
+        check_len(values.len(), self.dx, self.dy)?;
+        self.check_deadline()?;
         if !self.is_empty {
             self.clear();
         }
@@ -132,6 +301,7 @@ impl IsoRingBuilder {
         // General case for the intermediate rows.
         y += 1;
         while y < dy - 1 {
+            self.check_deadline()?;
             x = -1;
             t1 = (values[(y * dx + dx) as usize] >= threshold) as usize;
             t2 = (values[(y * dx) as usize] >= threshold) as usize;
@@ -162,21 +332,147 @@ impl IsoRingBuilder {
         }
         case_stitch!(t2 << 3, x, y, &mut result);
         self.is_empty = false;
+        event!(rings = result.len(), "rings stitched");
         Ok(result)
     }
 
-    #[allow(clippy::unnecessary_cast)]
-    fn index(&self, point: &Pt) -> usize {
-        (point.x as f64 * 2.0 + point.y as f64 * ((self.dx + 1) * 4) as f64) as usize
+    /// Computes isoring for the given slice of `values` according to the `threshold` value
+    /// (as [`compute`](Self::compute)), restricting the traversal to the cells touching the
+    /// given `extents` (in samples, such as the subregions of the grid that changed, or the
+    /// ones assigned to a worker), which may overlap.
+    ///
+    /// Only the rings lying entirely in the traversed cells are returned, the fragments of
+    /// the other ones being left open (see [`take_open_fragments`](Self::take_open_fragments)).
+    ///
+    /// ```
+    /// use contour::IsoRingBuilder;
+    /// use geo_types::{coord, Rect};
+    ///
+    /// let values = [
+    ///     1., 0., 0., 0.,
+    ///     0., 0., 0., 0.,
+    ///     0., 0., 0., 1.,
+    /// ];
+    /// let mut builder = IsoRingBuilder::new(4, 3);
+    /// let extent = Rect::new(coord! { x: 2, y: 1 }, coord! { x: 4, y: 3 });
+    /// let rings = builder.compute_in(&values, 0.5, &[extent]).unwrap();
+    /// assert_eq!(rings.len(), 1);
+    /// assert_eq!(rings, &builder.compute(&values, 0.5).unwrap()[1..]);
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The slice of values to be used.
+    /// * `threshold` - The threshold value to use.
+    /// * `extents` - The extents of the grid to traverse.
+    pub fn compute_in(
+        &mut self,
+        values: &[Float],
+        threshold: Float,
+        extents: &[Rect<usize>],
+    ) -> Result<Vec<Ring>> {
+        span!("marching_squares", threshold);
+        check_len(values.len(), self.dx, self.dy)?;
+        let dx = self.dx;
+        self.compute_extents(extents, |x, y| values[y * dx + x] >= threshold)
+    }
+
+    // Computes the rings in the cells touching the `extents`, `inside` telling whether
+    // the sample at a given column and row of the grid is inside the rings.
+    pub(crate) fn compute_extents<F: Fn(usize, usize) -> bool>(
+        &mut self,
+        extents: &[Rect<usize>],
+        inside: F,
+    ) -> Result<Vec<Ring>> {
+        if self.dx > MAX_DIMENSION || self.dy > MAX_DIMENSION {
+            return Err(new_error(ErrorKind::Overflow));
+        }
+        if !self.is_empty {
+            self.clear();
+        }
+        let mut result = Vec::new();
+        let (dx, dy) = (self.dx as i64, self.dy as i64);
+        let inside = |x: i64, y: i64| {
+            (x >= 0 && x < dx && y >= 0 && y < dy && inside(x as usize, y as usize)) as usize
+        };
+        let mut spans = Vec::new();
+        for y in -1..dy {
+            self.check_deadline()?;
+            // The columns of the cells of the row touching the extents (the cell of a row
+            // touching the samples of this row and of the next one)
+            spans.clear();
+            spans.extend(
+                extents
+                    .iter()
+                    .filter(|e| e.min().y as i64 <= y + 1 && y < e.max().y as i64)
+                    .map(|e| (e.min().x as i64 - 1, (e.max().x as i64).min(dx) - 1))
+                    .filter(|(start, end)| start <= end),
+            );
+            spans.sort_unstable();
+            let mut next = -1;
+            for &(start, end) in &spans {
+                for x in start.max(next)..=end {
+                    let case = inside(x, y + 1)
+                        | inside(x + 1, y + 1) << 1
+                        | inside(x + 1, y) << 2
+                        | inside(x, y) << 3;
+                    for line in CASES[case].iter() {
+                        self.stitch(line, x, y, &mut result, &|p| p)?;
+                    }
+                }
+                next = next.max(end + 1);
+            }
+        }
+        self.is_empty = false;
+        event!(rings = result.len(), "rings stitched");
+        Ok(result)
+    }
+
+    // The coordinates of the points are multiples of 0.5,
+    // so their doubled values are exact integers.
+    fn index(&self, point: &Pt) -> Key {
+        ((point.x * 2.0) as i64, (point.y * 2.0) as i64)
+    }
+
+    /// Computes the segments of a single row of cells, between the samples of the `upper` row `y`
+    /// and of the `lower` row `y + 1` (missing rows, before the first one and after the last one,
+    /// being considered below the threshold), and stitches them to the pending fragments.
+    /// The points of the segments are passed through `map` before being stored in the rings
+    /// (their keys being computed from the unmapped points).
+    pub(crate) fn compute_row<F: Fn(Pt) -> Pt>(
+        &mut self,
+        upper: Option<&[Float]>,
+        lower: Option<&[Float]>,
+        y: i64,
+        threshold: Float,
+        map: &F,
+        result: &mut Vec<Ring>,
+    ) -> Result<()> {
+        let dx = self.dx as i64;
+        let inside = |row: Option<&[Float]>, x: i64| {
+            (x >= 0 && x < dx && row.is_some_and(|row| row[x as usize] >= threshold)) as usize
+        };
+        for x in -1..dx {
+            let case = inside(lower, x)
+                | inside(lower, x + 1) << 1
+                | inside(upper, x + 1) << 2
+                | inside(upper, x) << 3;
+            for line in CASES[case].iter() {
+                self.stitch(line, x, y, result, map)?;
+            }
+        }
+        self.is_empty = false;
+        Ok(())
     }
 
     // Stitchs segments to rings.
-    fn stitch(
+    fn stitch<F: Fn(Pt) -> Pt>(
         &mut self,
         line: &[Vec<Float>],
         x: i64,
         y: i64,
         result: &mut Vec<Ring>,
+        map: &F,
     ) -> Result<()> {
         let start = Pt {
             x: line[0][0] + x as Float,
@@ -188,6 +484,61 @@ impl IsoRingBuilder {
         };
         let start_index = self.index(&start);
         let end_index = self.index(&end);
+        self.join(map(start), map(end), start_index, end_index, result)
+    }
+
+    /// Stitches the segment going from `start` to `end` to the segments given so far
+    /// (since the construction of the generator or the last call to [`clear`](Self::clear)),
+    /// pushing to `rings` the ring it closes, if any.
+    ///
+    /// This is the ring assembly used by [`compute`](Self::compute), exposed for the segments
+    /// computed with another classification of the cells (such as on another lattice,
+    /// or on a GPU): the segments are expected to be consistently oriented (such as with the
+    /// inside of the rings on their right), and their ends are joined when their coordinates
+    /// are exactly equal.
+    ///
+    /// ```
+    /// use contour::IsoRingBuilder;
+    /// use geo_types::coord;
+    ///
+    /// let mut builder = IsoRingBuilder::new(0, 0);
+    /// let mut rings = Vec::new();
+    /// let (a, b, c) = (coord! { x: 0., y: 0. }, coord! { x: 1., y: 0. }, coord! { x: 0., y: 1. });
+    /// builder.stitch_segment(c, a, &mut rings).unwrap();
+    /// builder.stitch_segment(a, b, &mut rings).unwrap();
+    /// assert!(rings.is_empty());
+    /// builder.stitch_segment(b, c, &mut rings).unwrap();
+    /// assert_eq!(rings, [vec![c, a, b, c]]);
+    /// ```
+    pub fn stitch_segment(&mut self, start: Pt, end: Pt, rings: &mut Vec<Ring>) -> Result<()> {
+        let key = |p: &Pt| {
+            // The negative zeros are normalized so that they match the positive ones
+            let (x, y) = (p.x + 0., p.y + 0.);
+            (x.to_bits() as i64, y.to_bits() as i64)
+        };
+        self.is_empty = false;
+        self.join(start, end, key(&start), key(&end), rings)
+    }
+
+    /// Returns the fragments of rings that are still open (the segments stitched by
+    /// [`stitch_segment`](Self::stitch_segment) that did not close a ring, such as the
+    /// segments of a partial grid), and clears the generator.
+    pub fn take_open_fragments(&mut self) -> Vec<Ring> {
+        let fragments = self.f.drain().map(|f| f.ring).collect();
+        self.clear();
+        fragments
+    }
+
+    // Joins the segment going from `start` to `end` (whose keys are given)
+    // to the pending fragments, pushing to `result` the ring it closes, if any.
+    fn join(
+        &mut self,
+        start: Pt,
+        end: Pt,
+        start_index: Key,
+        end_index: Key,
+        result: &mut Vec<Ring>,
+    ) -> Result<()> {
         if self.fragment_by_end.contains_key(&start_index) {
             if self.fragment_by_start.contains_key(&end_index) {
                 let f_ix = self
@@ -278,6 +629,7 @@ impl IsoRingBuilder {
         Ok(())
     }
 
+    /// Drops the pending fragments of rings.
     pub fn clear(&mut self) {
         self.f.clear();
         self.fragment_by_end.clear();
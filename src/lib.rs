@@ -9,7 +9,7 @@
 //! - isobands, as a Vec of [`Band`].
 //!
 //! The [`contour_rings`] function is a convenience function to compute ring (isoline) coordinates
-//! for a single threshold.
+//! for a single threshold (and [`contour_rings_multi`] for several thresholds).
 //!
 //! While contour polygons ([`Contour`]) enclose all the values above a given threshold,
 //! isobands ([`Band`]) are polygons that enclose all the values between two thresholds.
@@ -53,15 +53,75 @@
 //! ```
 //!
 //! [`contour_rings`]: fn.contour_rings.html
+//! [`contour_rings_multi`]: fn.contour_rings_multi.html
 //! [`ContourBuilder`]: struct.ContourBuilder.html
 
+// Enters a `tracing` span (at the debug level) until the end of the current block
+// when the `tracing` feature is enabled, and expands to nothing otherwise.
+macro_rules! span {
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($($args)*).entered();
+    };
+}
+
+// Emits a `tracing` event (at the debug level) when the `tracing` feature is enabled.
+macro_rules! event {
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($args)*);
+    };
+}
+
 mod area;
 mod band;
+mod bandindex;
+mod buffer;
+mod config;
 mod contour;
 mod contourbuilder;
+mod csv;
+mod density;
+mod derived;
+mod distance;
 mod error;
+mod interpolation;
 mod isoringbuilder;
 mod line;
+mod merge;
+#[cfg(feature = "mesh")]
+mod mesh;
+mod mosaic;
+#[cfg(feature = "npy")]
+mod npy;
+#[cfg(feature = "lyon")]
+mod path;
+#[cfg(feature = "plotters")]
+pub mod plot;
+mod polygonize;
+mod pyramid;
+mod quadtree;
+mod raw;
+pub mod ring;
+mod rle;
+mod simplify;
+mod split;
+mod stream;
+#[cfg(feature = "geojson")]
+mod style;
+mod surfer;
+#[cfg(feature = "synthetic")]
+pub mod synthetic;
+mod thresholds;
+#[cfg(feature = "tin")]
+mod tin;
+mod tracking;
+#[cfg(feature = "geo-traits")]
+mod traits;
+mod validation;
+#[cfg(feature = "geojson")]
+mod writer;
+mod zonal;
 
 #[cfg(feature = "f32")]
 pub type Float = f32;
@@ -74,16 +134,51 @@ pub type Pt = geo_types::Coord;
 
 pub type Ring = Vec<Pt>;
 
-pub use crate::band::Band;
+pub use crate::band::{Band, Change};
+pub use crate::bandindex::BandIndex;
+pub use crate::buffer::Buffer;
+pub use crate::config::ContourConfig;
 pub use crate::contour::Contour;
-pub use crate::contourbuilder::ContourBuilder;
+pub use crate::contourbuilder::{ContourBuilder, Registration};
+pub use crate::csv::CsvReader;
+pub use crate::density::{histogram, weighted_histogram, DensityBuilder};
+pub use crate::derived::{
+    aspect, gradient_magnitude, pad, resample, slope, warp, Padding, Resampling, SlopeUnit,
+};
+pub use crate::distance::{distance_buffers, distance_grid};
 pub use crate::error::{Error, ErrorKind, Result};
-pub use crate::isoringbuilder::contour_rings;
-pub use crate::line::Line;
+pub use crate::interpolation::{inverse_distance_weighting, nearest_neighbor};
+pub use crate::isoringbuilder::{
+    contour_rings, contour_rings_multi, contour_segments, stitch_segments, IsoRingBuilder, Segment,
+};
+pub use crate::line::{Gradient, Line};
+pub use crate::merge::{dissolve_bands, merge_lines, merge_polygons};
+#[cfg(feature = "mesh")]
+pub use crate::mesh::Mesh;
+pub use crate::mosaic::{Mosaic, Overlap};
+pub use crate::pyramid::Pyramid;
+pub use crate::quadtree::QuadTree;
+pub use crate::raw::{ByteOrder, DataType, RawReader};
+pub use crate::rle::RleBuffer;
+pub use crate::simplify::simplify_bands;
+pub use crate::stream::RingStream;
+#[cfg(feature = "geojson")]
+pub use crate::style::{maplibre_fill_layer, maplibre_line_layer};
+pub use crate::surfer::SurferGrid;
+pub use crate::thresholds::{
+    relative_thresholds, sigma_thresholds, Relative, Sigma, ThresholdGenerator,
+};
+#[cfg(feature = "tin")]
+pub use crate::tin::TinBuilder;
+pub use crate::tracking::{TrackEvent, Tracker};
+pub use crate::validation::{BandIssue, RingId, SelfIntersections};
+#[cfg(feature = "geojson")]
+pub use crate::writer::{GeoJsonSeqWriter, GeoJsonWriter};
+pub use crate::zonal::ZonalStatistics;
 
 #[cfg(test)]
 mod tests {
-    use crate::{ContourBuilder, Float};
+    use crate::{Change, ContourBuilder, Float};
     use geo_types::{line_string, polygon, MultiLineString, MultiPolygon};
 
     #[test]
@@ -741,4 +836,2697 @@ mod tests {
             _ => panic!(""),
         };
     }
+
+    #[test]
+    fn test_merge_polygons_of_adjacent_tiles() {
+        #[rustfmt::skip]
+        let values: [Float; 100] = [
+            0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
+            0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
+            0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
+            0., 0., 0., 1., 1., 1., 1., 0., 0., 0.,
+            0., 0., 0., 1., 1., 1., 1., 0., 0., 0.,
+            0., 0., 0., 1., 1., 1., 1., 0., 0., 0.,
+            0., 0., 0., 1., 1., 1., 1., 0., 0., 0.,
+            0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
+            0., 0., 0., 0., 0., 0., 0., 0., 0., 0.,
+            0., 0., 0., 0., 0., 0., 0., 0., 0., 0.
+        ];
+        let left = values
+            .chunks(10)
+            .flat_map(|row| row[..5].to_vec())
+            .collect::<Vec<_>>();
+        let right = values
+            .chunks(10)
+            .flat_map(|row| row[5..].to_vec())
+            .collect::<Vec<_>>();
+        let left = ContourBuilder::new(5, 10, false)
+            .contours(&left, &[0.5])
+            .unwrap();
+        let right = ContourBuilder::new(5, 10, false)
+            .x_origin(5.)
+            .contours(&right, &[0.5])
+            .unwrap();
+        let polygons = left[0]
+            .geometry()
+            .0
+            .iter()
+            .chain(right[0].geometry().0.iter())
+            .cloned();
+        let merged = crate::merge_polygons(polygons, 1e-6);
+        assert_eq!(merged.0.len(), 1);
+        assert!(merged.0[0].interiors().is_empty());
+        assert!(!merged.0[0]
+            .exterior()
+            .0
+            .iter()
+            .any(|p| p.x == 5. && p.y > 3.5 && p.y < 6.5));
+    }
+
+    #[test]
+    fn test_merge_lines() {
+        let merged = crate::merge_lines(
+            vec![
+                line_string![(x: 1., y: 0.), (x: 2., y: 1.)],
+                line_string![(x: 2., y: 1.), (x: 1., y: 2.), (x: 0., y: 1.)],
+                line_string![(x: 0., y: 1.), (x: 1., y: 0.)],
+                line_string![(x: 5., y: 5.), (x: 6., y: 5.)],
+            ],
+            1e-6,
+        );
+        assert_eq!(merged.0.len(), 2);
+        assert!(merged.0[0].is_closed());
+        assert_eq!(merged.0[0].0.len(), 5);
+        assert_eq!(merged.0[1], line_string![(x: 5., y: 5.), (x: 6., y: 5.)]);
+
+        // The endpoints are matched by their distance, whatever their location
+        let merge = |gap: Float| {
+            crate::merge_lines(
+                vec![
+                    line_string![(x: 0., y: 0.), (x: 1.0249, y: 0.)],
+                    line_string![(x: 1.0249 + gap, y: 0.), (x: 2., y: 0.)],
+                ],
+                0.1,
+            )
+            .0
+            .len()
+        };
+        assert_eq!(merge(0.005), 1);
+        assert_eq!(merge(0.09), 1);
+        assert_eq!(merge(0.11), 2);
+    }
+
+    #[test]
+    fn test_pyramid_levels() {
+        #[rustfmt::skip]
+        let values = vec![
+            0., 0., 2., 2., 1.,
+            0., 0., 2., 2., 1.,
+            4., 4., 0., 0., 1.,
+            4., 4., 0., 0., 1.,
+            3., 3., 3., 3., 3.,
+        ];
+        let pyramid = crate::Pyramid::new(values, 5, 5, 10).unwrap();
+        assert_eq!(pyramid.levels(), 4);
+        assert_eq!(pyramid.dimensions(1), (3, 3));
+        assert_eq!(pyramid.values(1), &[0., 2., 1., 4., 0., 1., 3., 3., 3.]);
+        assert_eq!(pyramid.dimensions(3), (1, 1));
+        assert_eq!(pyramid.level_for_scale(1.), 0);
+        assert_eq!(pyramid.level_for_scale(4.5), 2);
+
+        let res = pyramid
+            .contour_builder(1, false)
+            .contours(pyramid.values(1), &[3.5])
+            .unwrap();
+        assert_eq!(
+            res[0].geometry(),
+            &MultiPolygon::<Float>(vec![polygon![
+                (x: 2., y: 3.),
+                (x: 1., y: 2.),
+                (x: 0., y: 3.),
+                (x: 1., y: 4.),
+                (x: 2., y: 3.),
+            ]])
+        );
+    }
+
+    #[test]
+    fn test_density_contours() {
+        use geo_types::coord;
+        let points = vec![
+            coord! { x: 30., y: 30. },
+            coord! { x: 32., y: 31. },
+            coord! { x: 29., y: 33. },
+            coord! { x: 70., y: 70. },
+        ];
+        let builder = crate::DensityBuilder::new(100, 100)
+            .cell_size(4)
+            .bandwidth(5.);
+        let (n, m) = builder.dimensions();
+        let grid = builder.grid(&points);
+        assert_eq!(grid.len(), n * m);
+        // The rounding errors of the sum grow with the number of cells
+        let epsilon = (n * m) as Float * Float::EPSILON;
+        assert!((grid.iter().sum::<Float>() - 4.).abs() < epsilon);
+
+        let res = builder.contours(&points, &[0.001, 0.004]).unwrap();
+        assert_eq!(res[0].threshold(), 0.001);
+        assert_eq!(res[0].geometry().0.len(), 2);
+        assert_eq!(res[1].geometry().0.len(), 1);
+        let exterior = &res[1].geometry().0[0].exterior().0;
+        assert!(exterior.iter().all(|p| p.x > 15. && p.x < 45.));
+    }
+
+    #[test]
+    fn test_weighted_histogram() {
+        use geo_types::{coord, Rect};
+        let points = vec![
+            coord! { x: 0., y: 0. },
+            coord! { x: 0.5, y: 0.5 },
+            coord! { x: 3.9, y: 1.9 },
+            coord! { x: 4., y: 2. },
+            coord! { x: 5., y: 2. },
+        ];
+        let extent = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 4., y: 2. });
+        let res = crate::weighted_histogram(&points, &[1., 2., 3., 4., 5.], extent, 1.).unwrap();
+        assert_eq!((res.dx(), res.dy()), (4, 2));
+        assert_eq!(res.data(), &[3., 0., 0., 0., 0., 0., 0., 7.]);
+        assert!(crate::weighted_histogram(&points, &[1.], extent, 1.).is_err());
+
+        let err = crate::Buffer::filled(0., usize::MAX, 2).unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::Overflow));
+        let huge = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 1e30, y: 1e30 });
+        let err = crate::weighted_histogram(&points, &[1.; 5], huge, 1e-30).unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::Overflow));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        use geo_types::{coord, Rect};
+        let points = vec![coord! { x: 0.5, y: 0.5 }, coord! { x: 2.5, y: 0.5 }];
+        let extent = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 3., y: 1. });
+        let res = crate::inverse_distance_weighting(&points, &[1., 3.], extent, 1., 2.).unwrap();
+        assert_eq!(res.data(), &[1., 2., 3.]);
+        let res = crate::nearest_neighbor(&points, &[1., 3.], extent, 1.).unwrap();
+        assert_eq!(res.data(), &[1., 1., 3.]);
+        assert!(crate::nearest_neighbor(&[], &[], extent, 1.).is_err());
+    }
+
+    #[cfg(feature = "tin")]
+    #[test]
+    fn test_tin_contours() {
+        use geo_types::coord;
+        let points = vec![
+            coord! { x: 0., y: 0. },
+            coord! { x: 4., y: 0. },
+            coord! { x: 4., y: 4. },
+            coord! { x: 0., y: 4. },
+            coord! { x: 2., y: 2. },
+        ];
+        let builder = crate::TinBuilder::new(&points, &[0., 0., 2., 0., 2.]).unwrap();
+
+        let res = builder.contours(&[1.]).unwrap();
+        assert_eq!(res[0].geometry().0.len(), 1);
+        let exterior = &res[0].geometry().0[0].exterior().0;
+        assert_eq!(exterior.len(), 7);
+        assert!(exterior.contains(&coord! { x: 4., y: 4. }));
+        assert!(exterior.contains(&coord! { x: 1., y: 1. }));
+        assert!(crate::area::area(exterior) > 0.);
+
+        let res = builder.lines(&[1.]).unwrap();
+        assert_eq!(res[0].geometry().0.len(), 1);
+        assert_eq!(res[0].geometry().0[0].0.len(), 5);
+        assert!(!res[0].geometry().0[0].is_closed());
+    }
+
+    #[test]
+    fn test_polygonize() {
+        let c = ContourBuilder::new(4, 3, true).x_origin(10.);
+        #[rustfmt::skip]
+        let res = c.polygonize(&[
+            1, 1, 2, 2,
+            3, 1, 2, 1,
+            1, 1, 1, 1,
+        ]).unwrap();
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].0, 1);
+        assert_eq!(
+            res[0].1,
+            MultiPolygon::<Float>(vec![polygon! {
+                exterior: [
+                    (x: 10., y: 0.),
+                    (x: 10., y: 1.),
+                    (x: 11., y: 1.),
+                    (x: 11., y: 2.),
+                    (x: 10., y: 2.),
+                    (x: 10., y: 3.),
+                    (x: 14., y: 3.),
+                    (x: 14., y: 1.),
+                    (x: 13., y: 1.),
+                    (x: 13., y: 2.),
+                    (x: 12., y: 2.),
+                    (x: 12., y: 0.),
+                    (x: 10., y: 0.),
+                ],
+                interiors: [],
+            }])
+        );
+        assert_eq!(res[1].1 .0.len(), 1);
+        assert_eq!(res[1].1 .0[0].exterior().0.len(), 7);
+        assert_eq!(
+            res[2].1,
+            MultiPolygon::<Float>(vec![polygon![
+                (x: 10., y: 1.),
+                (x: 10., y: 2.),
+                (x: 11., y: 2.),
+                (x: 11., y: 1.),
+                (x: 10., y: 1.),
+            ]])
+        );
+    }
+
+    #[test]
+    fn test_mask() {
+        let c = ContourBuilder::new(4, 4, false);
+        #[rustfmt::skip]
+        let mask = [
+            false, false, false, false,
+            false, true,  true,  false,
+            false, true,  false, false,
+            false, false, false, true,
+        ];
+        let res = c.mask(&mask, true).unwrap();
+        assert_eq!(
+            res,
+            MultiPolygon::<Float>(vec![
+                polygon![
+                    (x: 1., y: 1.),
+                    (x: 1., y: 3.),
+                    (x: 2., y: 3.),
+                    (x: 2., y: 2.),
+                    (x: 3., y: 2.),
+                    (x: 3., y: 1.),
+                    (x: 1., y: 1.),
+                ],
+                polygon![
+                    (x: 3., y: 3.),
+                    (x: 3., y: 4.),
+                    (x: 4., y: 4.),
+                    (x: 4., y: 3.),
+                    (x: 3., y: 3.),
+                ],
+            ])
+        );
+        let mask = mask.map(|v| v as u8);
+        let res = c.mask(&mask, false).unwrap();
+        assert_eq!(res.0.len(), 2);
+        assert_eq!(res.0[0].exterior().0.len(), 9);
+    }
+
+    #[test]
+    fn test_supersample() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0.,
+            0., 1., 1., 0.,
+            0., 1., 1., 0.,
+            0., 0., 0., 0.,
+        ];
+        let c = ContourBuilder::new(4, 4, true).supersample(2);
+        let res = c.contours(&values, &[0.3]).unwrap();
+        let exterior = &res[0].geometry().0[0].exterior().0;
+        assert_eq!(exterior.len(), 21);
+        assert!(exterior
+            .iter()
+            .all(|p| p.x > 0.75 && p.x < 3.25 && p.y > 0.75 && p.y < 3.25));
+        let coarse = ContourBuilder::new(4, 4, true)
+            .contours(&values, &[0.3])
+            .unwrap();
+        assert_eq!(coarse[0].geometry().0[0].exterior().0.len(), 9);
+
+        let res = c.isobands(&values, &[0.5, 1.5]).unwrap();
+        assert_eq!(res[0].geometry().0.len(), 1);
+
+        let res = ContourBuilder::new(4, 4, true)
+            .supersample(2)
+            .x_origin(10.)
+            .lines(&[1.; 16], &[0.5])
+            .unwrap();
+        let points = &res[0].geometry().0[0].0;
+        assert!(points.iter().all(|p| p.x >= 10. && p.x <= 14.));
+        assert!(points.contains(&geo_types::coord! { x: 10., y: 0.5 }));
+        assert!(points.contains(&geo_types::coord! { x: 14., y: 3.5 }));
+
+        let err = ContourBuilder::new(0, 4, true)
+            .supersample(2)
+            .contours(&[], &[0.5])
+            .unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::BadDimension));
+    }
+
+    #[test]
+    fn test_refine() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0.,
+            0., 1., 2., 0.,
+            0., 2., 3., 0.,
+            0., 0., 0., 0.,
+        ];
+        let coarse = ContourBuilder::new(4, 4, true)
+            .lines(&values, &[0.8])
+            .unwrap();
+        let refined = ContourBuilder::new(4, 4, true)
+            .refine(3)
+            .lines(&values, &[0.8])
+            .unwrap();
+        let (coarse, refined) = (&coarse[0].geometry().0[0].0, &refined[0].geometry().0[0].0);
+        assert!(refined.len() > coarse.len());
+        assert!(coarse.iter().all(|p| refined.contains(p)));
+        assert_eq!(refined.first(), refined.last());
+    }
+
+    #[test]
+    fn test_contour_rings_too_large() {
+        let res = crate::contour_rings(&[0.], 0.5, 1 << 60, 1);
+        assert!(matches!(
+            res.unwrap_err().kind(),
+            crate::ErrorKind::Overflow
+        ));
+    }
+
+    #[test]
+    fn test_dimensions_overflow() {
+        let res = ContourBuilder::new(usize::MAX, 2, false).contours(&[0.], &[0.5]);
+        assert!(matches!(
+            res.unwrap_err().kind(),
+            crate::ErrorKind::Overflow
+        ));
+    }
+
+    #[cfg(feature = "robust")]
+    #[test]
+    fn test_robust_contains() {
+        use geo_types::coord;
+        let ring = [
+            coord! { x: 0., y: 0. },
+            coord! { x: 3., y: 1. },
+            coord! { x: 0., y: 1. },
+            coord! { x: 0., y: 0. },
+        ];
+        // Right above the (0, 0) - (3, 1) edge, at the precision of the floats
+        let point = [coord! { x: 1.5, y: 0.5 + Float::EPSILON }];
+        assert_eq!(crate::area::contains(&ring, &point), 1);
+    }
+
+    #[test]
+    fn test_tracker() {
+        use crate::{TrackEvent, Tracker};
+        let builder = ContourBuilder::new(7, 3, false);
+        let mut tracker = Tracker::new(0.25);
+        #[rustfmt::skip]
+        let frames = [
+            [0., 0., 0., 0., 0., 0., 0.,
+             0., 1., 1., 1., 1., 0., 0.,
+             0., 0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0., 0.,
+             0., 1., 0., 0., 1., 1., 0.,
+             0., 0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0., 0.,
+             0., 0., 0., 0., 1., 1., 0.,
+             0., 0., 0., 0., 0., 0., 0.],
+        ];
+        let mut results = frames.iter().map(|values| {
+            let contours = builder.contours(values, &[0.5]).unwrap();
+            tracker.track(contours[0].geometry())
+        });
+        assert_eq!(
+            results.next().unwrap(),
+            (vec![0], vec![TrackEvent::Appear(0)])
+        );
+        assert_eq!(
+            results.next().unwrap(),
+            (
+                vec![0, 1],
+                vec![TrackEvent::Split {
+                    from: 0,
+                    into: vec![0, 1]
+                }]
+            )
+        );
+        assert_eq!(
+            results.next().unwrap(),
+            (vec![1], vec![TrackEvent::Disappear(0)])
+        );
+    }
+
+    #[test]
+    fn test_interpolated_contour() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0.,
+            0., 1., 1., 1., 0.,
+            0., 1., 2., 1., 0.,
+            0., 1., 1., 1., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let c = ContourBuilder::new(5, 5, true);
+        let area =
+            |contour: &crate::Contour| crate::area::area(&contour.geometry().0[0].exterior().0);
+        let lower = c.contours(&values, &[0.5]).unwrap().remove(0);
+        let upper = c.contours(&values, &[1.5]).unwrap().remove(0);
+        let start = c
+            .interpolated_contour(&values, &values, 0.5, 1.5, 0.)
+            .unwrap();
+        let middle = c
+            .interpolated_contour(&values, &values, 0.5, 1.5, 0.5)
+            .unwrap();
+        assert_eq!(start.geometry(), lower.geometry());
+        assert_eq!(middle.threshold(), 1.);
+        assert!(area(&upper) < area(&middle) && area(&middle) < area(&lower));
+    }
+
+    #[test]
+    fn test_polygon_ids() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0.,
+            0., 1., 0., 1., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let c = ContourBuilder::new(5, 3, true).x_origin(10.).x_step(2.);
+        let contour = c.contours(&values, &[0.5]).unwrap().remove(0);
+        let ids = c.polygon_ids(contour.geometry(), 0.5);
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        // Slightly changed values keep the same identifiers
+        let mut changed = values;
+        changed[6] = 0.9;
+        let contour = c.contours(&changed, &[0.5]).unwrap().remove(0);
+        assert_eq!(c.polygon_ids(contour.geometry(), 0.5), ids);
+        assert_ne!(c.polygon_ids(contour.geometry(), 0.6), ids);
+    }
+
+    #[test]
+    fn test_metadata() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0.,
+            0., 1., 2., 0.,
+            0., 0., 0., 0.,
+        ];
+        let c = ContourBuilder::new(4, 3, false);
+        let bands = c
+            .isobands_with_metadata(&values, &[0.5, 1.5, 2.5], &["low", "high"])
+            .unwrap();
+        assert_eq!(bands.len(), 2);
+        assert_eq!((bands[0].0.min_v(), bands[0].1), (0.5, "low"));
+        assert_eq!((bands[1].0.min_v(), bands[1].1), (1.5, "high"));
+        // The metadata follow the intervals of the given thresholds
+        let bands = c
+            .isobands_with_metadata(&values, &[2.5, 1.5, 0.5], &["high", "low"])
+            .unwrap();
+        assert_eq!((bands[0].0.min_v(), bands[0].1), (1.5, "high"));
+        assert_eq!((bands[1].0.min_v(), bands[1].1), (0.5, "low"));
+        let err = c
+            .isobands_with_metadata(&values, &[1.5, 0.5, 2.5], &["low", "high"])
+            .unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::BadThreshold(2)));
+        let contours = c
+            .contours_with_metadata(&values, &[0.5, 1.5], &["#eee", "#333"])
+            .unwrap();
+        assert_eq!((contours[1].0.threshold(), contours[1].1), (1.5, "#333"));
+        assert!(c.lines_with_metadata(&values, &[0.5], &[1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_ring_stream() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0., 0.,
+            0., 1., 3., 0., 2., 0.,
+            0., 2., 0., 0., 1., 0.,
+            0., 4., 2., 0., 3., 0.,
+            0., 0., 0., 0., 0., 0.,
+        ];
+        for smooth in [false, true] {
+            let c = ContourBuilder::new(6, 5, smooth).x_origin(10.).y_step(2.);
+            let mut stream = c.ring_stream(0.5);
+            let mut rings = Vec::new();
+            for row in values.chunks(6) {
+                stream.push_row(row, |ring| rings.push(ring)).unwrap();
+            }
+            stream.finish(|ring| rings.push(ring)).unwrap();
+            let contour = c.contours(&values, &[0.5]).unwrap().remove(0);
+            let expected = contour
+                .geometry()
+                .iter()
+                .flat_map(|polygon| {
+                    std::iter::once(polygon.exterior())
+                        .chain(polygon.interiors())
+                        .map(|ring| ring.0.clone())
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(rings.len(), expected.len());
+            for ring in &rings {
+                let matching = expected.iter().find(|e| e.len() == ring.len()).unwrap();
+                let mut sorted = ring.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+                let mut other = matching.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+                sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                other.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                assert_eq!(sorted, other);
+            }
+        }
+
+        let mut stream = ContourBuilder::new(6, 5, false).ring_stream(0.5);
+        let err = stream.push_row(&values[..5], |_| ()).unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::BadDimension));
+    }
+
+    #[test]
+    fn test_bad_thresholds() {
+        let c = ContourBuilder::new(2, 2, false);
+        let values = [0., 1., 2., 3.];
+        let kind = |res: crate::Result<Vec<crate::Contour>>| res.unwrap_err().into_kind();
+        assert!(matches!(
+            kind(c.contours(&values, &[0.5, Float::NAN])),
+            crate::ErrorKind::BadThreshold(1)
+        ));
+        assert!(matches!(
+            kind(c.contours(&values, &[1.5, 0.5, 1.5])),
+            crate::ErrorKind::BadThreshold(2)
+        ));
+        assert!(c.lines(&values, &[Float::INFINITY]).is_err());
+        // Open bands
+        let bands = c
+            .isobands(&values, &[Float::NEG_INFINITY, 1.5, Float::INFINITY])
+            .unwrap();
+        assert_eq!(bands.len(), 2);
+        assert!(c.isobands(&values, &[1.5, 1.5]).is_err());
+    }
+
+    #[test]
+    fn test_unsorted_isobands() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0.,
+            0., 1., 2., 0.,
+            0., 3., 2., 0.,
+            0., 0., 0., 0.,
+        ];
+        let c = ContourBuilder::new(4, 4, true);
+        let ascending = c.isobands(&values, &[0.5, 1.5, 2.5]).unwrap();
+        let descending = c.isobands(&values, &[2.5, 1.5, 0.5]).unwrap();
+        let unsorted = c.isobands(&values, &[1.5, 2.5, 0.5]).unwrap();
+        let bounds = |bands: &[crate::Band]| {
+            bands
+                .iter()
+                .map(|b| (b.min_v(), b.max_v(), b.geometry().clone()))
+                .collect::<Vec<_>>()
+        };
+        let mut reversed = bounds(&descending);
+        reversed.reverse();
+        assert_eq!(bounds(&ascending), reversed);
+        assert_eq!(bounds(&ascending), bounds(&unsorted));
+        // The bands are mapped back to the given thresholds
+        let indices = |bands: &[crate::Band]| {
+            bands
+                .iter()
+                .map(|b| b.threshold_indices())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(indices(&ascending), [(0, 1), (1, 2)]);
+        assert_eq!(indices(&descending), [(1, 0), (2, 1)]);
+        assert_eq!(indices(&unsorted), [(2, 0), (0, 1)]);
+        let supersampled = c
+            .supersample(2)
+            .isobands(&values, &[1.5, 2.5, 0.5])
+            .unwrap();
+        assert_eq!(indices(&supersampled), [(2, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_holes_as_polygons() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0.,
+            0., 1., 1., 1., 0.,
+            0., 1., 2., 1., 0.,
+            0., 1., 1., 1., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let c = ContourBuilder::new(5, 5, false).nest_holes(false);
+        let ring_areas = |geometry: &MultiPolygon<Float>| {
+            geometry
+                .iter()
+                .map(|p| {
+                    assert!(p.interiors().is_empty());
+                    crate::area::area(&p.exterior().0)
+                })
+                .collect::<Vec<_>>()
+        };
+        let bands = c.isobands(&values, &[0.5, 1.5, 2.5]).unwrap();
+        let areas = ring_areas(bands[0].geometry());
+        assert_eq!(areas.len(), 2);
+        assert!(areas[0] > 0. && areas[1] < 0.);
+        assert_eq!(ring_areas(bands[1].geometry()).len(), 1);
+
+        // The nested output has the same rings
+        let nested = ContourBuilder::new(5, 5, false)
+            .isobands(&values, &[0.5, 1.5, 2.5])
+            .unwrap();
+        let polygon = &nested[0].geometry().0[0];
+        assert_eq!(&bands[0].geometry().0[0].exterior(), &polygon.exterior());
+        assert_eq!(polygon.interiors().len(), 1);
+    }
+
+    #[cfg(feature = "geo-traits")]
+    #[test]
+    fn test_geo_traits() {
+        use geo_traits::{GeometryTrait, GeometryType, MultiPolygonTrait, PolygonTrait};
+
+        let c = ContourBuilder::new(3, 3, false);
+        let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+        let contour = c.contours(&values, &[0.5]).unwrap().remove(0);
+        match contour.as_type() {
+            GeometryType::MultiPolygon(multi_polygon) => {
+                assert_eq!(multi_polygon.num_polygons(), 1);
+                let polygon = multi_polygon.polygon(0).unwrap();
+                assert_eq!(polygon.num_interiors(), 0);
+            }
+            _ => panic!("expected a MultiPolygon"),
+        }
+        let geometry: MultiPolygon<Float> = contour.clone().into();
+        assert_eq!(&geometry, contour.geometry());
+        let line = c.lines(&values, &[0.5]).unwrap().remove(0);
+        assert!(matches!(line.as_type(), GeometryType::MultiLineString(_)));
+    }
+
+    #[test]
+    fn test_segments() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0.,
+            0., 1., 1., 1., 0.,
+            0., 1., 0., 1., 0.,
+            0., 1., 1., 1., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let c = ContourBuilder::new(5, 5, true).x_origin(10.);
+        let segments = c.segments(&values, &[0.5]).unwrap().remove(0);
+        let lines = c.lines(&values, &[0.5]).unwrap().remove(0);
+        // Each segment of the stitched rings is one of the raw segments
+        let n = lines
+            .geometry()
+            .iter()
+            .map(|l| l.0.len() - 1)
+            .sum::<usize>();
+        assert_eq!(segments.len(), n);
+        for line in lines.geometry() {
+            for w in line.0.windows(2) {
+                assert!(segments.iter().any(|s| s.start == w[0] && s.end == w[1]));
+            }
+        }
+        assert!(segments
+            .iter()
+            .all(|s| (-1..5).contains(&s.cell.0) && (-1..5).contains(&s.cell.1)));
+
+        // The segments are clamped to the extent of the values as the lines are
+        let mut values = values;
+        values[0] = 1.;
+        let c = c.registration(crate::Registration::Point);
+        let segments = c.segments(&values, &[0.5]).unwrap().remove(0);
+        let lines = c.lines(&values, &[0.5]).unwrap().remove(0);
+        let inside = |p: &crate::Pt| p.x >= 10. && p.x <= 14. && p.y >= 0. && p.y <= 4.;
+        assert!(segments.iter().all(|s| inside(&s.start) && inside(&s.end)));
+        for line in lines.geometry() {
+            for w in line.0.windows(2) {
+                assert!(segments.iter().any(|s| s.start == w[0] && s.end == w[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_gradients() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0.,
+            0., 1., 2., 1., 0.,
+            0., 2., 4., 2., 0.,
+            0., 1., 2., 1., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let c = ContourBuilder::new(5, 5, true)
+            .x_origin(10.)
+            .y_step(2.)
+            .gradients(true);
+        let center = crate::Pt { x: 12.5, y: 5. };
+        for builder in [
+            c.with_smoothing(true),
+            c.with_smoothing(true).supersample(2),
+        ] {
+            let lines = builder.lines(&values, &[1.5]).unwrap();
+            let gradients = lines[0].gradients().unwrap();
+            for (line, gradients) in lines[0].geometry().iter().zip(gradients) {
+                assert_eq!(line.0.len(), gradients.len());
+                for (w, gradient) in line.0.windows(2).zip(gradients) {
+                    // The normal points towards the peak and the tangent along the line
+                    let normal = gradient.normal().unwrap();
+                    let tangent = gradient.tangent().unwrap();
+                    let (p, q) = (w[0], w[1]);
+                    assert!(normal.x * (center.x - p.x) + normal.y * (center.y - p.y) > 0.);
+                    assert!(tangent.x * (q.x - p.x) + tangent.y * (q.y - p.y) >= 0.);
+                }
+            }
+        }
+        // The gradients at the quantized vertices are the ones at the vertices in the world
+        let extent = geo_types::Rect::new(
+            geo_types::coord! { x: 10., y: 0. },
+            geo_types::coord! { x: 15., y: 10. },
+        );
+        let lines = c.lines(&values, &[1.5]).unwrap();
+        let quantized = c
+            .with_smoothing(true)
+            .quantize(extent, 4096)
+            .lines(&values, &[1.5])
+            .unwrap();
+        let (expected, gradients) = (lines[0].gradients(), quantized[0].gradients());
+        for (expected, gradients) in expected.unwrap().iter().zip(gradients.unwrap()) {
+            assert_eq!(expected.len(), gradients.len());
+            for (a, b) in expected.iter().zip(gradients) {
+                assert!(b.x != 0. || b.y != 0.);
+                assert!((a.x - b.x).abs() < 1e-2 && (a.y - b.y).abs() < 1e-2);
+            }
+        }
+        assert!(c.gradients(false).lines(&values, &[1.5]).unwrap()[0]
+            .gradients()
+            .is_none());
+    }
+
+    #[test]
+    fn test_distance_buffers() {
+        use geo_types::{coord, point, Geometry, Rect};
+
+        let extent = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 20., y: 20. });
+        let square = polygon![(x: 6., y: 6.), (x: 14., y: 6.), (x: 14., y: 14.), (x: 6., y: 14.)];
+        let geometries = vec![
+            Geometry::Polygon(square),
+            Geometry::Point(point! { x: 3., y: 17. }),
+        ];
+        let grid = crate::distance_grid(&geometries, extent, 1.).unwrap();
+        assert_eq!((grid.dx(), grid.dy()), (20, 20));
+        assert_eq!(grid.get(10, 10), Some(&-3.5));
+        assert_eq!(grid.get(16, 10), Some(&2.5));
+
+        let buffers = crate::distance_buffers(&geometries, &[-2., 1.], extent, 0.5).unwrap();
+        assert_eq!(buffers[0].threshold(), -2.);
+        // The shrunk square only
+        let shrunk = &buffers[0].geometry().0;
+        assert_eq!(shrunk.len(), 1);
+        // (the area of the rings being doubled)
+        let area = crate::area::area(&shrunk[0].exterior().0);
+        assert!((area / 2. - 16.).abs() < 0.5);
+        // The square and the disc around the point
+        assert_eq!(buffers[1].geometry().0.len(), 2);
+    }
+
+    #[test]
+    fn test_weighted_density() {
+        use geo_types::coord;
+
+        let points = vec![coord! { x: 20., y: 20. }, coord! { x: 80., y: 80. }];
+        let builder = crate::DensityBuilder::new(100, 100).bandwidth(5.);
+        // Unit weights give the unweighted density
+        assert_eq!(
+            builder.weighted_grid(&points, &[1., 1.]).unwrap(),
+            builder.grid(&points)
+        );
+        let threshold = 0.002;
+        let unweighted = builder.contours(&points, &[threshold]).unwrap();
+        assert_eq!(unweighted[0].geometry().0.len(), 2);
+        // Only the heavy point exceeds the threshold
+        let weighted = builder
+            .weighted_contours(&points, &[0.1, 2.], &[threshold])
+            .unwrap();
+        assert_eq!(weighted[0].geometry().0.len(), 1);
+        assert!(weighted[0].geometry().0[0].exterior().0[0].x > 50.);
+        assert!(builder.weighted_grid(&points, &[1.]).is_err());
+    }
+
+    #[test]
+    fn test_bandwidth_selection() {
+        use crate::DensityBuilder;
+        use geo_types::coord;
+
+        let points = (0..100)
+            .map(|i| coord! { x: (i % 10) as Float, y: (i / 10) as Float })
+            .collect::<Vec<_>>();
+        // Standard deviation of 0..10 is about 2.87, and 100^(-1/6) about 0.46
+        let scott = DensityBuilder::scott_bandwidth(&points);
+        assert!((scott - 2.87 * 0.464).abs() < 0.01);
+        // The interquartile range (5 / 1.34) is larger than the standard deviation
+        let silverman = DensityBuilder::silverman_bandwidth(&points);
+        assert!((silverman - 0.9 * 2.87 * 0.398).abs() < 0.01);
+        // Unless there are outliers
+        let mut outliers = points.clone();
+        outliers.extend([
+            coord! { x: 1000., y: 1000. },
+            coord! { x: -1000., y: -1000. },
+        ]);
+        let silverman = DensityBuilder::silverman_bandwidth(&outliers);
+        assert!(silverman < DensityBuilder::scott_bandwidth(&outliers) / 10.);
+        assert_eq!(DensityBuilder::scott_bandwidth(&points[..1]), 0.);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_buffer_from_json() {
+        let file = std::fs::File::open("tests/fixtures/volcano.json").unwrap();
+        let buffer = crate::Buffer::from_json_reader(std::io::BufReader::new(file)).unwrap();
+        assert_eq!((buffer.dx(), buffer.dy()), (87, 61));
+        let json = r#"{"width": 2, "height": 2, "data": [0, 1, 0]}"#;
+        assert!(matches!(
+            crate::Buffer::from_json_reader(json.as_bytes())
+                .unwrap_err()
+                .kind(),
+            crate::ErrorKind::BadDimension
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_serde() {
+        use crate::ring::Orientation;
+        use crate::{ContourConfig, Registration, SelfIntersections};
+        use geo_types::{coord, Rect};
+
+        let mut config = ContourConfig::new(4, 3, true);
+        config.registration = Registration::Point;
+        config.hole_orientation = Some(Orientation::Clockwise);
+        config.self_intersections = SelfIntersections::Drop;
+        config.quantize = Some((
+            Rect::new(coord! { x: 0., y: 0. }, coord! { x: 4., y: 3. }),
+            4096,
+        ));
+        config.timeout = Some(std::time::Duration::from_millis(250));
+        let json = serde_json::to_string(&config).unwrap();
+        let restored: ContourConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, config);
+        assert_eq!(ContourBuilder::from(restored).config(), config);
+    }
+
+    #[test]
+    fn test_csv_reader() {
+        let tsv = "1\t2\t3\n4\t\t-9999\n\n";
+        let reader = crate::CsvReader::new().delimiter('\t').nodata(&["-9999"]);
+        let buffer = reader.read(tsv.as_bytes()).unwrap();
+        assert_eq!((buffer.dx(), buffer.dy()), (3, 2));
+        assert_eq!(&buffer.data()[..4], &[1., 2., 3., 4.]);
+        assert!(buffer.data()[4..].iter().all(|v| v.is_nan()));
+
+        let err = reader.read("1\t2\n3\n".as_bytes()).unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::BadDimension));
+        let err = reader.read("1\t2\n3\tx\n".as_bytes()).unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::Parse(2)));
+    }
+
+    #[cfg(feature = "npy")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn test_buffer_from_npy() {
+        let npy = |descr: &str, fortran_order: bool, data: &[u8]| {
+            let header = format!(
+                "{{'descr': '{}', 'fortran_order': {}, 'shape': (2, 3), }}\n",
+                descr,
+                if fortran_order { "True" } else { "False" }
+            );
+            let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+            bytes.extend((header.len() as u16).to_le_bytes());
+            bytes.extend(header.as_bytes());
+            bytes.extend(data);
+            bytes
+        };
+        let expected: [Float; 6] = [1., 2., 3., 4., 5., 6.];
+
+        let data = expected
+            .iter()
+            .flat_map(|v| (*v as f64).to_le_bytes())
+            .collect::<Vec<_>>();
+        let buffer = crate::Buffer::from_npy_reader(&npy("<f8", false, &data)[..]).unwrap();
+        assert_eq!((buffer.dx(), buffer.dy()), (3, 2));
+        assert_eq!(buffer.data(), &expected);
+
+        // Column by column, as big endian 16-bit integers
+        let data = [1i16, 4, 2, 5, 3, 6]
+            .iter()
+            .flat_map(|v| v.to_be_bytes())
+            .collect::<Vec<_>>();
+        let buffer = crate::Buffer::from_npy_reader(&npy(">i2", true, &data)[..]).unwrap();
+        assert_eq!(buffer.data(), &expected);
+
+        let err = crate::Buffer::from_npy_reader(&npy("<c16", false, &data)[..]).unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::BadFormat));
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn test_surfer_grid() {
+        use crate::SurferGrid;
+
+        let values = [0., 1., 0., 1., 2., 1.70141e38];
+        let check = |grid: SurferGrid| {
+            assert_eq!((grid.buffer().dx(), grid.buffer().dy()), (3, 2));
+            assert_eq!(&grid.buffer().data()[..5], &values[..5]);
+            assert!(grid.buffer().data()[5].is_nan());
+            assert_eq!(grid.min(), (10., 20.));
+            assert_eq!(grid.step(), (5., 2.));
+        };
+
+        // Surfer 6 binary
+        let mut bytes = b"DSBB".to_vec();
+        bytes.extend(3i16.to_le_bytes());
+        bytes.extend(2i16.to_le_bytes());
+        for v in [10f64, 20., 20., 22., 0., 2.] {
+            bytes.extend(v.to_le_bytes());
+        }
+        bytes.extend(values.iter().flat_map(|v| (*v as f32).to_le_bytes()));
+        check(SurferGrid::from_reader(&bytes[..]).unwrap());
+
+        // Surfer 7
+        let mut bytes = b"DSRB".to_vec();
+        bytes.extend(4u32.to_le_bytes());
+        bytes.extend(2i32.to_le_bytes());
+        bytes.extend(b"GRID");
+        bytes.extend(72u32.to_le_bytes());
+        bytes.extend(2i32.to_le_bytes());
+        bytes.extend(3i32.to_le_bytes());
+        for v in [10f64, 20., 5., 2., 0., 2., 0., 1.70141e38] {
+            bytes.extend(v.to_le_bytes());
+        }
+        bytes.extend(b"DATA");
+        bytes.extend(48u32.to_le_bytes());
+        bytes.extend(values.iter().flat_map(|v| (*v as f64).to_le_bytes()));
+        check(SurferGrid::from_reader(&bytes[..]).unwrap());
+
+        let err = SurferGrid::from_reader(&b"GRID"[..]).unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::BadFormat));
+    }
+
+    #[test]
+    fn test_simplify_bands() {
+        use std::collections::HashMap;
+
+        let (dx, dy) = (20, 20);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float - 9.5, (i / dx) as Float - 9.5);
+                (x * x + y * y).sqrt() + (x * 0.7).sin()
+            })
+            .collect::<Vec<_>>();
+        let bands = ContourBuilder::new(dx, dy, true)
+            .isobands(&values, &[-1., 3., 6., 9., 20.])
+            .unwrap();
+        let simplified = crate::simplify_bands(&bands, 0.3);
+        // The number of occurrences of each (undirected) edge
+        let edges = |bands: &[crate::Band]| {
+            let mut edges = HashMap::new();
+            for band in bands {
+                for polygon in band.geometry() {
+                    for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+                        for w in ring.0.windows(2) {
+                            let (a, b) = (
+                                (w[0].x.to_bits(), w[0].y.to_bits()),
+                                (w[1].x.to_bits(), w[1].y.to_bits()),
+                            );
+                            edges
+                                .entry((a.min(b), a.max(b)))
+                                .or_insert((0, w[0], w[1]))
+                                .0 += 1;
+                        }
+                    }
+                }
+            }
+            edges
+        };
+        let simplified_edges = edges(&simplified);
+        assert!(simplified_edges.len() < edges(&bands).len() / 2);
+        // Each edge is still shared by two bands, except along the border of the grid
+        // (where the nested rings may run together)
+        let on_border =
+            |p: crate::Pt| p.x == 0. || p.y == 0. || p.x == dx as Float || p.y == dy as Float;
+        for (n, a, b) in simplified_edges.into_values() {
+            assert!(n == 2 || (on_border(a) && on_border(b)));
+        }
+    }
+
+    #[test]
+    fn test_snap() {
+        let (dx, dy) = (12, 10);
+        let values = (0..dx * dy)
+            .map(|i| ((i % dx) as Float * 0.7).sin() + ((i / dx) as Float * 0.4).cos())
+            .collect::<Vec<_>>();
+        let thresholds = [-1., -0.5, 0., 0.5, 1.];
+        let builder = ContourBuilder::new(dx, dy, true)
+            .x_origin(0.1)
+            .y_origin(-0.3)
+            .x_step(0.3)
+            .y_step(0.7);
+        let snapped = ContourBuilder::from(builder.config()).snap(1e-6);
+        let on_grid = |p: &crate::Pt| {
+            (p.x / 1e-6).round() * 1e-6 == p.x && (p.y / 1e-6).round() * 1e-6 == p.y
+        };
+
+        let bands = snapped.isobands(&values, &thresholds).unwrap();
+        assert_eq!(
+            bands
+                .iter()
+                .map(|b| b.geometry().0.len())
+                .collect::<Vec<_>>(),
+            builder
+                .isobands(&values, &thresholds)
+                .unwrap()
+                .iter()
+                .map(|b| b.geometry().0.len())
+                .collect::<Vec<_>>()
+        );
+        for polygon in bands.iter().flat_map(|b| b.geometry()) {
+            assert!(polygon.exterior().0.iter().all(on_grid));
+            assert!(polygon.interiors().iter().all(|r| r.0.iter().all(on_grid)));
+        }
+        let lines = snapped.supersample(2).lines(&values, &thresholds).unwrap();
+        assert!(lines
+            .iter()
+            .flat_map(|l| l.geometry())
+            .all(|l| l.0.iter().all(on_grid)));
+    }
+
+    #[test]
+    fn test_validate_bands() {
+        use crate::{Band, BandIssue};
+
+        let (dx, dy) = (16, 12);
+        let values = (0..dx * dy)
+            .map(|i| ((i % dx) as Float * 0.6).sin() * ((i / dx) as Float * 0.5).cos())
+            .collect::<Vec<_>>();
+        let builder = ContourBuilder::new(dx, dy, true)
+            .x_origin(-3.)
+            .y_origin(2.)
+            .x_step(0.5)
+            .y_step(2.);
+        let thresholds = [Float::NEG_INFINITY, -0.5, 0., 0.5, Float::INFINITY];
+        let bands = builder.isobands(&values, &thresholds).unwrap();
+        assert_eq!(builder.validate_bands(&bands, 1e-6), vec![]);
+
+        // Moving a polygon makes it overlap the polygons of the other bands
+        let mut moved = bands.clone();
+        let (mut geometry, min_v, max_v) = moved.remove(2).into_inner();
+        geometry.0[0].exterior_mut(|ring| ring.0.iter_mut().for_each(|p| p.x += 0.6));
+        moved.insert(2, Band::new(geometry, min_v, max_v));
+        let issues = builder.validate_bands(&moved, 1e-6);
+        assert!(!issues.is_empty());
+        assert!(issues.iter().all(|issue| match issue {
+            BandIssue::Overlap(a, b) => a.band == 2 || b.band == 2,
+            _ => false,
+        }));
+
+        // Removing a band leaves a gap
+        let issues = builder.validate_bands(&bands[1..], 1e-6);
+        assert!(
+            matches!(issues[..], [BandIssue::Coverage { covered, expected }] if covered < expected)
+        );
+
+        // The area of the grid follows the registration and the quantization
+        let point = ContourBuilder::new(dx, dy, true).registration(crate::Registration::Point);
+        let bands = point.isobands(&values, &thresholds).unwrap();
+        assert_eq!(point.validate_bands(&bands, 1e-6), vec![]);
+        let extent = geo_types::Rect::new(
+            geo_types::coord! { x: -3., y: 2. },
+            geo_types::coord! { x: 5., y: 26. },
+        );
+        let quantized = builder.quantize(extent, 4096);
+        let bands = quantized.isobands(&values, &thresholds).unwrap();
+        assert_eq!(quantized.validate_bands(&bands, 1e-6), vec![]);
+    }
+
+    #[test]
+    fn test_self_intersections() {
+        use crate::SelfIntersections;
+
+        // A bow-tie crosses itself at its center
+        let bow_tie = [(0., 0.), (2., 2.), (2., 0.), (0., 2.), (0., 0.)]
+            .map(|(x, y)| geo_types::coord! { x: x, y: y });
+        assert_eq!(
+            crate::validation::self_intersection(&bow_tie),
+            Some(geo_types::coord! { x: 1., y: 1. })
+        );
+
+        // The refinement of the upper left saddle cell (symmetric about its diagonal)
+        // makes the ring cross itself on the diagonal
+        #[rustfmt::skip]
+        let values = [
+            0.25, 1., 1.,
+            1., 0., 1.,
+            1., 1., 1.,
+        ];
+        let builder = ContourBuilder::new(3, 3, false).refine(3);
+        let rings = |contour: &crate::Contour| {
+            contour
+                .geometry()
+                .iter()
+                .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+                .map(|ring| ring.0.clone())
+                .collect::<Vec<_>>()
+        };
+        let ignored = builder.contours(&values, &[0.55]).unwrap().remove(0);
+        assert!(rings(&ignored)
+            .iter()
+            .any(|ring| crate::validation::self_intersection(ring).is_some()));
+
+        let err = ContourBuilder::new(3, 3, false)
+            .refine(3)
+            .x_step(2.)
+            .self_intersections(SelfIntersections::Error)
+            .contours(&values, &[0.55])
+            .unwrap_err();
+        // The intersection is on the diagonal of the cell (stretched by the step)
+        let on_diagonal = |p: &crate::Pt| p.x > 1. && p.x < 2. && (p.x - p.y * 2.).abs() < 1e-3;
+        assert!(matches!(err.kind(), crate::ErrorKind::SelfIntersection(p) if on_diagonal(p)));
+
+        let repaired = ContourBuilder::new(3, 3, false)
+            .refine(3)
+            .self_intersections(SelfIntersections::Repair)
+            .contours(&values, &[0.55])
+            .unwrap()
+            .remove(0);
+        assert_eq!(rings(&repaired).len(), rings(&ignored).len());
+        assert!(rings(&repaired)
+            .iter()
+            .all(|ring| crate::validation::self_intersection(ring).is_none()));
+
+        let dropped = ContourBuilder::new(3, 3, false)
+            .refine(3)
+            .self_intersections(SelfIntersections::Drop)
+            .contours(&values, &[0.55])
+            .unwrap()
+            .remove(0);
+        assert!(rings(&dropped).len() < rings(&ignored).len());
+    }
+
+    #[test]
+    fn test_bbox() {
+        use geo_types::{coord, Rect};
+
+        let rect = |points: Vec<crate::Pt>| {
+            let min = points.iter().fold(
+                points[0],
+                |m, p| coord! { x: m.x.min(p.x), y: m.y.min(p.y) },
+            );
+            let max = points.iter().fold(
+                points[0],
+                |m, p| coord! { x: m.x.max(p.x), y: m.y.max(p.y) },
+            );
+            Rect::new(min, max)
+        };
+        let values = [
+            0., 0., 0., 0., 0., 1., 2., 0., 0., 2., 1., 0., 0., 0., 0., 0.,
+        ];
+        let builder = ContourBuilder::new(4, 4, true)
+            .x_origin(10.)
+            .y_step(-2.)
+            .supersample(2);
+
+        let contours = builder.contours(&values, &[0.5, 3.]).unwrap();
+        let points = contours[0]
+            .geometry()
+            .iter()
+            .flat_map(|p| p.exterior().0.clone())
+            .collect();
+        assert_eq!(contours[0].bbox(), Some(rect(points)));
+        assert_eq!(contours[1].bbox(), None);
+
+        let bands = builder.isobands(&values, &[0.5, 1.5, 3.]).unwrap();
+        let points = bands[1]
+            .geometry()
+            .iter()
+            .flat_map(|p| p.exterior().0.clone())
+            .collect();
+        assert_eq!(bands[1].bbox(), Some(rect(points)));
+
+        let lines = builder.lines(&values, &[1.5]).unwrap();
+        let points = lines[0]
+            .geometry()
+            .iter()
+            .flat_map(|l| l.0.clone())
+            .collect();
+        assert_eq!(lines[0].bbox(), Some(rect(points)));
+    }
+
+    #[test]
+    fn test_interior_points() {
+        use crate::area::contains;
+
+        // A ring with a hole at its center, and a smaller ring inside the hole
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0., 0., 0.,
+            0., 1., 1., 1., 1., 1., 0.,
+            0., 1., 0., 0., 0., 1., 0.,
+            0., 1., 0., 1., 0., 1., 0.,
+            0., 1., 0., 0., 0., 1., 0.,
+            0., 1., 1., 1., 1., 1., 0.,
+            0., 0., 0., 0., 0., 0., 0.,
+        ];
+        let builder = ContourBuilder::new(7, 7, true).y_step(-2.);
+        let is_inside = |polygon: &geo_types::Polygon<Float>, point: &crate::Pt| {
+            contains(&polygon.exterior().0, &[*point]) == 1
+                && polygon
+                    .interiors()
+                    .iter()
+                    .all(|hole| contains(&hole.0, &[*point]) == -1)
+        };
+        let check = |geometry: &MultiPolygon<Float>, points: &[Option<crate::Pt>]| {
+            assert_eq!(points.len(), geometry.0.len());
+            for (polygon, point) in geometry.iter().zip(points) {
+                assert!(is_inside(polygon, &point.unwrap()));
+            }
+        };
+
+        let contours = builder.contours(&values, &[0.5]).unwrap();
+        assert_eq!(contours[0].geometry().0.len(), 2);
+        check(contours[0].geometry(), contours[0].interior_points());
+
+        let bands = builder.isobands(&values, &[-1., 0.5, 2.]).unwrap();
+        assert_eq!(bands[0].geometry().0.len(), 2);
+        for band in &bands {
+            check(band.geometry(), band.interior_points());
+        }
+
+        let bands = builder
+            .supersample(2)
+            .isobands(&values, &[0.5, 2.])
+            .unwrap();
+        check(bands[0].geometry(), bands[0].interior_points());
+        assert_eq!(
+            crate::area::interior_point(&[&[crate::Pt { x: 0., y: 0. }; 3]]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_envelope() {
+        use geo_types::{coord, Rect};
+
+        // Two blobs, at the top left and at the bottom right of the grid
+        let (dx, dy) = (12, 12);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float, (i / dx) as Float);
+                let blob =
+                    |cx: Float, cy: Float| (-((x - cx).powi(2) + (y - cy).powi(2)) / 2.).exp();
+                blob(2., 2.) + blob(9., 9.)
+            })
+            .collect::<Vec<_>>();
+        // The top left blob lies around (105, 35) with this transform
+        let envelope = Rect::new(coord! { x: 100., y: 30. }, coord! { x: 110., y: 40. });
+        for supersample in [1, 3] {
+            let builder = ContourBuilder::new(dx, dy, true)
+                .x_origin(100.)
+                .y_origin(30.)
+                .x_step(2.)
+                .y_step(2.)
+                .supersample(supersample);
+            let all = builder.contours(&values, &[0.5]).unwrap();
+            assert_eq!(all[0].geometry().0.len(), 2);
+            let builder = ContourBuilder::from(builder.config()).envelope(envelope);
+            let contours = builder.contours(&values, &[0.5]).unwrap();
+            assert_eq!(contours[0].geometry().0.len(), 1);
+            assert!(contours[0].geometry().0[0]
+                .exterior()
+                .0
+                .iter()
+                .all(|p| p.x < 110. && p.y < 40.));
+            let bands = builder.isobands(&values, &[0.25, 0.5, 2.]).unwrap();
+            assert!(bands.iter().all(|band| band.geometry().0.len() == 1));
+            let lines = builder.lines(&values, &[0.5]).unwrap();
+            assert_eq!(lines[0].geometry().0.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_max_vertices() {
+        let (dx, dy) = (30, 24);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float - 14.5, (i / dx) as Float - 11.5);
+                ((x * x + y * y).sqrt() * 0.6).sin() + (x * 0.3).cos() * 0.5
+            })
+            .collect::<Vec<_>>();
+        let thresholds = [Float::NEG_INFINITY, -0.5, 0., 0.5, Float::INFINITY];
+        let builder = ContourBuilder::new(dx, dy, true);
+        let split = ContourBuilder::new(dx, dy, true).max_vertices(20);
+
+        // The split bands still cover the grid
+        let bands = split.isobands(&values, &thresholds).unwrap();
+        assert!(builder
+            .isobands(&values, &thresholds)
+            .unwrap()
+            .iter()
+            .flat_map(|band| band.geometry())
+            .any(|p| p.exterior().0.len() > 20));
+        for polygon in bands.iter().flat_map(|band| band.geometry()) {
+            assert!(polygon.exterior().0.len() <= 20);
+            assert!(polygon.interiors().iter().all(|ring| ring.0.len() <= 20));
+        }
+        // (the points where the rings are cut being rounded, they may slightly cross
+        // the rings of the adjacent bands, which is only checked through the covered area)
+        assert!(split
+            .validate_bands(&bands, 1e-6)
+            .iter()
+            .all(|issue| matches!(issue, crate::BandIssue::Overlap(..))));
+
+        // The split contours keep their orientation and their area
+        let area = |contour: &crate::Contour| {
+            contour
+                .geometry()
+                .iter()
+                .map(|p| {
+                    crate::area::area(&p.exterior().0)
+                        - p.interiors()
+                            .iter()
+                            .map(|r| crate::area::area(&r.0).abs())
+                            .sum::<f64>()
+                })
+                .sum::<f64>()
+        };
+        let contours = builder.contours(&values, &[0.]).unwrap();
+        let pieces = split.contours(&values, &[0.]).unwrap();
+        assert!(pieces[0].geometry().0.len() > contours[0].geometry().0.len());
+        assert!((area(&pieces[0]) - area(&contours[0])).abs() < 1e-3);
+
+        let lines = builder.lines(&values, &[0.]).unwrap();
+        let parts = split.lines(&values, &[0.]).unwrap();
+        let vertices =
+            |line: &crate::Line| line.geometry().iter().map(|l| l.0.len()).sum::<usize>();
+        assert!(parts[0].geometry().iter().all(|l| l.0.len() <= 20));
+        assert_eq!(
+            vertices(&parts[0]) - vertices(&lines[0]),
+            parts[0].geometry().0.len() - lines[0].geometry().0.len()
+        );
+    }
+
+    #[test]
+    fn test_complexity_limits() {
+        let values = (0..100)
+            .map(|i| ((i * 7919) % 13) as Float)
+            .collect::<Vec<_>>();
+        let builder = ContourBuilder::new(10, 10, true);
+        let bands = builder.isobands(&values, &[0., 6., 13.]).unwrap();
+        let rings = builder.contours(&values, &[6.]).unwrap()[0]
+            .geometry()
+            .iter()
+            .map(|p| 1 + p.interiors().len())
+            .sum::<usize>();
+
+        let limited = ContourBuilder::from(builder.config()).max_rings(rings);
+        assert!(limited.contours(&values, &[6.]).is_ok());
+        let limited = limited.max_rings(rings - 1);
+        let err = limited.contours(&values, &[6.]).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            crate::ErrorKind::TooManyRings { threshold, max } if *threshold == 6. && *max == rings - 1
+        ));
+
+        let limited = ContourBuilder::new(10, 10, true).max_total_vertices(100);
+        let err = limited.isobands(&values, &[0., 6., 13.]).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            crate::ErrorKind::TooManyVertices { max: 100 }
+        ));
+        let limited = ContourBuilder::new(10, 10, true).max_total_vertices(100_000);
+        let unlimited = limited.isobands(&values, &[0., 6., 13.]).unwrap();
+        assert_eq!(unlimited[0].geometry(), bands[0].geometry());
+    }
+
+    #[test]
+    fn test_round_corners() {
+        let (dx, dy) = (12, 10);
+        let values = (0..dx * dy)
+            .map(|i| (((i * 7919) % 23) as Float / 4.).sin())
+            .collect::<Vec<_>>();
+        let builder = ContourBuilder::new(dx, dy, true).round_corners(3);
+        let contours = builder.contours(&values, &[0.1, 0.6]).unwrap();
+        let unrounded = ContourBuilder::new(dx, dy, true)
+            .contours(&values, &[0.1, 0.6])
+            .unwrap();
+        for (contour, unrounded) in contours.iter().zip(&unrounded) {
+            let vertices = |c: &crate::Contour| {
+                c.geometry()
+                    .iter()
+                    .map(|p| p.exterior().0.len())
+                    .sum::<usize>()
+            };
+            assert!(vertices(contour) > vertices(unrounded));
+            // Each point of the grid stays on the side of the contours given by its value
+            for (i, v) in values.iter().enumerate() {
+                let (x, y) = ((i % dx) as Float + 0.5, (i / dx) as Float + 0.5);
+                let crossings = contour
+                    .geometry()
+                    .iter()
+                    .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+                    .flat_map(|ring| ring.0.windows(2))
+                    .filter(|w| {
+                        let (a, b) = (w[0], w[1]);
+                        (a.y > y) != (b.y > y) && x < (b.x - a.x) * (y - a.y) / (b.y - a.y) + a.x
+                    })
+                    .count();
+                let inside = crossings % 2 == 1;
+                assert_eq!(inside, *v >= contour.threshold(), "{} {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nodata_holes() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0., 0.,
+            0., 2., 2., 2., 2., 0.,
+            0., 2., Float::NAN, Float::NAN, 2., 0.,
+            0., 2., 2., 2., 2., 0.,
+            0., 0., 0., 0., 0., 0.,
+        ];
+        let builder = ContourBuilder::new(6, 5, true);
+        let bands = builder.isobands(&values, &[1., 3.]).unwrap();
+        let polygons = &bands[0].geometry().0;
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].interiors().len(), 1);
+        let hole = &polygons[0].interiors()[0];
+        assert!(hole
+            .0
+            .iter()
+            .all(|p| (2. ..=4.).contains(&p.x) && (2. ..=3.).contains(&p.y)));
+        assert!(polygons[0].exterior().0.iter().all(|p| !p.x.is_nan()));
+    }
+
+    #[test]
+    fn test_footprint() {
+        #[rustfmt::skip]
+        let values = [
+            Float::NAN, Float::NAN, 1., 2.,
+            Float::NAN, 3., 2., 1.,
+            2., 1., 0., 1.,
+        ];
+        let builder = ContourBuilder::new(4, 3, true).x_origin(10.).x_step(2.);
+        let footprint = builder.footprint(&values, true).unwrap();
+        assert_eq!(
+            footprint,
+            MultiPolygon::<Float>(vec![polygon![
+                (x: 10., y: 2.),
+                (x: 10., y: 3.),
+                (x: 18., y: 3.),
+                (x: 18., y: 0.),
+                (x: 14., y: 0.),
+                (x: 14., y: 1.),
+                (x: 12., y: 1.),
+                (x: 12., y: 2.),
+                (x: 10., y: 2.),
+            ]])
+        );
+        // The traced footprint follows the NODATA boundaries of the contours
+        let traced = builder.footprint(&values, false).unwrap();
+        let contours = builder.contours(&values, &[-1.]).unwrap();
+        assert_eq!(&traced, contours[0].geometry());
+    }
+
+    #[test]
+    fn test_gradient_magnitude() {
+        // A cone, whose slope is 1 everywhere but at its apex
+        let (dx, dy) = (9, 9);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float - 4., (i / dx) as Float - 4.);
+                -(x * x + y * y).sqrt()
+            })
+            .collect::<Vec<_>>();
+        let grid = crate::Buffer::new(values, dx, dy).unwrap();
+        let magnitude = crate::gradient_magnitude(&grid, 1., 1.).unwrap();
+        assert_eq!(magnitude.get(4, 4), Some(&0.));
+        assert!((magnitude.get(0, 4).unwrap() - 1.).abs() < 1e-9);
+        assert!((magnitude.get(8, 4).unwrap() - 1.).abs() < 1e-9);
+        let contours = ContourBuilder::new(dx, dy, true)
+            .contours(magnitude.data(), &[0.5])
+            .unwrap();
+        assert_eq!(contours[0].geometry().0[0].interiors().len(), 1);
+    }
+
+    #[test]
+    fn test_slope_aspect() {
+        // A cone (north up, with a step of 10 units), sloping at 10% all around its apex
+        let (dx, dy) = (9, 9);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float - 4., (i / dx) as Float - 4.);
+                100. - (x * x + y * y).sqrt()
+            })
+            .collect::<Vec<_>>();
+        let grid = crate::Buffer::new(values, dx, dy).unwrap();
+        let slope = crate::slope(&grid, 10., -10., crate::SlopeUnit::Percent).unwrap();
+        assert_eq!(slope.get(4, 4), Some(&0.));
+        // The slope of a plane is the same everywhere, including on the borders of the grid
+        let plane = crate::Buffer::new(
+            (0..dx * dy)
+                .map(|i| 2. * (i % dx) as Float + (i / dx) as Float)
+                .collect(),
+            dx,
+            dy,
+        )
+        .unwrap();
+        let slope = crate::slope(&plane, 10., -10., crate::SlopeUnit::Percent).unwrap();
+        assert!(slope
+            .data()
+            .iter()
+            .all(|s| (s - (5. as Float).sqrt() * 10.).abs() < 1e-3));
+        let aspect = crate::aspect(&grid, 10., -10.).unwrap();
+        assert!(aspect.get(4, 4).unwrap().is_nan());
+        // The rows are stored from north to south
+        assert_eq!(aspect.get(4, 1), Some(&0.));
+        assert_eq!(aspect.get(7, 4), Some(&90.));
+        assert_eq!(aspect.get(4, 7), Some(&180.));
+        assert_eq!(aspect.get(1, 4), Some(&270.));
+        assert!((aspect.get(1, 1).unwrap() - 315.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_resample() {
+        let grid = crate::Buffer::new(vec![0., 1., 2., 3., 4., 5.], 3, 2).unwrap();
+        // The values of the centers of the cells are kept when resampling to the same grid
+        for method in [crate::Resampling::Bilinear, crate::Resampling::Bicubic] {
+            assert_eq!(crate::resample(&grid, 3, 2, method).unwrap(), grid);
+        }
+        let bilinear = crate::resample(&grid, 6, 4, crate::Resampling::Bilinear).unwrap();
+        assert_eq!(bilinear.data()[..6], [0., 0.25, 0.75, 1.25, 1.75, 2.]);
+        // A linear ramp is reproduced by the bicubic interpolation (far from the borders)
+        let ramp = crate::Buffer::new((0..8).map(|i| i as Float).collect(), 8, 1).unwrap();
+        let bicubic = crate::resample(&ramp, 16, 1, crate::Resampling::Bicubic).unwrap();
+        assert_eq!(
+            bicubic.data()[4..12],
+            [1.75, 2.25, 2.75, 3.25, 3.75, 4.25, 4.75, 5.25]
+        );
+        assert!(crate::resample(&grid, 0, 2, crate::Resampling::Bilinear).is_err());
+    }
+
+    #[test]
+    fn test_warp() {
+        use geo_types::{coord, Rect};
+
+        let values = (0..20).map(|i| ((i * 7) % 5) as Float).collect();
+        let grid = crate::Buffer::new(values, 5, 4).unwrap();
+        let extent = Rect::new(coord! { x: -10., y: 40. }, coord! { x: 0., y: 48. });
+        // The identity projection resamples the grid
+        for method in [crate::Resampling::Bilinear, crate::Resampling::Bicubic] {
+            let warped = crate::warp(&grid, extent, extent, 10, 8, method, Some).unwrap();
+            assert_eq!(warped, crate::resample(&grid, 10, 8, method).unwrap());
+        }
+        // The cells that can't be projected are NODATA
+        let warped = crate::warp(
+            &grid,
+            extent,
+            extent,
+            5,
+            4,
+            crate::Resampling::Nearest,
+            |p| (p.x < -5.).then_some(p),
+        )
+        .unwrap();
+        assert_eq!(warped.get(1, 2), grid.get(1, 2));
+        assert!(warped.get(3, 2).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_mosaic() {
+        use crate::{Buffer, Mosaic, Overlap};
+
+        let patches = [
+            (
+                Buffer::new(vec![1., 2., Float::NAN, 4.], 2, 2).unwrap(),
+                0,
+                0,
+            ),
+            (Buffer::filled(3., 2, 3).unwrap(), 1, 1),
+        ];
+        let expected = [
+            (
+                Overlap::First,
+                [Float::NAN, 4., 3., Float::NAN, 3., 3., Float::NAN, 3., 3.],
+            ),
+            (
+                Overlap::Last,
+                [Float::NAN, 3., 3., Float::NAN, 3., 3., Float::NAN, 3., 3.],
+            ),
+            (
+                Overlap::Min,
+                [Float::NAN, 3., 3., Float::NAN, 3., 3., Float::NAN, 3., 3.],
+            ),
+            (
+                Overlap::Max,
+                [Float::NAN, 4., 3., Float::NAN, 3., 3., Float::NAN, 3., 3.],
+            ),
+        ];
+        for (overlap, expected) in expected {
+            let mut mosaic = Mosaic::new(overlap);
+            patches
+                .iter()
+                .for_each(|(b, x, y)| mosaic.push(b.clone(), *x, *y));
+            let buffer = mosaic.to_buffer().unwrap();
+            assert_eq!((buffer.dx(), buffer.dy()), (3, 4));
+            let data = &buffer.data()[3..];
+            for (value, expected) in data.iter().zip(expected) {
+                assert!(value == &expected || (value.is_nan() && expected.is_nan()));
+            }
+            assert_eq!(mosaic.get(0, 0), Some(1.));
+            assert!(mosaic.get(0, 1).unwrap().is_nan());
+            assert_eq!(mosaic.get(3, 0), None);
+        }
+        let mut mosaic = Mosaic::new(Overlap::Last);
+        patches
+            .iter()
+            .for_each(|(b, x, y)| mosaic.push(b.clone(), *x, *y));
+        assert_eq!(mosaic.extents()[1].max(), geo_types::coord! { x: 3, y: 4 });
+        // Each buffer has its own NODATA value
+        let mut filled = Mosaic::new(Overlap::Last);
+        filled.push_with_nodata(Buffer::new(vec![-1., 5.], 1, 2).unwrap(), 0, 0, -1.);
+        filled.push_with_nodata(Buffer::new(vec![0., 2., 6.], 3, 1).unwrap(), 0, 1, 0.);
+        filled.push(Buffer::new(vec![-1., 0.], 2, 1).unwrap(), 1, 0);
+        assert!(filled.get(0, 0).unwrap().is_nan());
+        assert_eq!(filled.row(0)[1..], [-1., 0.]);
+        assert_eq!(filled.row(1), [5., 2., 6.]);
+        assert_eq!(filled.get(0, 1), Some(5.));
+        // Contouring the rows of the mosaic gives the same rings as contouring the merged buffer
+        let builder = ContourBuilder::new(3, 4, true);
+        let mut stream = builder.ring_stream(2.5);
+        let mut rings = Vec::new();
+        for row in mosaic.rows() {
+            stream.push_row(&row, |ring| rings.push(ring)).unwrap();
+        }
+        stream.finish(|ring| rings.push(ring)).unwrap();
+        let merged = builder
+            .lines(mosaic.to_buffer().unwrap().data(), &[2.5])
+            .unwrap();
+        assert_eq!(rings.len(), merged[0].geometry().0.len());
+    }
+
+    #[test]
+    fn test_dissolve_bands() {
+        #[rustfmt::skip]
+        let values: [Float; 48] = [
+            0., 0., 0., 0., 0., 0., 0., 0.,
+            0., 1., 1., 2., 2., 1., 1., 0.,
+            0., 1., 2., 3., 3., 2., 1., 0.,
+            0., 1., 2., 3., 3., 2., 1., 0.,
+            0., 1., 1., 2., 2., 1., 1., 0.,
+            0., 0., 0., 0., 0., 0., 0., 0.,
+        ];
+        let thresholds = [Float::NEG_INFINITY, 0.5, 1.5, Float::INFINITY];
+        let tiles = (0..2).flat_map(|i| {
+            let tile = values
+                .chunks(8)
+                .flat_map(|row| row[i * 4..(i + 1) * 4].to_vec())
+                .collect::<Vec<_>>();
+            ContourBuilder::new(4, 6, false)
+                .x_origin(i as Float * 4.)
+                .isobands(&tile, &thresholds)
+                .unwrap()
+        });
+        let coverage = crate::dissolve_bands(tiles, 1e-6);
+        assert_eq!(coverage.len(), 3);
+        assert!(coverage.windows(2).all(|w| w[0].max_v() == w[1].min_v()));
+        // The polygons of a class are merged across the seam of the tiles
+        assert_eq!(coverage[0].geometry().0.len(), 1);
+        assert_eq!(coverage[0].geometry().0[0].interiors().len(), 1);
+        assert_eq!(coverage[2].geometry().0.len(), 1);
+        assert_eq!(
+            coverage[2].bbox(),
+            crate::area::polygons_bbox(coverage[2].geometry())
+        );
+    }
+
+    #[test]
+    fn test_difference_contours() {
+        let builder = ContourBuilder::new(5, 5, false);
+        let before = [0.; 25];
+        #[rustfmt::skip]
+        let after = [
+            0., 0., 0., 0., 0.,
+            0., 2., 0., -2., 0.,
+            0., 0., 0., 0., 0.,
+            0., 0., Float::NAN, 0., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let bands = builder
+            .difference_contours(
+                &after,
+                &before,
+                &[Float::NEG_INFINITY, -1., 1., Float::INFINITY],
+            )
+            .unwrap();
+        let changes = bands.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+        assert_eq!(changes, [Change::Loss, Change::Stable, Change::Gain]);
+        // The NODATA cell is a hole of the stable band
+        assert_eq!(bands[1].0.geometry().0[0].interiors().len(), 3);
+        assert!(builder
+            .difference_contours(&after, &before[1..], &[0., 1.])
+            .is_err());
+    }
+
+    #[test]
+    fn test_relative_thresholds() {
+        let values = [Float::NAN, 1., 5., -3., 20.];
+        let thresholds = crate::relative_thresholds(&values, &[0.1, 0.5, 0.9], None);
+        assert_eq!(thresholds, [2., 10., 18.]);
+        let contours = ContourBuilder::new(5, 1, false)
+            .contours(&values, &thresholds)
+            .unwrap();
+        assert_eq!(contours.len(), 3);
+        assert!(crate::relative_thresholds(&[Float::NAN], &[0.5], None).is_empty());
+    }
+
+    #[test]
+    fn test_sigma_thresholds() {
+        let values = [1., Float::NAN, 3., 1., 3.];
+        assert_eq!(
+            crate::sigma_thresholds(&values, &[-2., -1., 0., 1.5]),
+            [0., 1., 2., 3.5]
+        );
+        assert!(crate::sigma_thresholds(&[], &[1.]).is_empty());
+    }
+
+    #[test]
+    fn test_threshold_generators() {
+        let values = [1., 1., 1., 1., 3., 1., 1., 1., 1.];
+        let builder = ContourBuilder::new(3, 3, false);
+        let sigma = crate::Sigma { k: vec![1., 2.] };
+        let lines = builder.lines_auto(&values, &sigma).unwrap();
+        let expected = crate::sigma_thresholds(&values, &[1., 2.]);
+        assert_eq!(
+            lines.iter().map(|l| l.threshold()).collect::<Vec<_>>(),
+            expected
+        );
+        // A closure generating the thresholds between the minimum and the maximum
+        let bounds = |values: &[Float]| {
+            let min = values.iter().copied().fold(Float::INFINITY, Float::min);
+            let max = values.iter().copied().fold(Float::NEG_INFINITY, Float::max);
+            vec![min, (min + max) / 2., max + 1.]
+        };
+        let bands = builder.isobands_auto(&values, &bounds).unwrap();
+        assert_eq!(bands.len(), 2);
+        assert_eq!((bands[1].min_v(), bands[1].max_v()), (2., 4.));
+        let generator: &dyn crate::ThresholdGenerator = &sigma;
+        assert!(builder.contours_auto(&values[1..], generator).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let values = (0..100)
+            .map(|i| (((i * 37) % 11) as Float).sqrt())
+            .collect::<Vec<_>>();
+        let builder = ContourBuilder::new(10, 10, false);
+        let estimate = builder.validate(&values, &[1.2, 2.5]).unwrap();
+        // The estimate is the number of vertices of the rings (whose first vertex is repeated)
+        let vertices = builder
+            .lines(&values, &[1.2, 2.5])
+            .unwrap()
+            .iter()
+            .flat_map(|line| line.geometry().0.iter().map(|ring| ring.0.len() - 1))
+            .sum::<usize>();
+        assert_eq!(estimate, vertices);
+        assert!(builder.validate(&values[1..], &[1.]).is_err());
+        assert!(builder.validate(&values, &[1., Float::NAN]).is_err());
+        assert!(builder
+            .y_origin(Float::INFINITY)
+            .validate(&values, &[1.])
+            .is_err());
+    }
+
+    #[test]
+    fn test_quantize() {
+        use geo_types::{coord, Rect};
+
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0., 0., 0., 0.,
+            0., 1., 0., 0., 1., 1., 1., 0.,
+            0., 0., 0., 0., 1., 1., 1., 0.,
+            0., 0., 0., 0., 1., 1., 1., 0.,
+            0., 0., 0., 0., 0., 0., 0., 0.,
+        ];
+        let extent = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 8., y: 5. });
+        let builder = ContourBuilder::new(8, 5, true).quantize(extent, 16);
+        let contours = builder.contours(&values, &[0.5]).unwrap();
+        assert_eq!(contours[0].geometry().0.len(), 2);
+        let exterior = &contours[0].geometry().0[1].exterior().0;
+        assert!(exterior
+            .iter()
+            .all(|p| p.x.fract() == 0. && p.y.fract() == 0. && p.x <= 16. && p.y <= 16.));
+        // The smallest ring collapses at a coarser resolution
+        let builder = ContourBuilder::from(builder.config()).quantize(extent, 4);
+        let contours = builder.contours(&values, &[0.5]).unwrap();
+        assert_eq!(contours[0].geometry().0.len(), 1);
+        assert_eq!(
+            contours[0].bbox(),
+            Some(Rect::new(coord! { x: 2., y: 1. }, coord! { x: 4., y: 3. }))
+        );
+    }
+
+    #[test]
+    fn test_encoded_polylines() {
+        // The example of the specification of the algorithm
+        let line = crate::Line::new(
+            MultiLineString(vec![
+                line_string![(x: -120.2, y: 38.5), (x: -120.95, y: 40.7), (x: -126.453, y: 43.252)],
+                line_string![(x: 0., y: 0.)],
+            ]),
+            1.,
+        );
+        assert_eq!(
+            line.to_encoded_polylines(5),
+            ["_p~iF~ps|U_ulLnnqC_mqNvxq`@", "??"]
+        );
+        assert_eq!(line.to_encoded_polylines(0)[0], "mAnFC@CH");
+    }
+
+    #[test]
+    fn test_band_labels() {
+        let c = ContourBuilder::new(4, 4, false);
+        #[rustfmt::skip]
+        let bands = c.isobands(&[
+            0., 1., 1., 0.,
+            1., 2., 2., 1.,
+            1., 2., 2., 1.,
+            0., 1., 1., 0.,
+        ], &[Float::NEG_INFINITY, 0.5, 1.5, Float::INFINITY]).unwrap();
+        let labels = bands.iter().map(|b| b.label("")).collect::<Vec<_>>();
+        assert_eq!(labels, ["< 0.5", "0.5–1.5", "≥ 1.5"]);
+    }
+
+    #[test]
+    fn test_ring_utilities() {
+        use crate::ring::{self, Orientation};
+        let c = ContourBuilder::new(5, 5, false);
+        #[rustfmt::skip]
+        let res = c.contours(&[
+            1., 1., 1., 1., 1.,
+            1., 0., 0., 0., 1.,
+            1., 0., 0., 0., 1.,
+            1., 0., 0., 0., 1.,
+            1., 1., 1., 1., 1.,
+        ], &[0.5]).unwrap();
+        let polygon = &res[0].geometry().0[0];
+        let (exterior, hole) = (&polygon.exterior().0, &polygon.interiors()[0].0);
+        assert_eq!(ring::orientation(exterior), Some(Orientation::Clockwise));
+        assert_eq!(ring::orientation(hole), Some(Orientation::CounterClockwise));
+        assert_eq!(ring::area(exterior), 24.5);
+        assert_eq!(ring::contains_ring(exterior, hole), 1);
+        assert_eq!(ring::contains_ring(hole, exterior), -1);
+        let center = ring::centroid(hole).unwrap();
+        assert_eq!(center, geo_types::coord! { x: 2.5, y: 2.5 });
+        assert_eq!(ring::winding_number(exterior, &center), -1);
+        assert_eq!(ring::winding_number(hole, &center), 1);
+        assert_eq!(
+            ring::contains(exterior, &geo_types::coord! { x: 0., y: 2. }),
+            0
+        );
+        assert_eq!(ring::area(&[]), 0.);
+    }
+
+    #[test]
+    fn test_stitch_segments() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0.,
+            0., 1., 0., 0.,
+            0., 0., 0., 1.,
+            0., 0., 0., 0.,
+        ];
+        let segments = crate::contour_segments(&values, 0.5, 4, 4).unwrap();
+        assert_eq!(
+            crate::stitch_segments(&segments).unwrap(),
+            crate::contour_rings(&values, 0.5, 4, 4).unwrap()
+        );
+        // The segments of the cells of a single row leave open fragments
+        let mut isoring = crate::IsoRingBuilder::new(4, 4);
+        let mut rings = Vec::new();
+        for segment in segments.iter().filter(|s| s.cell.1 == 0) {
+            isoring
+                .stitch_segment(segment.start, segment.end, &mut rings)
+                .unwrap();
+        }
+        assert!(rings.is_empty());
+        let fragments = isoring.take_open_fragments();
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(fragments[0].len(), 3);
+        assert!(isoring.take_open_fragments().is_empty());
+    }
+
+    #[test]
+    fn test_densify() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0.,
+            0., 1., 1., 1., 0.,
+            0., 1., 1., 1., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let builder = ContourBuilder::new(5, 4, true).x_step(3.).y_step(3.);
+        let contours = builder.contours(&values, &[0.5]).unwrap();
+        let exterior = &contours[0].geometry().0[0].exterior().0;
+        let builder = builder.densify(0.5);
+        let densified = builder.contours(&values, &[0.5]).unwrap();
+        let densified = &densified[0].geometry().0[0].exterior().0;
+        assert!(densified.len() > exterior.len());
+        assert_eq!(densified.first(), densified.last());
+        assert!(exterior.iter().all(|p| densified.contains(p)));
+        assert!((crate::ring::area(densified) - crate::ring::area(exterior)).abs() < 1e-9);
+        assert!(densified.windows(2).all(|s| {
+            let (a, b) = (s[0], s[1]);
+            ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt() <= 0.5 + 1e-9
+        }));
+    }
+
+    #[test]
+    fn test_non_crossing() {
+        #[rustfmt::skip]
+        let values = [
+            2., 5., 6., 1.,
+            6., 9., 7., 2.,
+            7., 2., 5., 7.,
+            2., 9., 4., 5.,
+        ];
+        let crossing = |builder: &ContourBuilder| {
+            let lines = builder.lines(&values, &[4.5, 4.55]).unwrap();
+            let side = |p: &crate::Pt, q: &crate::Pt, r: &crate::Pt| {
+                (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x)
+            };
+            let (lower, upper) = (&lines[0].geometry().0, &lines[1].geometry().0);
+            lower.iter().any(|a| {
+                upper.iter().any(|b| {
+                    a.0.windows(2).any(|s| {
+                        b.0.windows(2).any(|t| {
+                            side(&s[0], &s[1], &t[0]) * side(&s[0], &s[1], &t[1]) < 0.
+                                && side(&t[0], &t[1], &s[0]) * side(&t[0], &t[1], &s[1]) < 0.
+                        })
+                    })
+                })
+            })
+        };
+        let builder = ContourBuilder::new(4, 4, true).round_corners(4);
+        assert!(crossing(&builder));
+        assert!(!crossing(&builder.non_crossing(true)));
+        let builder = ContourBuilder::new(4, 4, true)
+            .round_corners(4)
+            .supersample(2)
+            .non_crossing(true);
+        assert!(!crossing(&builder));
+    }
+
+    #[test]
+    fn test_hole_orientation() {
+        use crate::ring::{self, Orientation};
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0., 0., 0.,
+            0., 1., 1., 1., 1., 1., 0.,
+            0., 1., 2., 2., 2., 1., 0.,
+            0., 1., 2., 0., 2., 1., 0.,
+            0., 1., 2., 2., 2., 1., 0.,
+            0., 1., 1., 1., 1., 1., 0.,
+            0., 0., 0., 0., 0., 0., 0.,
+        ];
+        let holes = |orientation: Option<Orientation>| {
+            let mut builder = ContourBuilder::new(7, 7, true);
+            if let Some(orientation) = orientation {
+                builder = builder.hole_orientation(orientation);
+            }
+            let bands = builder.isobands(&values, &[0.5, 1.5, 3.]).unwrap();
+            bands
+                .iter()
+                .flat_map(|band| band.geometry().iter())
+                .flat_map(|polygon| polygon.interiors())
+                .map(|hole| ring::orientation(&hole.0).unwrap())
+                .collect::<Vec<_>>()
+        };
+        // The holes coming from an exterior ring of the upper threshold of a band are
+        // clockwise, the ones coming from a hole of its lower threshold counterclockwise
+        assert_eq!(
+            holes(None),
+            [
+                Orientation::Clockwise,
+                Orientation::CounterClockwise,
+                Orientation::CounterClockwise
+            ]
+        );
+        for orientation in [Orientation::Clockwise, Orientation::CounterClockwise] {
+            assert_eq!(holes(Some(orientation)), [orientation; 3]);
+        }
+    }
+
+    #[cfg(feature = "synthetic")]
+    #[test]
+    fn test_synthetic_grids() {
+        use crate::synthetic::{checkerboard, fbm, perlin, ramp};
+        let board = checkerboard(4, 2, 2).unwrap();
+        assert_eq!(board.data(), [0., 0., 1., 1., 0., 0., 1., 1.]);
+        let ramp = ramp(3, 2, std::f64::consts::FRAC_PI_2 as Float).unwrap();
+        assert!(ramp
+            .data()
+            .iter()
+            .zip([0., 0., 0., 1., 1., 1.])
+            .all(|(a, b)| (a - b).abs() < 1e-6));
+        // The noise is deterministic, continuous and null on the points of its lattice
+        let noise = perlin(64, 64, 16., 7).unwrap();
+        assert_eq!(noise, perlin(64, 64, 16., 7).unwrap());
+        assert_ne!(noise, perlin(64, 64, 16., 8).unwrap());
+        assert_eq!(noise.get(16, 32), Some(&0.));
+        assert!(noise
+            .data()
+            .chunks(64)
+            .all(|row| row.windows(2).all(|w| (w[0] - w[1]).abs() < 0.5)));
+        let terrain = fbm(64, 64, 32., 5, 7).unwrap();
+        assert!(terrain.data().iter().all(|v| v.abs() <= 1.));
+        let c = ContourBuilder::new(64, 64, true);
+        assert!(!c.contours(terrain.data(), &[0.]).unwrap()[0]
+            .geometry()
+            .0
+            .is_empty());
+    }
+
+    #[test]
+    fn test_pad() {
+        use crate::{pad, Buffer, Padding};
+        let grid = Buffer::new(vec![1., 1., 1., 1.], 2, 2).unwrap();
+        // The contour of a plateau touching the edges of the grid is closed by
+        // a constant padding, and follows the padded border otherwise
+        let contour = |grid: &Buffer<Float>, n: usize| {
+            let builder = ContourBuilder::new(grid.dx(), grid.dy(), false)
+                .x_origin(-(n as Float))
+                .y_origin(-(n as Float));
+            builder.contours(grid.data(), &[0.5]).unwrap()[0]
+                .bbox()
+                .unwrap()
+        };
+        let padded = pad(&grid, 2, Padding::Constant(0.)).unwrap();
+        assert_eq!(contour(&padded, 2), contour(&grid, 0));
+        let padded = pad(&grid, 2, Padding::Replicate).unwrap();
+        assert_eq!(contour(&padded, 2).width(), 6.);
+        let mirrored = pad(&grid, 3, Padding::Mirror).unwrap();
+        assert!(mirrored.data().iter().all(|v| *v == 1.));
+        assert!(pad(&Buffer::new(vec![], 0, 0).unwrap(), 1, Padding::Replicate).is_err());
+    }
+
+    #[test]
+    fn test_registration() {
+        use crate::Registration;
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0.,
+            0., 1., 2., 1.,
+            0., 1., 2., 1.,
+            0., 0., 0., 0.,
+        ];
+        let builder = ContourBuilder::new(4, 4, true)
+            .x_origin(100.)
+            .y_origin(50.)
+            .x_step(2.)
+            .y_step(-2.);
+        let area = builder.lines(&values, &[0.5, 1.5]).unwrap();
+        let builder = builder.registration(Registration::Point);
+        let point = builder.lines(&values, &[0.5, 1.5]).unwrap();
+        // The isolines inside the grid are moved by half a cell
+        let moved = |line: &crate::Line| {
+            line.geometry().0[0]
+                .0
+                .iter()
+                .map(|p| geo_types::coord! { x: p.x - 1., y: p.y + 1. })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(moved(&area[1]), point[1].geometry().0[0].0);
+        // The isolines crossing the border are clamped to the extent of the values
+        let bbox = point[0].bbox().unwrap();
+        assert_eq!((bbox.max().x, bbox.min().y), (106., 44.5));
+        let bbox = area[0].bbox().unwrap();
+        assert_eq!((bbox.max().x, bbox.min().y), (108., 43.5));
+        // The supersampled isolines are registered the same way
+        let supersampled = builder.supersample(2).lines(&values, &[0.5]).unwrap();
+        assert_eq!(supersampled[0].bbox().unwrap().max().x, 106.);
+    }
+
+    #[test]
+    fn test_extent() {
+        use crate::Registration;
+        let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+        // The extent of the grid is the extent of the cells of its border
+        let builder = ContourBuilder::new(3, 3, false).extent(-30., 60., 0., 90.);
+        let contours = builder.contours(&values, &[0.5]).unwrap();
+        let bbox = contours[0].bbox().unwrap();
+        assert_eq!((bbox.min().x, bbox.min().y), (-20., 70.));
+        assert_eq!((bbox.max().x, bbox.max().y), (-10., 80.));
+        // The values at the points spanning the same extent are twice as far apart
+        let builder = ContourBuilder::new(3, 3, false)
+            .registration(Registration::Point)
+            .extent(-30., 60., 0., 90.);
+        let contours = builder.contours(&values, &[0.5]).unwrap();
+        let bbox = contours[0].bbox().unwrap();
+        assert_eq!((bbox.min().x, bbox.min().y), (-22.5, 67.5));
+        assert_eq!((bbox.max().x, bbox.max().y), (-7.5, 82.5));
+    }
+
+    #[test]
+    fn test_contour_rings_multi() {
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0.,
+            0., 1., 0., 0.,
+            0., 0., 2., 0.,
+            0., 0., 0., 0.,
+        ];
+        let thresholds = [0.5, 1.5, 2.5];
+        let rings = crate::contour_rings_multi(&values, &thresholds, 4, 4).unwrap();
+        assert_eq!(rings.len(), 3);
+        for (rings, threshold) in rings.iter().zip(thresholds) {
+            assert_eq!(
+                rings,
+                &crate::contour_rings(&values, threshold, 4, 4).unwrap()
+            );
+        }
+        assert!(rings[2].is_empty());
+        assert!(crate::contour_rings_multi(&values, &[], 4, 4)
+            .unwrap()
+            .is_empty());
+        assert!(crate::contour_rings_multi(&values, &[], 3, 4).is_err());
+    }
+
+    #[test]
+    fn test_compute_in_extents() {
+        use geo_types::{coord, Rect};
+
+        #[rustfmt::skip]
+        let values = [
+            1., 0., 0., 0., 0.,
+            0., 0., 0., 0., 0.,
+            0., 0., 1., 1., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let mut builder = crate::IsoRingBuilder::new(5, 4);
+        let all = builder.compute(&values, 0.5).unwrap();
+        // The whole grid (and beyond)
+        let whole = Rect::new(coord! { x: 0, y: 0 }, coord! { x: 9, y: 9 });
+        assert_eq!(builder.compute_in(&values, 0.5, &[whole]).unwrap(), all);
+        // Two overlapping halves give the same rings
+        let left = Rect::new(coord! { x: 0, y: 0 }, coord! { x: 3, y: 4 });
+        let right = Rect::new(coord! { x: 2, y: 0 }, coord! { x: 5, y: 4 });
+        let mut rings = builder.compute_in(&values, 0.5, &[right, left]).unwrap();
+        assert_eq!(rings.len(), all.len());
+        rings.retain(|ring| !all.contains(ring));
+        assert_eq!(rings.len(), 0);
+        // The ring crossing the boundary of the extent is left open
+        let part = Rect::new(coord! { x: 0, y: 0 }, coord! { x: 3, y: 2 });
+        let rings = builder.compute_in(&values, 0.5, &[part]).unwrap();
+        assert_eq!(rings, all[..1]);
+        assert_eq!(builder.take_open_fragments().len(), 1);
+        assert!(builder.compute_in(&values, 0.5, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_quadtree() {
+        // Plateaus, a NODATA region, a noisy region and values on the border of the grid
+        let (dx, dy) = (37, 29);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = (i % dx, i / dx);
+                if (20..25).contains(&x) && (3..8).contains(&y) {
+                    Float::NAN
+                } else if (5..12).contains(&x) && (15..22).contains(&y) {
+                    ((x * 7 + y * 13) % 5) as Float
+                } else if x > 30 || y < 2 {
+                    3.
+                } else if (x as isize - 12).pow(2) + (y as isize - 10).pow(2) < 40 {
+                    2.
+                } else {
+                    0.
+                }
+            })
+            .collect::<Vec<_>>();
+        let tree = crate::QuadTree::new(&values, dx, dy, 4).unwrap();
+        assert!(tree.leaves() < dx * dy / 4);
+        let same = |a: &Float, b: &Float| a == b || (a.is_nan() && b.is_nan());
+        assert!(tree.values().iter().zip(&values).all(|(a, b)| same(a, b)));
+        assert_eq!(tree.get(dx, 0), None);
+        // The rings are the ones of the whole grid, whatever their first point
+        let normalize = |mut rings: Vec<crate::Ring>| {
+            for ring in rings.iter_mut() {
+                ring.pop();
+                let first = (0..ring.len())
+                    .min_by(|a, b| {
+                        (ring[*a].x, ring[*a].y)
+                            .partial_cmp(&(ring[*b].x, ring[*b].y))
+                            .unwrap()
+                    })
+                    .unwrap();
+                ring.rotate_left(first);
+            }
+            rings.sort_by(|a, b| (a[0].x, a[0].y).partial_cmp(&(b[0].x, b[0].y)).unwrap());
+            rings
+        };
+        for threshold in [0.5, 1.5, 2.5, 3.5] {
+            let expected = crate::contour_rings(&values, threshold, dx, dy).unwrap();
+            let rings = tree.contour_rings(threshold).unwrap();
+            assert_eq!(normalize(rings), normalize(expected));
+        }
+        assert!(tree.extents(4.5).is_empty());
+    }
+
+    #[test]
+    fn test_rle_buffer() {
+        // Classes, a NODATA region, and classes on the border of the grid
+        let (dx, dy) = (41, 23);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = (i % dx, i / dx);
+                if (20..25).contains(&x) && (3..8).contains(&y) {
+                    Float::NAN
+                } else if x > 35 || y == 0 {
+                    3.
+                } else if (x as isize - 10).pow(2) + (y as isize - 12).pow(2) < 30 {
+                    2.
+                } else if x % 9 == 4 && y % 5 == 2 {
+                    1.
+                } else {
+                    0.
+                }
+            })
+            .collect::<Vec<_>>();
+        let grid = crate::RleBuffer::new(&values, dx, dy).unwrap();
+        assert!(grid.runs() < dx * dy / 4);
+        let same = |a: &Float, b: &Float| a == b || (a.is_nan() && b.is_nan());
+        assert!(grid.values().iter().zip(&values).all(|(a, b)| same(a, b)));
+        assert_eq!(grid.get(0, dy), None);
+        for threshold in [0.5, 1.5, 2.5] {
+            let extents = grid.extents(threshold);
+            let traversed = extents
+                .iter()
+                .map(|e| e.width() * e.height())
+                .sum::<usize>();
+            assert!(traversed < dx * dy / 2);
+            assert_eq!(
+                grid.contour_rings(threshold).unwrap(),
+                crate::contour_rings(&values, threshold, dx, dy).unwrap()
+            );
+        }
+        assert!(grid.extents(3.5).is_empty());
+    }
+
+    #[test]
+    fn test_zonal_statistics() {
+        let (dx, dy) = (24, 18);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float, (i / dx) as Float);
+                (x / 3.).sin() * (y / 4.).cos() * 10.
+            })
+            .collect::<Vec<_>>();
+        let builder = ContourBuilder::new(dx, dy, true).extent(100., 40., 148., 58.);
+        // The bands cover all the samples, each of them in a single polygon
+        let thresholds = [Float::NEG_INFINITY, -5., 0., 5., Float::INFINITY];
+        let bands = builder.isobands(&values, &thresholds).unwrap();
+        let statistics = builder.band_statistics(&values, &bands).unwrap();
+        let count = statistics.iter().flatten().map(|s| s.count).sum::<usize>();
+        assert_eq!(count, dx * dy);
+        let sum = statistics.iter().flatten().map(|s| s.sum).sum::<Float>();
+        assert!((sum - values.iter().sum::<Float>()).abs() < 1e-3);
+        for (band, statistics) in bands.iter().zip(&statistics) {
+            assert_eq!(statistics.len(), band.geometry().0.len());
+            for s in statistics.iter().filter(|s| s.count > 0) {
+                assert!(s.min >= band.min_v() && s.max < band.max_v());
+            }
+        }
+        // The contours cover the samples greater than or equal to their threshold
+        let contours = builder.contours(&values, &[2.5]).unwrap();
+        let statistics = builder.contour_statistics(&values, &contours).unwrap();
+        let count = statistics[0].iter().map(|s| s.count).sum::<usize>();
+        assert_eq!(count, values.iter().filter(|v| **v >= 2.5).count());
+        assert!(statistics[0].iter().all(|s| s.mean().unwrap() >= 2.5));
+        assert_eq!(crate::ZonalStatistics::default().mean(), None);
+        assert!(builder.band_statistics(&values[1..], &bands).is_err());
+    }
+
+    #[test]
+    fn test_band_index() {
+        let (dx, dy) = (40, 30);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float, (i / dx) as Float);
+                (x / 2.5).sin() * (y / 3.5).cos() * 10. + 0.1
+            })
+            .collect::<Vec<_>>();
+        let builder = ContourBuilder::new(dx, dy, true).x_origin(-20.).x_step(0.5);
+        let thresholds = [-20., -6., -2., 2., 6., 20.];
+        let bands = builder.isobands(&values, &thresholds).unwrap();
+        let index = crate::BandIndex::new(&bands);
+        // Each sample is in the band of its value
+        for (i, value) in values.iter().enumerate() {
+            let point = geo_types::coord! {
+                x: -20. + ((i % dx) as Float + 0.5) * 0.5,
+                y: (i / dx) as Float + 0.5,
+            };
+            let (band, polygon) = index.classify_polygon(point).unwrap();
+            assert!(bands[band].min_v() <= *value && *value < bands[band].max_v());
+            let polygon = &bands[band].geometry().0[polygon];
+            assert_eq!(crate::ring::contains(&polygon.exterior().0, &point), 1);
+        }
+        assert_eq!(index.classify(geo_types::coord! { x: 0., y: 0. }), None);
+        assert_eq!(
+            crate::BandIndex::new(&[]).classify(geo_types::coord! { x: 0., y: 0. }),
+            None
+        );
+        // A point at the ordinate of the first point of a ring is not on its boundary
+        let ring = &bands[0].geometry().0[0].exterior().0;
+        let point = geo_types::coord! { x: -100., y: ring[0].y };
+        assert_eq!(crate::ring::contains(ring, &point), -1);
+    }
+
+    #[cfg(feature = "mesh")]
+    #[test]
+    fn test_mesh() {
+        let (dx, dy) = (20, 16);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float, (i / dx) as Float);
+                (x / 2.).sin() * (y / 3.).cos() * 10.
+            })
+            .collect::<Vec<_>>();
+        let builder = ContourBuilder::new(dx, dy, true).x_step(2.).y_step(-1.);
+        let bands = builder.isobands(&values, &[-10., -3., 3., 10.]).unwrap();
+        for band in &bands {
+            let mesh = band.to_mesh().unwrap();
+            let rings = band
+                .geometry()
+                .0
+                .iter()
+                .map(|p| {
+                    p.exterior().0.len() - 1
+                        + p.interiors().iter().map(|r| r.0.len() - 1).sum::<usize>()
+                })
+                .sum::<usize>();
+            assert_eq!(mesh.vertices.len(), rings);
+            assert!(mesh.indices.iter().all(|i| (*i as usize) < rings));
+            // The triangles cover the polygons
+            let area = mesh
+                .indices
+                .chunks(3)
+                .map(|t| {
+                    let [a, b, c] = [t[0], t[1], t[2]].map(|i| mesh.vertices[i as usize]);
+                    ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])).abs() as f64
+                        / 2.
+                })
+                .sum::<f64>();
+            let expected = band
+                .geometry()
+                .0
+                .iter()
+                .map(|p| {
+                    crate::area::area(&p.exterior().0).abs()
+                        - p.interiors()
+                            .iter()
+                            .map(|r| crate::area::area(&r.0).abs())
+                            .sum::<f64>()
+                })
+                .sum::<f64>()
+                / 2.;
+            assert!((area - expected).abs() < 1e-3 * expected.max(1.));
+        }
+        assert_eq!(
+            crate::Mesh::from_polygons(&MultiPolygon(vec![]))
+                .unwrap()
+                .triangles(),
+            0
+        );
+    }
+
+    #[cfg(feature = "lyon")]
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn test_lyon_paths() {
+        use lyon_path::PathEvent;
+
+        let builder = ContourBuilder::new(5, 5, false);
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0.,
+            0., 2., 2., 2., 0.,
+            0., 2., 0., 2., 0.,
+            0., 2., 2., 2., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let contour = builder.contours(&values, &[1.]).unwrap().remove(0);
+        let events = contour.to_path().iter().collect::<Vec<_>>();
+        // The exterior ring and its hole, as closed subpaths
+        let ends = events
+            .iter()
+            .filter_map(|e| match e {
+                PathEvent::End { close, .. } => Some(*close),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ends, [true, true]);
+        let polygon = &contour.geometry().0[0];
+        let edges = polygon.exterior().0.len() - 1 + polygon.interiors()[0].0.len() - 1;
+        let lines = events
+            .iter()
+            .filter(|e| matches!(e, PathEvent::Line { .. }))
+            .count();
+        assert_eq!(lines + 2, edges);
+        if let PathEvent::Begin { at } = events[0] {
+            assert_eq!(
+                (at.x, at.y),
+                (
+                    polygon.exterior().0[0].x as f32,
+                    polygon.exterior().0[0].y as f32
+                )
+            );
+        } else {
+            panic!("the path doesn't begin with a subpath");
+        }
+        // The isolines are open subpaths
+        let line = builder.lines(&values, &[1.]).unwrap().remove(0);
+        assert!(line
+            .to_path()
+            .iter()
+            .all(|e| !matches!(e, PathEvent::End { close: true, .. })));
+        let band = builder.isobands(&values, &[1., 3.]).unwrap().remove(0);
+        assert_eq!(band.to_path().iter().count(), events.len());
+    }
+
+    #[cfg(feature = "plotters")]
+    #[test]
+    fn test_plot() {
+        use crate::plot::{draw_bands, draw_lines, ramp_color};
+        use plotters::coord::types::RangedCoordf64;
+        use plotters::prelude::*;
+        use plotters_backend::{BackendColor, BackendCoord, DrawingErrorKind};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        // A backend recording the drawn pixels
+        type Canvas = Rc<RefCell<Vec<Option<(u8, u8, u8)>>>>;
+        struct Pixels(Canvas);
+
+        impl DrawingBackend for Pixels {
+            type ErrorType = std::fmt::Error;
+
+            fn get_size(&self) -> (u32, u32) {
+                (50, 50)
+            }
+
+            fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<std::fmt::Error>> {
+                Ok(())
+            }
+
+            fn present(&mut self) -> Result<(), DrawingErrorKind<std::fmt::Error>> {
+                Ok(())
+            }
+
+            fn draw_pixel(
+                &mut self,
+                (x, y): BackendCoord,
+                color: BackendColor,
+            ) -> Result<(), DrawingErrorKind<std::fmt::Error>> {
+                if (0..50).contains(&x) && (0..50).contains(&y) && color.alpha > 0.5 {
+                    self.0.borrow_mut()[(y * 50 + x) as usize] = Some(color.rgb);
+                }
+                Ok(())
+            }
+        }
+
+        let builder = ContourBuilder::new(5, 5, false);
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0., 0.,
+            0., 2., 2., 2., 0.,
+            0., 2., Float::NAN, 2., 0.,
+            0., 2., 2., 2., 0.,
+            0., 0., 0., 0., 0.,
+        ];
+        let bands = builder.isobands(&values, &[1., 3.]).unwrap();
+        assert_eq!(bands[0].geometry().0[0].interiors().len(), 1);
+        let pixels = Rc::new(RefCell::new(vec![None; 50 * 50]));
+        {
+            let area = Pixels(pixels.clone())
+                .into_drawing_area()
+                .apply_coord_spec(Cartesian2d::<RangedCoordf64, RangedCoordf64>::new(
+                    0f64..5f64,
+                    0f64..5f64,
+                    (0..50, 0..50),
+                ));
+            draw_bands(&area, &bands, &[RED]).unwrap();
+            let lines = builder.lines(&values, &[1.]).unwrap();
+            draw_lines(&area, &lines, &[BLUE], false).unwrap();
+        }
+        let pixels = pixels.borrow();
+        let pixel = |x: usize, y: usize| pixels[y * 50 + x];
+        // The band is filled, but neither its hole nor the outside of the band
+        assert_eq!(pixel(15, 25), Some((255, 0, 0)));
+        assert_eq!(pixel(25, 25), None);
+        assert_eq!(pixel(2, 2), None);
+        assert!(pixels.contains(&Some((0, 0, 255))));
+
+        assert_eq!(ramp_color(&[BLACK, WHITE], 0.5), RGBColor(128, 128, 128));
+        assert_eq!(ramp_color(&[RED, GREEN, BLUE], 1.), BLUE);
+        assert_eq!(ramp_color(&[], 0.), BLACK);
+    }
+
+    #[cfg(feature = "boolean-ops")]
+    #[test]
+    fn test_isobands_exact() {
+        let (dx, dy) = (20, 16);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float, (i / dx) as Float);
+                (x / 2.).sin() * (y / 3.).cos() * 10.
+            })
+            .collect::<Vec<_>>();
+        let polygon_area = |band: &crate::Band| {
+            band.geometry()
+                .0
+                .iter()
+                .map(|p| {
+                    crate::area::area(&p.exterior().0).abs()
+                        - p.interiors()
+                            .iter()
+                            .map(|r| crate::area::area(&r.0).abs())
+                            .sum::<f64>()
+                })
+                .sum::<f64>()
+        };
+        let builder = ContourBuilder::new(dx, dy, true).x_step(2.).y_step(-1.);
+        let thresholds = [-10., -3., 3., 10.];
+        let bands = builder.isobands(&values, &thresholds).unwrap();
+        let exact = builder.isobands_exact(&values, &thresholds).unwrap();
+        assert_eq!(exact.len(), bands.len());
+        for (a, b) in bands.iter().zip(&exact) {
+            assert_eq!((a.min_v(), a.max_v()), (b.min_v(), b.max_v()));
+            let (area, expected) = (polygon_area(b), polygon_area(a));
+            assert!(area > 0.);
+            assert!((area - expected).abs() < 1e-6 * expected);
+            // The rings are oriented as the ones of the contours (reversed by the negative step)
+            for polygon in &b.geometry().0 {
+                assert!(crate::area::area(&polygon.exterior().0) < 0.);
+                assert!(polygon
+                    .interiors()
+                    .iter()
+                    .all(|r| crate::area::area(&r.0) > 0.));
+            }
+        }
+
+        // Descending and infinite thresholds
+        let thresholds = [Float::INFINITY, 0., Float::NEG_INFINITY];
+        let bands = builder.isobands(&values, &thresholds).unwrap();
+        let exact = builder.isobands_exact(&values, &thresholds).unwrap();
+        assert_eq!(
+            (exact[0].min_v(), exact[1].min_v()),
+            (0., Float::NEG_INFINITY)
+        );
+        for (a, b) in bands.iter().zip(&exact) {
+            let (area, expected) = (polygon_area(b), polygon_area(a));
+            assert!((area - expected).abs() < 1e-6 * expected);
+        }
+        assert!(builder.isobands_exact(&values, &[0.]).is_err());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans() {
+        use std::sync::{Arc, Mutex};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        // A subscriber recording the names of the spans created (on the current thread)
+        struct Recorder(Arc<Mutex<Vec<&'static str>>>);
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut names = self.0.lock().unwrap();
+                names.push(span.metadata().name());
+                Id::from_u64(names.len() as u64)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {}
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        #[rustfmt::skip]
+        let values = [
+            0., 0., 0., 0.,
+            0., 1., 2., 0.,
+            0., 2., 1., 0.,
+            0., 0., 0., 0.,
+        ];
+        let names = Arc::new(Mutex::new(Vec::new()));
+        let bands = tracing::subscriber::with_default(Recorder(names.clone()), || {
+            ContourBuilder::new(4, 4, true).isobands(&values, &[0.5, 1.5, 2.5])
+        })
+        .unwrap();
+        assert_eq!(bands.len(), 2);
+        let names = names.lock().unwrap();
+        assert_eq!(names.first(), Some(&"isobands"));
+        assert_eq!(names.iter().filter(|name| **name == "nesting").count(), 2);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_rayon_parallel_results() {
+        use std::sync::Arc;
+
+        let (dx, dy) = (40, 30);
+        let values = (0..dx * dy)
+            .map(|i| {
+                let (x, y) = ((i % dx) as Float, (i / dx) as Float);
+                (x / 3.).sin() * (y / 4.).cos() * 10. + (x / 7.).cos() * 5.
+            })
+            .collect::<Vec<_>>();
+        let thresholds = (-14..=14).map(|t| t as Float).collect::<Vec<_>>();
+        // A single thread computes the thresholds and the nesting sequentially
+        let builder = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            ContourBuilder::new(dx, dy, true).thread_pool(Arc::new(pool))
+        };
+        let (sequential, parallel) = (builder(1), builder(4));
+
+        let geometries = |bands: Vec<crate::Band>| {
+            bands
+                .into_iter()
+                .map(crate::Band::into_inner)
+                .collect::<Vec<_>>()
+        };
+        let expected = geometries(sequential.isobands(&values, &thresholds).unwrap());
+        assert!(expected.iter().any(|(geometry, _, _)| geometry.0.len() > 1));
+        assert_eq!(
+            geometries(parallel.isobands(&values, &thresholds).unwrap()),
+            expected
+        );
+        let contours = |builder: &ContourBuilder| {
+            builder
+                .contours(&values, &thresholds)
+                .unwrap()
+                .into_iter()
+                .map(crate::Contour::into_inner)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(contours(&parallel), contours(&sequential));
+    }
 }
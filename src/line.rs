@@ -1,14 +1,27 @@
-use crate::Float;
-use geo_types::MultiLineString;
+use crate::area::lines_bbox;
+use crate::{Float, Pt};
+use geo_types::{MultiLineString, Rect};
 
 /// A line has the geometry and threshold of a contour ring, built by [ContourBuilder](`crate::contourbuilder::ContourBuilder`).
 #[derive(Debug, Clone)]
 pub struct Line {
     pub(crate) geometry: MultiLineString<Float>,
     pub(crate) threshold: Float,
+    pub(crate) gradients: Option<Vec<Vec<Gradient>>>,
+    pub(crate) bbox: Option<Rect<Float>>,
 }
 
 impl Line {
+    pub(crate) fn new(geometry: MultiLineString<Float>, threshold: Float) -> Self {
+        let bbox = lines_bbox(&geometry);
+        Line {
+            geometry,
+            threshold,
+            gradients: None,
+            bbox,
+        }
+    }
+
     /// Borrow the [`MultiLineString`](geo_types::MultiLineString) geometry of this contour.
     pub fn geometry(&self) -> &MultiLineString<Float> {
         &self.geometry
@@ -24,6 +37,69 @@ impl Line {
         self.threshold
     }
 
+    /// Get the bounding box of this isoline (`None` if it has no linestring),
+    /// computed along with its geometry.
+    pub fn bbox(&self) -> Option<Rect<Float>> {
+        self.bbox
+    }
+
+    /// Converts the lines of this isoline to a [`lyon_path::Path`] of an open subpath per line
+    /// (requires the `lyon` feature), to be stroked.
+    #[cfg(feature = "lyon")]
+    pub fn to_path(&self) -> lyon_path::Path {
+        crate::path::lines_to_path(&self.geometry)
+    }
+
+    /// Borrow the gradients of the values at each vertex of each linestring of this isoline,
+    /// if they were computed (see [`ContourBuilder::gradients`](crate::ContourBuilder::gradients)).
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, true).gradients(true);
+    /// let lines = builder.lines(&[0., 0., 0., 0., 1., 0., 0., 0., 0.], &[0.5]).unwrap();
+    /// let gradients = &lines[0].gradients().unwrap()[0];
+    /// assert_eq!(gradients.len(), lines[0].geometry().0[0].0.len());
+    /// ```
+    pub fn gradients(&self) -> Option<&[Vec<Gradient>]> {
+        self.gradients.as_deref()
+    }
+
+    /// Encodes each linestring of this isoline using the
+    /// [encoded polyline algorithm](https://developers.google.com/maps/documentation/utilities/polylinealgorithm)
+    /// with `precision` decimal digits (`5` in the original algorithm, `6` for some APIs),
+    /// the `y` coordinate (latitude) of each vertex being encoded before its `x` coordinate
+    /// (longitude).
+    ///
+    /// ```
+    /// use contour::ContourBuilder;
+    ///
+    /// let builder = ContourBuilder::new(3, 3, false);
+    /// let lines = builder.lines(&[0., 0., 0., 0., 1., 0., 0., 0., 0.], &[0.5]).unwrap();
+    /// let polylines = lines[0].to_encoded_polylines(5);
+    /// assert_eq!(polylines, ["_~cH_seK~s`B~s`B_t`B~s`B_t`B_t`B~s`B_t`B"]);
+    /// ```
+    pub fn to_encoded_polylines(&self, precision: u32) -> Vec<String> {
+        let factor = (10 as Float).powi(precision as i32);
+        self.geometry
+            .iter()
+            .map(|linestring| {
+                let mut encoded = String::new();
+                let mut previous = (0, 0);
+                for point in linestring.points() {
+                    let current = (
+                        (point.y() * factor).round() as i64,
+                        (point.x() * factor).round() as i64,
+                    );
+                    encode_value(current.0 - previous.0, &mut encoded);
+                    encode_value(current.1 - previous.1, &mut encoded);
+                    previous = current;
+                }
+                encoded
+            })
+            .collect()
+    }
+
     #[cfg(feature = "geojson")]
     /// Convert the line to a struct from the `geojson` crate.
     ///
@@ -64,3 +140,54 @@ impl Line {
         }
     }
 }
+
+impl From<Line> for MultiLineString<Float> {
+    fn from(line: Line) -> Self {
+        line.geometry
+    }
+}
+
+// Appends the given difference of coordinates to the encoded polyline,
+// as chunks of 5 bits offset into printable characters.
+fn encode_value(value: i64, encoded: &mut String) {
+    let mut value = if value < 0 { !(value << 1) } else { value << 1 };
+    while value >= 0x20 {
+        encoded.push(char::from((((value & 0x1f) | 0x20) + 63) as u8));
+        value >>= 5;
+    }
+    encoded.push(char::from((value + 63) as u8));
+}
+
+/// The gradient of the values of the grid at a vertex of a [`Line`]
+/// (the variation of the value per unit of distance, along each axis).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gradient {
+    /// The derivative of the values along the horizontal axis.
+    pub x: Float,
+    /// The derivative of the values along the vertical axis.
+    pub y: Float,
+}
+
+impl Gradient {
+    /// Get the norm of the gradient (the slope of the values).
+    pub fn magnitude(&self) -> Float {
+        self.x.hypot(self.y)
+    }
+
+    /// Get the unit vector normal to the isoline, pointing towards the higher values
+    /// (or `None` if the gradient is null).
+    pub fn normal(&self) -> Option<Pt> {
+        let m = self.magnitude();
+        (m > 0.).then(|| Pt {
+            x: self.x / m,
+            y: self.y / m,
+        })
+    }
+
+    /// Get the unit vector tangent to the isoline, pointing in the direction of the line
+    /// (the higher values being on its right, as for the isolines built by this crate),
+    /// or `None` if the gradient is null.
+    pub fn tangent(&self) -> Option<Pt> {
+        self.normal().map(|n| Pt { x: -n.y, y: n.x })
+    }
+}
@@ -0,0 +1,312 @@
+use crate::area::{area, assign_holes};
+use crate::{Band, Float, Pt, Ring};
+use geo_types::{LineString, MultiLineString, MultiPolygon, Polygon};
+use rustc_hash::FxHashMap;
+
+pub(crate) type Key = (i64, i64);
+
+// Matches the points closer than `tolerance` to each other: each point gets the key of the
+// closest point matched before it within `tolerance` (if any), or else its own key, which is
+// its cell in a grid of cells half as large as `tolerance` (so that a cell holds one key).
+struct Matcher {
+    tolerance: Float,
+    cells: FxHashMap<Key, Pt>,
+    // The keys of the points already matched, so that a point always gets the same key
+    keys: FxHashMap<(u64, u64), Key>,
+}
+
+impl Matcher {
+    fn new(tolerance: Float) -> Self {
+        Matcher {
+            tolerance,
+            cells: FxHashMap::default(),
+            keys: FxHashMap::default(),
+        }
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    fn key(&mut self, point: &Pt) -> Key {
+        let bits = (point.x.to_bits() as u64, point.y.to_bits() as u64);
+        if let Some(key) = self.keys.get(&bits) {
+            return *key;
+        }
+        let size = self.tolerance / 2.;
+        let cell = (
+            (point.x / size).round() as i64,
+            (point.y / size).round() as i64,
+        );
+        // The points within `tolerance` lie in the cells at most two cells away
+        let mut closest: Option<(Float, Key)> = None;
+        for y in cell.1 - 2..=cell.1 + 2 {
+            for x in cell.0 - 2..=cell.0 + 2 {
+                if let Some(p) = self.cells.get(&(x, y)) {
+                    let d = (p.x - point.x).hypot(p.y - point.y);
+                    if d < self.tolerance && closest.is_none_or(|(min, _)| d < min) {
+                        closest = Some((d, (x, y)));
+                    }
+                }
+            }
+        }
+        let key = match closest {
+            Some((_, key)) => key,
+            None => {
+                self.cells.insert(cell, *point);
+                cell
+            }
+        };
+        self.keys.insert(bits, key);
+        key
+    }
+}
+
+/// Merges polygons computed independently for adjacent tiles into seamless polygons.
+///
+/// Boundary edges shared by two input polygons (running in opposite directions,
+/// as produced by [`ContourBuilder`](crate::ContourBuilder) on tiles of the same grid)
+/// are dropped and the remaining edges are stitched back into rings.
+/// Vertices closer than `tolerance` are considered to be the same point (the first
+/// of them, each vertex being matched to the closest one), so shared borders must have
+/// matching vertices on both sides.
+///
+/// # Arguments
+///
+/// * `polygons` - The polygons to merge (typically the polygons of several tiles for a given threshold).
+/// * `tolerance` - The distance under which two vertices are matched.
+pub fn merge_polygons<I>(polygons: I, tolerance: Float) -> MultiPolygon<Float>
+where
+    I: IntoIterator<Item = Polygon<Float>>,
+{
+    let mut matcher = Matcher::new(tolerance);
+    let mut coords: FxHashMap<Key, Pt> = FxHashMap::default();
+    let mut edges: FxHashMap<(Key, Key), usize> = FxHashMap::default();
+
+    let mut add_ring = |ring: &[Pt], exterior: bool| {
+        if ring.len() < 4 {
+            return;
+        }
+        // Exteriors are walked with a positive area and holes with a negative one,
+        // which is the orientation used by the rings built in this crate.
+        let reverse = (area(ring) > 0.0) != exterior;
+        let mut keys = ring
+            .iter()
+            .map(|p| {
+                let k = matcher.key(p);
+                coords.entry(k).or_insert(*p);
+                k
+            })
+            .collect::<Vec<_>>();
+        if reverse {
+            keys.reverse();
+        }
+        keys.dedup();
+        for w in keys.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            match edges.get_mut(&(b, a)) {
+                Some(n) if *n > 0 => *n -= 1,
+                _ => *edges.entry((a, b)).or_insert(0) += 1,
+            }
+        }
+    };
+
+    for polygon in polygons {
+        add_ring(&polygon.exterior().0, true);
+        for interior in polygon.interiors() {
+            add_ring(&interior.0, false);
+        }
+    }
+
+    edges_to_polygons(&coords, edges)
+}
+
+/// Dissolves the given bands (such as the bands of several adjacent tiles) into a classed
+/// coverage: the polygons of the bands of each class (sharing the same minimum and maximum
+/// values) are merged into seamless polygons (see [`merge_polygons`]).
+/// Returns a `Vec` of one [`Band`] per class, ordered by their minimum value,
+/// whose index is the index of the class.
+///
+/// # Arguments
+///
+/// * `bands` - The bands to dissolve.
+/// * `tolerance` - The distance under which two vertices are matched.
+///
+/// ```
+/// use contour::{dissolve_bands, ContourBuilder};
+///
+/// let values = [0., 1., 2., 2., 1., 0.];
+/// let left = ContourBuilder::new(3, 2, false).isobands(&values, &[0., 1.5, 3.]).unwrap();
+/// let right = ContourBuilder::new(3, 2, false)
+///     .x_origin(3.)
+///     .isobands(&values, &[0., 1.5, 3.])
+///     .unwrap();
+/// let coverage = dissolve_bands(left.into_iter().chain(right), 1e-6);
+/// assert_eq!(coverage.len(), 2);
+/// assert_eq!(coverage[1].min_v(), 1.5);
+/// ```
+pub fn dissolve_bands<I>(bands: I, tolerance: Float) -> Vec<Band>
+where
+    I: IntoIterator<Item = Band>,
+{
+    let mut classes: Vec<Band> = Vec::new();
+    for band in bands {
+        match classes
+            .iter_mut()
+            .find(|class| (class.min_v, class.max_v) == (band.min_v, band.max_v))
+        {
+            Some(class) => class.geometry.0.extend(band.geometry.0),
+            None => classes.push(band),
+        }
+    }
+    classes.sort_by(|a, b| (a.min_v, a.max_v).partial_cmp(&(b.min_v, b.max_v)).unwrap());
+    classes
+        .into_iter()
+        .map(|class| {
+            let geometry = merge_polygons(class.geometry.0, tolerance);
+            let mut band = Band::new(geometry, class.min_v, class.max_v);
+            band.threshold_indices = class.threshold_indices;
+            band
+        })
+        .collect()
+}
+
+// Stitches the given directed edges (with their multiplicity) into rings
+// and assembles these rings into polygons.
+pub(crate) fn edges_to_polygons(
+    coords: &FxHashMap<Key, Pt>,
+    edges: FxHashMap<(Key, Key), usize>,
+) -> MultiPolygon<Float> {
+    let mut outgoing: FxHashMap<Key, Vec<Key>> = FxHashMap::default();
+    let mut remaining = 0;
+    for ((a, b), n) in edges.into_iter() {
+        for _ in 0..n {
+            outgoing.entry(a).or_default().push(b);
+            remaining += 1;
+        }
+    }
+
+    // Start the rings from the smallest vertices so that the output is deterministic
+    let mut starts = outgoing.keys().copied().collect::<Vec<_>>();
+    starts.sort_unstable();
+    let mut starts = starts.into_iter();
+
+    let mut rings: Vec<Ring> = Vec::new();
+    while remaining > 0 {
+        let start = starts.by_ref().find(|k| !outgoing[k].is_empty()).unwrap();
+        let mut ring = vec![coords[&start]];
+        let mut prev = start;
+        let mut current = start;
+        loop {
+            let ends = outgoing.get_mut(&current).unwrap();
+            let ix = if ends.len() == 1 {
+                0
+            } else {
+                rightmost_turn(&coords[&prev], &coords[&current], ends, coords)
+            };
+            let next = ends.swap_remove(ix);
+            remaining -= 1;
+            ring.push(coords[&next]);
+            prev = current;
+            current = next;
+            if current == start || outgoing.get(&current).is_none_or(|e| e.is_empty()) {
+                break;
+            }
+        }
+        if ring.len() > 3 {
+            rings.push(ring);
+        }
+    }
+
+    let (mut polygons, mut holes) = (Vec::new(), Vec::new());
+    for ring in rings {
+        if area(&ring) > 0.0 {
+            polygons.push(Polygon::<Float>::new(LineString::new(ring), vec![]));
+        } else {
+            holes.push(LineString::new(ring));
+        }
+    }
+    assign_holes(&mut polygons, holes);
+
+    MultiPolygon::<Float>(polygons)
+}
+
+// When several edges leave the same vertex (polygons touching at a corner),
+// follow the one turning the most to the right so that touching polygons stay distinct.
+#[allow(clippy::unnecessary_cast)]
+fn rightmost_turn(prev: &Pt, current: &Pt, ends: &[Key], coords: &FxHashMap<Key, Pt>) -> usize {
+    use std::f64::consts::PI;
+    let incoming = (current.y as f64 - prev.y as f64).atan2(current.x as f64 - prev.x as f64);
+    let turn = |k: &Key| {
+        let p = &coords[k];
+        let mut t = (p.y as f64 - current.y as f64).atan2(p.x as f64 - current.x as f64) - incoming;
+        while t <= -PI {
+            t += 2. * PI;
+        }
+        while t > PI {
+            t -= 2. * PI;
+        }
+        t
+    };
+    let mut best = 0;
+    for i in 1..ends.len() {
+        if turn(&ends[i]) < turn(&ends[best]) {
+            best = i;
+        }
+    }
+    best
+}
+
+/// Merges lines computed independently for adjacent tiles into continuous lines.
+///
+/// A line starting where another one ends (within `tolerance`) is appended to it,
+/// and lines whose ends meet are closed into rings.
+///
+/// # Arguments
+///
+/// * `lines` - The lines to merge (typically the lines of several tiles for a given threshold).
+/// * `tolerance` - The distance under which two endpoints are matched.
+pub fn merge_lines<I>(lines: I, tolerance: Float) -> MultiLineString<Float>
+where
+    I: IntoIterator<Item = LineString<Float>>,
+{
+    let mut matcher = Matcher::new(tolerance);
+    let mut fragments: Vec<Option<Ring>> = Vec::new();
+    let mut by_start: FxHashMap<Key, usize> = FxHashMap::default();
+    let mut by_end: FxHashMap<Key, usize> = FxHashMap::default();
+    let mut result = Vec::new();
+
+    for line in lines {
+        let mut line = line.0;
+        if line.len() < 2 {
+            continue;
+        }
+        let mut start = matcher.key(&line[0]);
+        let mut end = matcher.key(&line[line.len() - 1]);
+        if start == end {
+            result.push(LineString(line));
+            continue;
+        }
+        if let Some(ix) = by_end.remove(&start) {
+            let mut f = fragments[ix].take().unwrap();
+            start = matcher.key(&f[0]);
+            by_start.remove(&start);
+            f.extend(line.drain(1..));
+            line = f;
+        }
+        if let Some(ix) = by_start.remove(&end) {
+            let f = fragments[ix].take().unwrap();
+            end = matcher.key(&f[f.len() - 1]);
+            by_end.remove(&end);
+            line.extend(f.into_iter().skip(1));
+        }
+        if start == end {
+            result.push(LineString(line));
+        } else {
+            let ix = fragments.len();
+            fragments.push(Some(line));
+            by_start.insert(start, ix);
+            by_end.insert(end, ix);
+        }
+    }
+
+    result.extend(fragments.into_iter().flatten().map(LineString));
+    MultiLineString::<Float>(result)
+}
@@ -0,0 +1,70 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::Float;
+use geo_types::MultiPolygon;
+
+/// A triangle mesh tessellating polygons (requires the `mesh` feature), whose vertex
+/// and index buffers are ready to be uploaded to a GPU (such as to draw the
+/// [`Band`](crate::Band)s or the [`Contour`](crate::Contour)s in a real-time renderer).
+///
+/// ```
+/// use contour::ContourBuilder;
+///
+/// let builder = ContourBuilder::new(3, 3, false);
+/// let values = [0., 0., 0., 0., 2., 0., 0., 0., 0.];
+/// let bands = builder.isobands(&values, &[0., 1., 3.]).unwrap();
+/// let mesh = bands[0].to_mesh().unwrap();
+/// // The square of the grid, around the diamond of the upper band
+/// assert_eq!(mesh.vertices.len(), 12 + 4);
+/// assert_eq!(mesh.triangles(), 12 + 4 - 2 + 2 * 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    /// The coordinates of the vertices.
+    pub vertices: Vec<[f32; 2]>,
+    /// The indices of the vertices of each triangle, three by three.
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    /// Tessellates the polygons of the given `geometry` (taking their holes into account).
+    ///
+    /// Returns an [`ErrorKind::Overflow`] error if the mesh has more vertices than
+    /// can be indexed by a `u32`.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_polygons(geometry: &MultiPolygon<Float>) -> Result<Self> {
+        let mut mesh = Mesh::default();
+        let mut coords = Vec::new();
+        let mut holes = Vec::new();
+        for polygon in &geometry.0 {
+            coords.clear();
+            holes.clear();
+            for (i, ring) in std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .enumerate()
+            {
+                // The last point of the rings repeats the first one
+                let points = &ring.0[..ring.0.len().saturating_sub(1)];
+                if i > 0 {
+                    holes.push(coords.len() / 2);
+                }
+                coords.extend(points.iter().flat_map(|p| [p.x as f64, p.y as f64]));
+            }
+            if u32::try_from(mesh.vertices.len() + coords.len() / 2).is_err() {
+                return Err(new_error(ErrorKind::Overflow));
+            }
+            let offset = mesh.vertices.len() as u32;
+            let indices = earcutr::earcut(&coords, &holes, 2)
+                .map_err(|_| new_error(ErrorKind::Unexpected))?;
+            mesh.vertices
+                .extend(coords.chunks(2).map(|c| [c[0] as f32, c[1] as f32]));
+            mesh.indices
+                .extend(indices.into_iter().map(|i| offset + i as u32));
+        }
+        Ok(mesh)
+    }
+
+    /// Returns the number of triangles of the mesh.
+    pub fn triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
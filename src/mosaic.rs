@@ -0,0 +1,180 @@
+use crate::buffer::grid_size;
+use crate::error::Result;
+use crate::{Buffer, Float};
+use geo_types::{coord, Rect};
+
+/// How the values of the overlapping buffers of a [`Mosaic`] are combined.
+///
+/// The NODATA values (NaN) never take precedence over the values of the other buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overlap {
+    /// The value of the first buffer added takes precedence.
+    First,
+    /// The value of the last buffer added takes precedence.
+    #[default]
+    Last,
+    /// The minimum value is kept.
+    Min,
+    /// The maximum value is kept.
+    Max,
+}
+
+/// A grid composed of several [`Buffer`]s placed at integer offsets (in cells),
+/// such as the overlapping patches of a field survey, whose cells covered by no buffer
+/// are NODATA (NaN).
+///
+/// The rows of the mosaic can be computed one by one, such as to feed a
+/// [`RingStream`](crate::RingStream) without merging the buffers in a single allocation.
+///
+/// ```
+/// use contour::{Buffer, ContourBuilder, Mosaic, Overlap};
+///
+/// let mut mosaic = Mosaic::new(Overlap::Last);
+/// mosaic.push(Buffer::filled(1., 3, 2).unwrap(), 0, 0);
+/// mosaic.push(Buffer::filled(2., 2, 2).unwrap(), 2, 1);
+/// assert_eq!((mosaic.dx(), mosaic.dy()), (4, 3));
+/// assert_eq!(mosaic.get(2, 1), Some(2.));
+///
+/// let builder = ContourBuilder::new(mosaic.dx(), mosaic.dy(), true);
+/// let mut stream = builder.ring_stream(1.5);
+/// let mut rings = Vec::new();
+/// for row in mosaic.rows() {
+///     stream.push_row(&row, |ring| rings.push(ring)).unwrap();
+/// }
+/// stream.finish(|ring| rings.push(ring)).unwrap();
+/// assert_eq!(rings.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Mosaic {
+    overlap: Overlap,
+    buffers: Vec<(Buffer<Float>, usize, usize, Option<Float>)>,
+}
+
+impl Mosaic {
+    /// Constructs a new empty mosaic, whose overlapping values are combined
+    /// according to `overlap`.
+    pub fn new(overlap: Overlap) -> Self {
+        Mosaic {
+            overlap,
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Adds the `buffer` to the mosaic, its first value being placed
+    /// at column `x` and row `y` of the mosaic.
+    pub fn push(&mut self, buffer: Buffer<Float>, x: usize, y: usize) {
+        self.buffers.push((buffer, x, y, None));
+    }
+
+    /// Adds the `buffer` to the mosaic (as [`push`](Self::push)), its values equal to `nodata`
+    /// (such as `-9999`, as each buffer may come from a source with its own fill convention)
+    /// being NODATA.
+    ///
+    /// ```
+    /// use contour::{Buffer, Mosaic, Overlap};
+    ///
+    /// let mut mosaic = Mosaic::new(Overlap::Last);
+    /// mosaic.push(Buffer::filled(1., 2, 1).unwrap(), 0, 0);
+    /// mosaic.push_with_nodata(Buffer::new(vec![-9999., 2.], 2, 1).unwrap(), 0, 0, -9999.);
+    /// assert_eq!(mosaic.row(0), [1., 2.]);
+    /// ```
+    pub fn push_with_nodata(&mut self, buffer: Buffer<Float>, x: usize, y: usize, nodata: Float) {
+        self.buffers.push((buffer, x, y, Some(nodata)));
+    }
+
+    /// Returns the number of columns of the mosaic (up to the rightmost buffer).
+    pub fn dx(&self) -> usize {
+        self.buffers
+            .iter()
+            .map(|(b, x, _, _)| x + b.dx())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of rows of the mosaic (up to the bottommost buffer).
+    pub fn dy(&self) -> usize {
+        self.buffers
+            .iter()
+            .map(|(b, _, y, _)| y + b.dy())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the extent (in cells) of each buffer of the mosaic, in their order of addition.
+    pub fn extents(&self) -> Vec<Rect<usize>> {
+        self.buffers
+            .iter()
+            .map(|(b, x, y, _)| {
+                Rect::new(
+                    coord! { x: *x, y: *y },
+                    coord! { x: x + b.dx(), y: y + b.dy() },
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the value at column `x` and row `y` of the mosaic, if any
+    /// (NaN if the cell is covered by no buffer).
+    pub fn get(&self, x: usize, y: usize) -> Option<Float> {
+        (x < self.dx() && y < self.dy()).then(|| {
+            self.combine(self.buffers.iter().filter_map(|(b, bx, by, nodata)| {
+                let value = *b.get(x.checked_sub(*bx)?, y.checked_sub(*by)?)?;
+                Some(valid(value, *nodata))
+            }))
+        })
+    }
+
+    /// Returns the values of the row `y` of the mosaic.
+    pub fn row(&self, y: usize) -> Vec<Float> {
+        let dx = self.dx();
+        let mut row = vec![Float::NAN; dx];
+        for (buffer, bx, by, nodata) in &self.buffers {
+            let Some(j) = y.checked_sub(*by).filter(|j| *j < buffer.dy()) else {
+                continue;
+            };
+            let values = &buffer.data()[j * buffer.dx()..(j + 1) * buffer.dx()];
+            for (i, value) in values.iter().enumerate() {
+                let current = &mut row[bx + i];
+                *current = self.combine([*current, valid(*value, *nodata)].into_iter());
+            }
+        }
+        row
+    }
+
+    /// Returns an iterator over the rows of the mosaic.
+    pub fn rows(&self) -> impl Iterator<Item = Vec<Float>> + '_ {
+        (0..self.dy()).map(|y| self.row(y))
+    }
+
+    /// Merges the buffers of the mosaic in a single buffer.
+    ///
+    /// Returns an [`ErrorKind::Overflow`](crate::ErrorKind::Overflow) error if the mosaic
+    /// has more cells than the number of values addressable on the target.
+    pub fn to_buffer(&self) -> Result<Buffer<Float>> {
+        let (dx, dy) = (self.dx(), self.dy());
+        grid_size(dx, dy)?;
+        Buffer::new(self.rows().flatten().collect(), dx, dy)
+    }
+
+    // Combines the values (in the order of addition of their buffers) of a cell.
+    fn combine<I: Iterator<Item = Float>>(&self, values: I) -> Float {
+        values
+            .filter(|v| !v.is_nan())
+            .reduce(|a, b| match self.overlap {
+                Overlap::First => a,
+                Overlap::Last => b,
+                Overlap::Min => a.min(b),
+                Overlap::Max => a.max(b),
+            })
+            .unwrap_or(Float::NAN)
+    }
+}
+
+// Returns the value, or NaN if it is the NODATA value of its buffer.
+fn valid(value: Float, nodata: Option<Float>) -> Float {
+    if nodata == Some(value) {
+        Float::NAN
+    } else {
+        value
+    }
+}
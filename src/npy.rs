@@ -0,0 +1,95 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::{Buffer, ByteOrder, DataType, Float, RawReader};
+use std::io::Read;
+
+impl Buffer<Float> {
+    /// Constructs a new buffer from a 2D NumPy array stored in the `.npy` format
+    /// (such as written by `numpy.save`), its first dimension being the rows of the grid.
+    ///
+    /// Arrays of floating point (`f4`, `f8`) and integer (`i1` to `i8`, `u1` to `u8`) values,
+    /// of any byte order, are supported, in C or Fortran order.
+    ///
+    /// Returns an [`ErrorKind::BadFormat`] error if the file isn't a 2D array of a supported type.
+    pub fn from_npy_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let bad_format = || new_error(ErrorKind::BadFormat);
+        let mut preamble = [0; 8];
+        reader.read_exact(&mut preamble)?;
+        if &preamble[..6] != b"\x93NUMPY" {
+            return Err(bad_format());
+        }
+        let header_len = if preamble[6] == 1 {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        } else {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        };
+        let mut header = vec![0; header_len];
+        reader.read_exact(&mut header)?;
+        let header = String::from_utf8_lossy(&header);
+
+        let descr = header_value(&header, "descr").ok_or_else(bad_format)?;
+        let descr = descr.trim_matches(|c| c == '\'' || c == '"');
+        let byte_order = if descr.starts_with('>') {
+            ByteOrder::BigEndian
+        } else {
+            ByteOrder::LittleEndian
+        };
+        let data_type = match descr.trim_start_matches(['<', '>', '|', '=']) {
+            "f4" => DataType::F32,
+            "f8" => DataType::F64,
+            "i1" => DataType::I8,
+            "u1" => DataType::U8,
+            "i2" => DataType::I16,
+            "u2" => DataType::U16,
+            "i4" => DataType::I32,
+            "u4" => DataType::U32,
+            "i8" => DataType::I64,
+            "u8" => DataType::U64,
+            _ => return Err(bad_format()),
+        };
+        let fortran_order =
+            header_value(&header, "fortran_order").ok_or_else(bad_format)? == "True";
+        let shape = header_value(&header, "shape")
+            .ok_or_else(bad_format)?
+            .trim_matches(|c| c == '(' || c == ')')
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<usize>().map_err(|_| bad_format()))
+            .collect::<Result<Vec<_>>>()?;
+        let (dy, dx) = match shape[..] {
+            [dy, dx] => (dy, dx),
+            _ => return Err(bad_format()),
+        };
+
+        let values = RawReader::new(dx, dy, data_type)
+            .byte_order(byte_order)
+            .read(reader)?
+            .into_inner();
+        let data = if fortran_order {
+            // Stored column by column
+            (0..dx * dy)
+                .map(|i| values[(i % dx) * dy + i / dx])
+                .collect()
+        } else {
+            values
+        };
+        Buffer::new(data, dx, dy)
+    }
+}
+
+// Returns the (raw) value of the given key of the header of a `.npy` file,
+// a Python dict literal such as `{'descr': '<f8', 'fortran_order': False, 'shape': (3, 4), }`.
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{}'", key))? + key.len() + 2;
+    let value = header[start..].trim_start().strip_prefix(':')?.trim_start();
+    let end = if value.starts_with('(') {
+        value.find(')')? + 1
+    } else {
+        value.find([',', '}'])?
+    };
+    Some(value[..end].trim())
+}
@@ -0,0 +1,39 @@
+use crate::{Float, Pt};
+use geo_types::{MultiLineString, MultiPolygon};
+use lyon_path::math::point;
+use lyon_path::Path;
+
+// Builds a path of a closed subpath per ring of the polygons of `geometry`
+// (to be filled with the even-odd rule, so that the holes are left empty).
+pub(crate) fn polygons_to_path(geometry: &MultiPolygon<Float>) -> Path {
+    let mut builder = Path::builder();
+    for polygon in &geometry.0 {
+        for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+            // The last point of the rings repeats the first one
+            let points = &ring.0[..ring.0.len().saturating_sub(1)];
+            add_subpath(&mut builder, points, true);
+        }
+    }
+    builder.build()
+}
+
+// Builds a path of an open subpath per line of `geometry`.
+pub(crate) fn lines_to_path(geometry: &MultiLineString<Float>) -> Path {
+    let mut builder = Path::builder();
+    for line in &geometry.0 {
+        add_subpath(&mut builder, &line.0, false);
+    }
+    builder.build()
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn add_subpath(builder: &mut lyon_path::path::Builder, points: &[Pt], close: bool) {
+    let Some((first, rest)) = points.split_first() else {
+        return;
+    };
+    builder.begin(point(first.x as f32, first.y as f32));
+    for p in rest {
+        builder.line_to(point(p.x as f32, p.y as f32));
+    }
+    builder.end(close);
+}
@@ -0,0 +1,143 @@
+//! Helpers drawing the computed isolines and isobands into a [plotters](https://crates.io/crates/plotters)
+//! drawing area (requires the `plotters` feature), such as the plotting area of a chart
+//! whose coordinates are the ones of the grid, to visually check the results.
+//!
+//! ```
+//! use contour::plot::{draw_bands, draw_lines};
+//! use contour::ContourBuilder;
+//! use plotters::coord::types::RangedCoordf64;
+//! use plotters::prelude::*;
+//!
+//! let values = [0., 1., 0., 1., 2., 1., 0., 1., 0.];
+//! let builder = ContourBuilder::new(3, 3, true);
+//! let bands = builder.isobands(&values, &[0., 0.5, 1.5, 2.5]).unwrap();
+//! let lines = builder.lines(&values, &[0.5, 1.5]).unwrap();
+//!
+//! let mut svg = String::new();
+//! {
+//!     let root = SVGBackend::with_string(&mut svg, (300, 300)).into_drawing_area();
+//!     let area = root.apply_coord_spec(Cartesian2d::<RangedCoordf64, RangedCoordf64>::new(
+//!         0f64..3f64,
+//!         0f64..3f64,
+//!         (0..300, 0..300),
+//!     ));
+//!     draw_bands(&area, &bands, &[BLUE, RED]).unwrap();
+//!     draw_lines(&area, &lines, &[BLACK], true).unwrap();
+//! }
+//! assert_eq!(svg.matches("<polygon").count(), 3);
+//! assert!(svg.contains("\n1.5\n</text>"));
+//! ```
+
+use crate::{Band, Float, Line, Pt};
+use plotters::coord::CoordTranslate;
+use plotters::drawing::{DrawingArea, DrawingAreaErrorKind};
+use plotters::element::{PathElement, Polygon, Text};
+use plotters::prelude::DrawingBackend;
+use plotters::style::{Color, IntoFont, RGBColor};
+
+/// Fills the polygons of the `bands` (leaving their holes empty), with the colors
+/// interpolated along the given `ramp` from the first band to the last one
+/// (see [`ramp_color`]).
+pub fn draw_bands<DB, CT>(
+    area: &DrawingArea<DB, CT>,
+    bands: &[Band],
+    ramp: &[RGBColor],
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>>
+where
+    DB: DrawingBackend,
+    CT: CoordTranslate<From = (f64, f64)>,
+{
+    for (i, band) in bands.iter().enumerate() {
+        let style = ramp_color(ramp, position(i, bands.len())).filled();
+        for polygon in &band.geometry().0 {
+            area.draw(&Polygon::new(keyhole(polygon), style))?;
+        }
+    }
+    Ok(())
+}
+
+/// Strokes the `lines`, with the colors interpolated along the given `ramp` from the
+/// first isoline to the last one (see [`ramp_color`]), labelling each of them with
+/// its threshold (at the middle of its longest linestring) if `labels` is true.
+pub fn draw_lines<DB, CT>(
+    area: &DrawingArea<DB, CT>,
+    lines: &[Line],
+    ramp: &[RGBColor],
+    labels: bool,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>>
+where
+    DB: DrawingBackend,
+    CT: CoordTranslate<From = (f64, f64)>,
+{
+    for (i, line) in lines.iter().enumerate() {
+        let color = ramp_color(ramp, position(i, lines.len()));
+        for linestring in &line.geometry().0 {
+            let points = linestring.0.iter().map(to_f64).collect::<Vec<_>>();
+            area.draw(&PathElement::new(points, color))?;
+        }
+        let longest = line.geometry().0.iter().max_by_key(|l| l.0.len());
+        if let Some(point) = longest
+            .filter(|_| labels)
+            .and_then(|l| l.0.get(l.0.len() / 2))
+        {
+            let text = format!("{}", line.threshold());
+            area.draw(&Text::new(
+                text,
+                to_f64(point),
+                ("sans-serif", 12).into_font().color(&color),
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the color at the position `t` (from `0` to `1`) of the `ramp`,
+/// interpolated linearly between its colors (black if the ramp is empty).
+pub fn ramp_color(ramp: &[RGBColor], t: f64) -> RGBColor {
+    match ramp {
+        [] => RGBColor(0, 0, 0),
+        [color] => *color,
+        _ => {
+            let t = t.clamp(0., 1.) * (ramp.len() - 1) as f64;
+            let i = (t.floor() as usize).min(ramp.len() - 2);
+            let (a, b, f) = (ramp[i], ramp[i + 1], t - i as f64);
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+            RGBColor(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+        }
+    }
+}
+
+// The position of the `i`-th of `n` items along a ramp.
+fn position(i: usize, n: usize) -> f64 {
+    if n > 1 {
+        i as f64 / (n - 1) as f64
+    } else {
+        0.
+    }
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn to_f64(p: &Pt) -> (f64, f64) {
+    (p.x as f64, p.y as f64)
+}
+
+// Joins the holes of the `polygon` to its exterior ring by a segment walked
+// back and forth, so that it is filled as a single ring leaving the holes empty.
+fn keyhole(polygon: &geo_types::Polygon<Float>) -> Vec<(f64, f64)> {
+    let exterior = &polygon.exterior().0;
+    let mut points = exterior.iter().map(to_f64).collect::<Vec<_>>();
+    let exterior_area = crate::area::area(exterior);
+    for hole in polygon.interiors() {
+        let Some(start) = exterior.first() else {
+            continue;
+        };
+        let mut ring = hole.0.iter().map(to_f64).collect::<Vec<_>>();
+        // The holes are walked in the opposite direction of the exterior ring
+        if (crate::area::area(&hole.0) > 0.) == (exterior_area > 0.) {
+            ring.reverse();
+        }
+        points.extend(ring);
+        points.push(to_f64(start));
+    }
+    points
+}
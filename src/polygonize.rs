@@ -0,0 +1,119 @@
+use crate::merge::{edges_to_polygons, Key};
+use crate::{Float, Pt, Ring};
+use geo_types::{LineString, MultiPolygon, Polygon};
+use rustc_hash::FxHashMap;
+
+// Traces the exact boundaries of the cells of each distinct value of the grid
+// (in grid coordinates, each cell covering a unit square) and returns the polygons
+// of each value, in the order of first appearance of the values.
+#[allow(clippy::eq_op)]
+pub(crate) fn polygonize<T: PartialEq + Copy>(
+    values: &[T],
+    dx: usize,
+    dy: usize,
+) -> Vec<(T, MultiPolygon<Float>)> {
+    let mut classes: Vec<T> = Vec::new();
+    let mut class_of: Vec<Option<usize>> = Vec::with_capacity(values.len());
+    for v in values {
+        // Values that are not equal to themselves (NaN) don't belong to any class
+        if v != v {
+            class_of.push(None);
+            continue;
+        }
+        let ix = match classes.iter().position(|c| c == v) {
+            Some(ix) => ix,
+            None => {
+                classes.push(*v);
+                classes.len() - 1
+            }
+        };
+        class_of.push(Some(ix));
+    }
+
+    let n_classes = classes.len();
+    classes
+        .into_iter()
+        .zip(trace(&class_of, n_classes, dx, dy))
+        .collect()
+}
+
+// Traces the exact boundaries of the cells of each class (given the class index of each
+// cell, if any) and returns the polygons of each class.
+pub(crate) fn trace(
+    class_of: &[Option<usize>],
+    n_classes: usize,
+    dx: usize,
+    dy: usize,
+) -> Vec<MultiPolygon<Float>> {
+    let mut coords: FxHashMap<Key, Pt> = FxHashMap::default();
+    let mut edges: Vec<FxHashMap<(Key, Key), usize>> = vec![FxHashMap::default(); n_classes];
+    let class_at = |x: i64, y: i64| {
+        if x < 0 || y < 0 || x >= dx as i64 || y >= dy as i64 {
+            None
+        } else {
+            class_of[y as usize * dx + x as usize]
+        }
+    };
+
+    for y in 0..dy as i64 {
+        for x in 0..dx as i64 {
+            let Some(c) = class_at(x, y) else {
+                continue;
+            };
+            // The sides of the cell that are on the boundary of its class,
+            // oriented with the cell on their right
+            let sides = [
+                ((x - 1, y), (x, y), (x, y + 1)),
+                ((x, y + 1), (x, y + 1), (x + 1, y + 1)),
+                ((x + 1, y), (x + 1, y + 1), (x + 1, y)),
+                ((x, y - 1), (x + 1, y), (x, y)),
+            ];
+            for (neighbor, a, b) in sides {
+                if class_at(neighbor.0, neighbor.1) == Some(c) {
+                    continue;
+                }
+                for k in [a, b] {
+                    coords.entry(k).or_insert(Pt {
+                        x: k.0 as Float,
+                        y: k.1 as Float,
+                    });
+                }
+                *edges[c].entry((a, b)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    edges
+        .into_iter()
+        .map(|edges| {
+            let polygons = edges_to_polygons(&coords, edges)
+                .0
+                .into_iter()
+                .map(|polygon| {
+                    let (exterior, interiors) = polygon.into_inner();
+                    Polygon::new(
+                        remove_collinear(exterior),
+                        interiors.into_iter().map(remove_collinear).collect(),
+                    )
+                })
+                .collect();
+            MultiPolygon::<Float>(polygons)
+        })
+        .collect()
+}
+
+// Removes the vertices located in the middle of a straight (horizontal or vertical) side.
+fn remove_collinear(ring: LineString<Float>) -> LineString<Float> {
+    let mut points = ring.0;
+    points.pop();
+    let n = points.len();
+    let mut result: Ring = (0..n)
+        .filter(|&i| {
+            let (prev, cur, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            !((prev.x == cur.x && cur.x == next.x) || (prev.y == cur.y && cur.y == next.y))
+        })
+        .map(|i| points[i])
+        .collect();
+    result.push(result[0]);
+    LineString(result)
+}
@@ -0,0 +1,125 @@
+use crate::buffer::check_len;
+use crate::error::Result;
+use crate::{ContourBuilder, Float};
+
+#[derive(Debug, Clone)]
+struct Level {
+    dx: usize,
+    dy: usize,
+    values: Vec<Float>,
+}
+
+/// A grid of values stored along with pre-aggregated overview levels.
+///
+/// Level `0` is the original grid, and each following level averages
+/// the 2 * 2 blocks of values of the previous one (halving its resolution).
+/// This allows to contour the resolution appropriate to the current zoom level
+/// of an interactive application without rebuilding the values each time.
+///
+/// ```
+/// use contour::Pyramid;
+///
+/// let values = vec![0.; 256 * 256];
+/// let pyramid = Pyramid::new(values, 256, 256, 3).unwrap();
+/// assert_eq!(pyramid.dimensions(2), (64, 64));
+///
+/// let level = 2;
+/// let res = pyramid
+///     .contour_builder(level, true)
+///     .contours(pyramid.values(level), &[0.5])
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pyramid {
+    levels: Vec<Level>,
+}
+
+impl Pyramid {
+    /// Constructs a new pyramid from the `values` of a grid with `dx` * `dy` dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the full resolution grid.
+    /// * `dx` - The number of columns in the grid.
+    /// * `dy` - The number of rows in the grid.
+    /// * `overviews` - The number of overview levels to compute (in addition to the full resolution).
+    pub fn new(values: Vec<Float>, dx: usize, dy: usize, overviews: usize) -> Result<Self> {
+        check_len(values.len(), dx, dy)?;
+        let mut levels = vec![Level { dx, dy, values }];
+        for _ in 0..overviews {
+            let previous = levels.last().unwrap();
+            if previous.dx == 1 && previous.dy == 1 {
+                break;
+            }
+            levels.push(aggregate(previous));
+        }
+        Ok(Pyramid { levels })
+    }
+
+    /// Returns the number of levels (including the full resolution level).
+    pub fn levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the values of the given `level`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is greater than or equal to the number of levels.
+    pub fn values(&self, level: usize) -> &[Float] {
+        &self.levels[level].values
+    }
+
+    /// Returns the number of columns and rows of the given `level`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is greater than or equal to the number of levels.
+    pub fn dimensions(&self, level: usize) -> (usize, usize) {
+        (self.levels[level].dx, self.levels[level].dy)
+    }
+
+    /// Returns the level to use for a given `scale`, i.e. the number of cells of
+    /// the full resolution grid that are displayed in a single pixel.
+    pub fn level_for_scale(&self, scale: Float) -> usize {
+        let mut level = 0;
+        while level + 1 < self.levels.len() && ((2usize << level) as Float) <= scale {
+            level += 1;
+        }
+        level
+    }
+
+    /// Constructs a [`ContourBuilder`] for the given `level`, whose output
+    /// coordinates are expressed in the coordinates of the full resolution grid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is greater than or equal to the number of levels.
+    pub fn contour_builder(&self, level: usize, smooth: bool) -> ContourBuilder {
+        let (dx, dy) = self.dimensions(level);
+        let step = (1usize << level) as Float;
+        ContourBuilder::new(dx, dy, smooth)
+            .x_step(step)
+            .y_step(step)
+    }
+}
+
+fn aggregate(level: &Level) -> Level {
+    let dx = level.dx.div_ceil(2);
+    let dy = level.dy.div_ceil(2);
+    let mut values = Vec::with_capacity(dx * dy);
+    for y in 0..dy {
+        for x in 0..dx {
+            let mut sum = 0.;
+            let mut n = 0;
+            for yy in (y * 2)..(y * 2 + 2).min(level.dy) {
+                for xx in (x * 2)..(x * 2 + 2).min(level.dx) {
+                    sum += level.values[yy * level.dx + xx];
+                    n += 1;
+                }
+            }
+            values.push(sum / n as Float);
+        }
+    }
+    Level { dx, dy, values }
+}
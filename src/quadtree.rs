@@ -0,0 +1,264 @@
+use crate::buffer::check_len;
+use crate::error::Result;
+use crate::{Float, IsoRingBuilder, Ring};
+use geo_types::{coord, Rect};
+
+#[derive(Debug, Clone)]
+enum Content {
+    // All the values of the node are equal (or NODATA)
+    Uniform(Float),
+    // The values of a leaf which is not uniform, row by row
+    Values(Vec<Float>),
+    Children(Vec<Node>),
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    x: usize,
+    y: usize,
+    dx: usize,
+    dy: usize,
+    // The range of the valid values (infinite and reversed if there is none)
+    min: Float,
+    max: Float,
+    // Whether some of the values are NODATA (NaN)
+    nodata: bool,
+    content: Content,
+}
+
+/// A grid whose homogeneous regions (of equal values) are stored as single nodes
+/// of a quadtree, such as for the rasters that are largely flat (oceans, plateaus).
+///
+/// Its [`extents`](Self::extents) are the regions that can contain the isolines of a
+/// threshold, so that computing the rings with [`contour_rings`](Self::contour_rings)
+/// does not scan the cells that can never produce a segment.
+///
+/// ```
+/// use contour::QuadTree;
+///
+/// let mut values = vec![0.; 256 * 256];
+/// values[100 * 256 + 100] = 1.;
+/// let tree = QuadTree::new(&values, 256, 256, 4).unwrap();
+/// assert!(tree.leaves() < 64);
+/// assert_eq!(tree.get(100, 100), Some(1.));
+///
+/// // The leaf of the peak, and the leaves of the neighbouring samples
+/// assert_eq!(tree.extents(0.5).len(), 4);
+/// let rings = tree.contour_rings(0.5).unwrap();
+/// assert_eq!(rings, contour::contour_rings(&values, 0.5, 256, 256).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct QuadTree {
+    dx: usize,
+    dy: usize,
+    root: Node,
+}
+
+impl QuadTree {
+    /// Constructs a new quadtree from the `values` of a grid with `dx` * `dy` dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the grid.
+    /// * `dx` - The number of columns in the grid.
+    /// * `dy` - The number of rows in the grid.
+    /// * `leaf_size` - The size (in cells) under which the regions which are not homogeneous
+    ///   are no longer split (at least `1`).
+    pub fn new(values: &[Float], dx: usize, dy: usize, leaf_size: usize) -> Result<Self> {
+        check_len(values.len(), dx, dy)?;
+        let root = build(values, dx, (0, 0, dx, dy), leaf_size.max(1));
+        Ok(QuadTree { dx, dy, root })
+    }
+
+    /// Returns the number of columns of the grid.
+    pub fn dx(&self) -> usize {
+        self.dx
+    }
+
+    /// Returns the number of rows of the grid.
+    pub fn dy(&self) -> usize {
+        self.dy
+    }
+
+    /// Returns the number of leaves of the quadtree (the homogeneous regions,
+    /// and the regions of at most `leaf_size` * `leaf_size` values which are not).
+    pub fn leaves(&self) -> usize {
+        let mut leaves = 0;
+        visit_leaves(&self.root, &mut |_| leaves += 1);
+        leaves
+    }
+
+    /// Returns the value at column `x` and row `y` of the grid, if any.
+    pub fn get(&self, x: usize, y: usize) -> Option<Float> {
+        if x >= self.dx || y >= self.dy {
+            return None;
+        }
+        let mut node = &self.root;
+        loop {
+            match &node.content {
+                Content::Uniform(value) => return Some(*value),
+                Content::Values(values) => {
+                    return Some(values[(y - node.y) * node.dx + x - node.x])
+                }
+                Content::Children(children) => {
+                    node = children.iter().find(|c| c.contains(x, y))?;
+                }
+            }
+        }
+    }
+
+    /// Returns the extents (in samples) of the leaves of the quadtree that can contain the
+    /// isolines of the given `threshold`, which are the ones whose values, or the values of
+    /// their neighbouring samples, are not all on the same side of the threshold
+    /// (the NODATA values being below it, as the samples outside the grid).
+    ///
+    /// They can be given to [`IsoRingBuilder::compute_in`].
+    pub fn extents(&self, threshold: Float) -> Vec<Rect<usize>> {
+        let mut extents = Vec::new();
+        visit_leaves(&self.root, &mut |leaf| {
+            let (x0, y0) = (leaf.x.saturating_sub(1), leaf.y.saturating_sub(1));
+            let (x1, y1) = (
+                (leaf.x + leaf.dx + 1).min(self.dx),
+                (leaf.y + leaf.dy + 1).min(self.dy),
+            );
+            let (min, max, nodata) = self.root.range(x0, y0, x1, y1);
+            let crossed = max >= threshold && (nodata || min < threshold);
+            let border = leaf.x == 0
+                || leaf.y == 0
+                || leaf.x + leaf.dx == self.dx
+                || leaf.y + leaf.dy == self.dy;
+            if crossed || (border && leaf.max >= threshold) {
+                extents.push(Rect::new(
+                    coord! { x: leaf.x, y: leaf.y },
+                    coord! { x: leaf.x + leaf.dx, y: leaf.y + leaf.dy },
+                ));
+            }
+        });
+        extents
+    }
+
+    /// Computes the isorings of the grid for the given `threshold` (as
+    /// [`contour_rings`](crate::contour_rings)), traversing only its [`extents`](Self::extents).
+    pub fn contour_rings(&self, threshold: Float) -> Result<Vec<Ring>> {
+        let mut isoring = IsoRingBuilder::new(self.dx, self.dy);
+        isoring.compute_extents(&self.extents(threshold), |x, y| {
+            self.get(x, y).is_some_and(|v| v >= threshold)
+        })
+    }
+
+    /// Returns the values of the grid, row by row.
+    pub fn values(&self) -> Vec<Float> {
+        let mut values = vec![Float::NAN; self.dx * self.dy];
+        visit_leaves(&self.root, &mut |leaf| {
+            for y in leaf.y..leaf.y + leaf.dy {
+                let row = &mut values[y * self.dx + leaf.x..y * self.dx + leaf.x + leaf.dx];
+                match &leaf.content {
+                    Content::Uniform(value) => row.fill(*value),
+                    Content::Values(v) => {
+                        row.copy_from_slice(&v[(y - leaf.y) * leaf.dx..(y - leaf.y + 1) * leaf.dx])
+                    }
+                    Content::Children(_) => {}
+                }
+            }
+        });
+        values
+    }
+}
+
+impl Node {
+    fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && x < self.x + self.dx && y >= self.y && y < self.y + self.dy
+    }
+
+    // Computes the range of the valid values, and whether there is a NODATA value,
+    // in the columns `x0..x1` and the rows `y0..y1`.
+    fn range(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> (Float, Float, bool) {
+        let (xa, ya) = (x0.max(self.x), y0.max(self.y));
+        let (xb, yb) = (x1.min(self.x + self.dx), y1.min(self.y + self.dy));
+        if xa >= xb || ya >= yb {
+            return (Float::INFINITY, Float::NEG_INFINITY, false);
+        }
+        if (xa, ya, xb, yb) == (self.x, self.y, self.x + self.dx, self.y + self.dy) {
+            return (self.min, self.max, self.nodata);
+        }
+        match &self.content {
+            Content::Uniform(_) => (self.min, self.max, self.nodata),
+            Content::Values(values) => (ya..yb)
+                .flat_map(|y| &values[(y - self.y) * self.dx..][xa - self.x..xb - self.x])
+                .fold((Float::INFINITY, Float::NEG_INFINITY, false), stats),
+            Content::Children(children) => children.iter().fold(
+                (Float::INFINITY, Float::NEG_INFINITY, false),
+                |(min, max, nodata), child| {
+                    let (cmin, cmax, cnodata) = child.range(x0, y0, x1, y1);
+                    (min.min(cmin), max.max(cmax), nodata || cnodata)
+                },
+            ),
+        }
+    }
+}
+
+// Accumulates a value to the range of the valid values and the NODATA flag.
+fn stats((min, max, nodata): (Float, Float, bool), value: &Float) -> (Float, Float, bool) {
+    if value.is_nan() {
+        (min, max, true)
+    } else {
+        (min.min(*value), max.max(*value), nodata)
+    }
+}
+
+// Builds the node of the region (`x`, `y`, `dx`, `dy`) of the grid of `width` columns.
+fn build(
+    values: &[Float],
+    width: usize,
+    (x, y, dx, dy): (usize, usize, usize, usize),
+    leaf_size: usize,
+) -> Node {
+    let (min, max, nodata) = (y..y + dy)
+        .flat_map(|y| &values[y * width + x..y * width + x + dx])
+        .fold((Float::INFINITY, Float::NEG_INFINITY, false), stats);
+    let content = if !nodata && min == max {
+        Content::Uniform(min)
+    } else if nodata && min > max {
+        Content::Uniform(Float::NAN)
+    } else if dx <= leaf_size && dy <= leaf_size {
+        Content::Values(
+            (y..y + dy)
+                .flat_map(|y| &values[y * width + x..y * width + x + dx])
+                .copied()
+                .collect(),
+        )
+    } else {
+        let (hx, hy) = (dx.div_ceil(2), dy.div_ceil(2));
+        let quadrants = [
+            (x, y, hx, hy),
+            (x + hx, y, dx - hx, hy),
+            (x, y + hy, hx, dy - hy),
+            (x + hx, y + hy, dx - hx, dy - hy),
+        ];
+        Content::Children(
+            quadrants
+                .into_iter()
+                .filter(|(_, _, dx, dy)| *dx > 0 && *dy > 0)
+                .map(|region| build(values, width, region, leaf_size))
+                .collect(),
+        )
+    };
+    Node {
+        x,
+        y,
+        dx,
+        dy,
+        min,
+        max,
+        nodata,
+        content,
+    }
+}
+
+// Calls `f` on each leaf of the `node`.
+fn visit_leaves<'a, F: FnMut(&'a Node)>(node: &'a Node, f: &mut F) {
+    match &node.content {
+        Content::Children(children) => children.iter().for_each(|c| visit_leaves(c, f)),
+        _ => f(node),
+    }
+}
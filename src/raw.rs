@@ -0,0 +1,176 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::{Buffer, Float};
+use std::io::Read;
+
+/// The type of the values of a binary grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+}
+
+/// The order of the bytes of the values of a binary grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+impl DataType {
+    /// Returns the number of bytes of a value of this type.
+    pub fn size(&self) -> usize {
+        match self {
+            DataType::I8 | DataType::U8 => 1,
+            DataType::I16 | DataType::U16 => 2,
+            DataType::I32 | DataType::U32 | DataType::F32 => 4,
+            DataType::I64 | DataType::U64 | DataType::F64 => 8,
+        }
+    }
+
+    // Decodes a value of this type from its bytes.
+    #[allow(clippy::unnecessary_cast)]
+    pub(crate) fn decode(&self, bytes: &[u8], byte_order: ByteOrder) -> Float {
+        macro_rules! decode {
+            ($t:ty) => {{
+                let bytes = bytes.try_into().unwrap();
+                (match byte_order {
+                    ByteOrder::LittleEndian => <$t>::from_le_bytes(bytes),
+                    ByteOrder::BigEndian => <$t>::from_be_bytes(bytes),
+                }) as Float
+            }};
+        }
+        match self {
+            DataType::I8 => decode!(i8),
+            DataType::U8 => decode!(u8),
+            DataType::I16 => decode!(i16),
+            DataType::U16 => decode!(u16),
+            DataType::I32 => decode!(i32),
+            DataType::U32 => decode!(u32),
+            DataType::I64 => decode!(i64),
+            DataType::U64 => decode!(u64),
+            DataType::F32 => decode!(f32),
+            DataType::F64 => decode!(f64),
+        }
+    }
+}
+
+/// Loader of headerless binary grids (such as the single band ENVI or BIL files),
+/// storing the values row by row, using builder pattern.
+///
+/// ```
+/// use contour::{ByteOrder, DataType, RawReader};
+///
+/// let mut bytes = b"HEADER".to_vec();
+/// for v in [0u16, 1, 2, 3, 4, 5] {
+///     bytes.extend(v.to_be_bytes());
+/// }
+/// let buffer = RawReader::new(3, 2, DataType::U16)
+///     .byte_order(ByteOrder::BigEndian)
+///     .skip(6)
+///     .read(&bytes[..])
+///     .unwrap();
+/// assert_eq!(buffer.get(2, 1), Some(&5.));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawReader {
+    /// The number of columns in the grid
+    dx: usize,
+    /// The number of rows in the grid
+    dy: usize,
+    /// The type of the values
+    data_type: DataType,
+    /// The order of the bytes of the values
+    byte_order: ByteOrder,
+    /// The number of bytes to skip before the values
+    skip: u64,
+    /// The scale and the offset applied to the decoded values
+    scale_offset: (Float, Float),
+}
+
+impl RawReader {
+    /// Constructs a new loader for a grid with `dx` * `dy` dimension of values of the given type.
+    ///
+    /// By default, the values are in little endian order, starting at the first byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `dx` - The number of columns in the grid.
+    /// * `dy` - The number of rows in the grid.
+    /// * `data_type` - The type of the values.
+    pub fn new(dx: usize, dy: usize, data_type: DataType) -> Self {
+        RawReader {
+            dx,
+            dy,
+            data_type,
+            byte_order: ByteOrder::LittleEndian,
+            skip: 0,
+            scale_offset: (1., 0.),
+        }
+    }
+
+    /// Sets the order of the bytes of the values.
+    pub fn byte_order(mut self, byte_order: ByteOrder) -> Self {
+        self.byte_order = byte_order;
+        self
+    }
+
+    /// Sets the number of bytes (such as a header) to skip before the values.
+    pub fn skip(mut self, bytes: u64) -> Self {
+        self.skip = bytes;
+        self
+    }
+
+    /// Sets the `scale` and the `offset` applied to each decoded value `v` as `v * scale + offset`
+    /// (the packing convention of GeoTIFF or NetCDF), so that packed integer grids are loaded
+    /// in physical units without an intermediate unpacked copy.
+    ///
+    /// By default, the values are neither scaled nor offset.
+    ///
+    /// ```
+    /// use contour::{DataType, RawReader};
+    ///
+    /// let bytes = [0i16, 100, 200, -50].map(i16::to_le_bytes).concat();
+    /// let buffer = RawReader::new(2, 2, DataType::I16)
+    ///     .scale_offset(0.01, 273.15)
+    ///     .read(&bytes[..])
+    ///     .unwrap();
+    /// assert_eq!(buffer.get(1, 0), Some(&(100. * 0.01 + 273.15)));
+    /// ```
+    pub fn scale_offset(mut self, scale: impl Into<Float>, offset: impl Into<Float>) -> Self {
+        self.scale_offset = (scale.into(), offset.into());
+        self
+    }
+
+    /// Reads the grid from `reader`.
+    ///
+    /// Returns an error if `reader` doesn't provide enough bytes for all the values.
+    pub fn read<R: Read>(&self, mut reader: R) -> Result<Buffer<Float>> {
+        std::io::copy(&mut reader.by_ref().take(self.skip), &mut std::io::sink())?;
+        let size = self.data_type.size();
+        let len = self
+            .dx
+            .checked_mul(self.dy)
+            .and_then(|len| len.checked_mul(size))
+            .ok_or_else(|| new_error(ErrorKind::Overflow))?;
+        let mut bytes = vec![0; len];
+        reader.read_exact(&mut bytes)?;
+        let data = bytes
+            .chunks_exact(size)
+            .map(|b| self.data_type.decode(b, self.byte_order));
+        let data = if self.scale_offset != (1., 0.) {
+            let (scale, offset) = self.scale_offset;
+            data.map(|v| v * scale + offset).collect()
+        } else {
+            data.collect()
+        };
+        Buffer::new(data, self.dx, self.dy)
+    }
+}
@@ -0,0 +1,127 @@
+//! Utilities operating on the [`Ring`](crate::Ring)s computed by this crate
+//! (such as by [`contour_rings`](crate::contour_rings) or [`RingStream`](crate::RingStream)),
+//! using the same conventions as the ones used internally to assemble the polygons.
+//!
+//! The orientations are given for axes whose y axis points up (the opposite of the
+//! row order of a grid), so that the exterior rings of the polygons computed by
+//! [`ContourBuilder`](crate::ContourBuilder) are [`Clockwise`](Orientation::Clockwise)
+//! and their holes [`CounterClockwise`](Orientation::CounterClockwise).
+//!
+//! ```
+//! use contour::ring::{self, Orientation};
+//! use geo_types::coord;
+//!
+//! let square = vec![
+//!     coord! { x: 0., y: 0. },
+//!     coord! { x: 2., y: 0. },
+//!     coord! { x: 2., y: 2. },
+//!     coord! { x: 0., y: 2. },
+//!     coord! { x: 0., y: 0. },
+//! ];
+//! assert_eq!(ring::area(&square), 4.);
+//! assert_eq!(ring::orientation(&square), Some(Orientation::CounterClockwise));
+//! assert_eq!(ring::centroid(&square), Some(coord! { x: 1., y: 1. }));
+//! assert_eq!(ring::contains(&square, &coord! { x: 1., y: 1. }), 1);
+//! ```
+
+use crate::{area, Float, Pt};
+
+/// The orientation of a ring (for axes whose y axis points up).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    /// The ring is walked clockwise (like the exterior rings of the computed polygons).
+    Clockwise,
+    /// The ring is walked counterclockwise (like the holes of the computed polygons).
+    CounterClockwise,
+}
+
+/// Computes the signed area of the `ring`, positive if it is
+/// [`CounterClockwise`](Orientation::CounterClockwise) and negative if it is
+/// [`Clockwise`](Orientation::Clockwise).
+///
+/// The ring may be closed (its last point repeating the first one) or not.
+#[allow(clippy::unnecessary_cast)]
+pub fn signed_area(ring: &[Pt]) -> Float {
+    if ring.is_empty() {
+        return 0.;
+    }
+    (-area::area(ring) / 2.) as Float
+}
+
+/// Computes the area of the `ring` (whatever its orientation).
+pub fn area(ring: &[Pt]) -> Float {
+    signed_area(ring).abs()
+}
+
+/// Returns the orientation of the `ring`, or `None` if its area is null.
+pub fn orientation(ring: &[Pt]) -> Option<Orientation> {
+    let area = signed_area(ring);
+    if area > 0. {
+        Some(Orientation::CounterClockwise)
+    } else if area < 0. {
+        Some(Orientation::Clockwise)
+    } else {
+        None
+    }
+}
+
+/// Computes the centroid of the surface enclosed by the `ring`,
+/// or `None` if its area is null.
+#[allow(clippy::unnecessary_cast)]
+pub fn centroid(ring: &[Pt]) -> Option<Pt> {
+    let n = ring.len();
+    let (mut a, mut cx, mut cy) = (0f64, 0f64, 0f64);
+    for i in 0..n {
+        let (p, q) = (ring[i], ring[(i + 1) % n]);
+        let (px, py, qx, qy) = (p.x as f64, p.y as f64, q.x as f64, q.y as f64);
+        let cross = px * qy - qx * py;
+        a += cross;
+        cx += (px + qx) * cross;
+        cy += (py + qy) * cross;
+    }
+    (a != 0.).then(|| Pt {
+        x: (cx / (3. * a)) as Float,
+        y: (cy / (3. * a)) as Float,
+    })
+}
+
+/// Returns whether the `point` is inside the `ring` (`1`), on its boundary (`0`)
+/// or outside of it (`-1`), following the even-odd rule.
+pub fn contains(ring: &[Pt], point: &Pt) -> i32 {
+    if ring.is_empty() {
+        return -1;
+    }
+    area::contains(ring, std::slice::from_ref(point))
+}
+
+/// Returns whether the `other` ring is inside the `ring` (`1`) or outside of it (`-1`),
+/// assuming that both rings do not cross each other (as the rings computed for a same
+/// threshold), according to the first point of `other` which is not on the boundary
+/// of `ring` (`0` if all of them are on it).
+pub fn contains_ring(ring: &[Pt], other: &[Pt]) -> i32 {
+    if ring.is_empty() {
+        return -1;
+    }
+    area::contains(ring, other)
+}
+
+/// Computes the winding number of the `ring` around the `point`, that is the number of
+/// times the ring travels counterclockwise around it (negative if it travels clockwise,
+/// `0` if the point is outside of a simple ring).
+pub fn winding_number(ring: &[Pt], point: &Pt) -> i32 {
+    let n = ring.len();
+    let mut winding = 0;
+    for i in 0..n {
+        let (a, b) = (ring[i], ring[(i + 1) % n]);
+        let side = (b.x - a.x) * (point.y - a.y) - (point.x - a.x) * (b.y - a.y);
+        if a.y <= point.y {
+            if b.y > point.y && side > 0. {
+                winding += 1;
+            }
+        } else if b.y <= point.y && side < 0. {
+            winding -= 1;
+        }
+    }
+    winding
+}
@@ -0,0 +1,200 @@
+use crate::buffer::check_len;
+use crate::error::Result;
+use crate::{Float, IsoRingBuilder, Ring};
+use geo_types::{coord, Rect};
+
+/// A grid whose rows are stored as runs of equal values (run-length encoding),
+/// such as for the classified or sparse rasters, whose values are decoded on access.
+///
+/// Its [`extents`](Self::extents) are derived from the boundaries of the runs,
+/// so that computing the rings with [`contour_rings`](Self::contour_rings) only
+/// traverses the cells along the isolines.
+///
+/// ```
+/// use contour::RleBuffer;
+///
+/// let mut values = vec![0.; 1000 * 1000];
+/// for y in 200..400 {
+///     values[y * 1000 + 300..y * 1000 + 700].fill(2.);
+/// }
+/// let grid = RleBuffer::new(&values, 1000, 1000).unwrap();
+/// assert_eq!(grid.runs(), 1000 + 200 * 2);
+/// assert_eq!(grid.get(300, 200), Some(2.));
+///
+/// let rings = grid.contour_rings(1.).unwrap();
+/// assert_eq!(rings, contour::contour_rings(&values, 1., 1000, 1000).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RleBuffer {
+    dx: usize,
+    dy: usize,
+    // The first column and the value of each run, row by row
+    runs: Vec<(usize, Float)>,
+    // The index of the first run of each row (and the number of runs)
+    rows: Vec<usize>,
+}
+
+impl RleBuffer {
+    /// Constructs a new run-length encoded grid from the `values` of a grid
+    /// with `dx` * `dy` dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the grid.
+    /// * `dx` - The number of columns in the grid.
+    /// * `dy` - The number of rows in the grid.
+    pub fn new(values: &[Float], dx: usize, dy: usize) -> Result<Self> {
+        check_len(values.len(), dx, dy)?;
+        let mut runs = Vec::new();
+        let mut rows = Vec::with_capacity(dy + 1);
+        for row in values.chunks(dx.max(1)).take(dy) {
+            rows.push(runs.len());
+            for (x, value) in row.iter().enumerate() {
+                let same = |(_, v): &(usize, Float)| v == value || (v.is_nan() && value.is_nan());
+                if x == 0 || !runs.last().is_some_and(same) {
+                    runs.push((x, *value));
+                }
+            }
+        }
+        rows.resize(dy + 1, runs.len());
+        Ok(RleBuffer { dx, dy, runs, rows })
+    }
+
+    /// Returns the number of columns of the grid.
+    pub fn dx(&self) -> usize {
+        self.dx
+    }
+
+    /// Returns the number of rows of the grid.
+    pub fn dy(&self) -> usize {
+        self.dy
+    }
+
+    /// Returns the number of runs of equal values stored for the grid.
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Returns the value at column `x` and row `y` of the grid, if any.
+    pub fn get(&self, x: usize, y: usize) -> Option<Float> {
+        if x >= self.dx || y >= self.dy {
+            return None;
+        }
+        let runs = self.row_runs(y);
+        let i = runs.partition_point(|(start, _)| *start <= x);
+        Some(runs[i - 1].1)
+    }
+
+    /// Returns the values of the row `y` of the grid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is greater than or equal to the number of rows.
+    pub fn row(&self, y: usize) -> Vec<Float> {
+        let runs = self.row_runs(y);
+        let mut row = Vec::with_capacity(self.dx);
+        for (i, (start, value)) in runs.iter().enumerate() {
+            let end = runs.get(i + 1).map_or(self.dx, |(next, _)| *next);
+            row.resize(row.len() + end - start, *value);
+        }
+        row
+    }
+
+    /// Returns the values of the grid, row by row.
+    pub fn values(&self) -> Vec<Float> {
+        (0..self.dy).flat_map(|y| self.row(y)).collect()
+    }
+
+    /// Returns the extents (in samples) of the grid that can contain the isolines
+    /// of the given `threshold`, which cover the samples having a neighbour
+    /// on the other side of the threshold (the NODATA values being below it,
+    /// as the samples outside the grid), row by row.
+    ///
+    /// They can be given to [`IsoRingBuilder::compute_in`].
+    pub fn extents(&self, threshold: Float) -> Vec<Rect<usize>> {
+        let mut extents = Vec::new();
+        let inside = |y: usize| {
+            if y < self.dy {
+                self.inside(y, threshold)
+            } else {
+                Vec::new()
+            }
+        };
+        let (mut previous, mut current) = (Vec::new(), inside(0));
+        for y in 0..self.dy {
+            let next = inside(y + 1);
+            let mut spans = Vec::new();
+            // The samples at the ends of the runs inside the isolines
+            for (start, end) in &current {
+                spans.push((start.saturating_sub(1), start + 1));
+                spans.push((end - 1, (end + 1).min(self.dx)));
+            }
+            // The samples whose upper or lower neighbour is on the other side
+            spans.extend(difference(&current, &previous));
+            spans.extend(difference(&current, &next));
+            spans.sort_unstable();
+            let mut merged: Vec<(usize, usize)> = Vec::new();
+            for (start, end) in spans {
+                match merged.last_mut() {
+                    Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                    _ => merged.push((start, end)),
+                }
+            }
+            extents.extend(merged.into_iter().map(|(start, end)| {
+                Rect::new(coord! { x: start, y: y }, coord! { x: end, y: y + 1 })
+            }));
+            previous = std::mem::replace(&mut current, next);
+        }
+        extents
+    }
+
+    /// Computes the isorings of the grid for the given `threshold` (as
+    /// [`contour_rings`](crate::contour_rings)), traversing only its [`extents`](Self::extents).
+    pub fn contour_rings(&self, threshold: Float) -> Result<Vec<Ring>> {
+        let mut isoring = IsoRingBuilder::new(self.dx, self.dy);
+        isoring.compute_extents(&self.extents(threshold), |x, y| {
+            self.get(x, y).is_some_and(|v| v >= threshold)
+        })
+    }
+
+    fn row_runs(&self, y: usize) -> &[(usize, Float)] {
+        &self.runs[self.rows[y]..self.rows[y + 1]]
+    }
+
+    // Returns the spans of columns of the row `y` whose values are greater than
+    // or equal to the `threshold`.
+    fn inside(&self, y: usize, threshold: Float) -> Vec<(usize, usize)> {
+        let runs = self.row_runs(y);
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        for (i, (start, value)) in runs.iter().enumerate() {
+            if *value >= threshold {
+                let end = runs.get(i + 1).map_or(self.dx, |(next, _)| *next);
+                match spans.last_mut() {
+                    Some(last) if last.1 == *start => last.1 = end,
+                    _ => spans.push((*start, end)),
+                }
+            }
+        }
+        spans
+    }
+}
+
+// Returns the spans of columns covered by only one of the sorted spans `a` and `b`.
+fn difference(a: &[(usize, usize)], b: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let covers = |spans: &[(usize, usize)], x: usize| {
+        let i = spans.partition_point(|(start, _)| *start <= x);
+        i > 0 && x < spans[i - 1].1
+    };
+    let mut bounds = a
+        .iter()
+        .chain(b)
+        .flat_map(|(start, end)| [*start, *end])
+        .collect::<Vec<_>>();
+    bounds.sort_unstable();
+    bounds.dedup();
+    bounds
+        .windows(2)
+        .filter(|w| covers(a, w[0]) != covers(b, w[0]))
+        .map(|w| (w[0], w[1]))
+        .collect()
+}
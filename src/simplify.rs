@@ -0,0 +1,164 @@
+use crate::distance::segment_distance;
+use crate::{Band, Float, Pt};
+use geo_types::{LineString, MultiPolygon, Polygon};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+type Key = (u64, u64);
+
+#[allow(clippy::unnecessary_cast)]
+fn key(point: &Pt) -> Key {
+    (point.x.to_bits() as u64, point.y.to_bits() as u64)
+}
+
+/// Simplifies the boundaries of adjacent bands (such as computed by
+/// [`ContourBuilder::isobands`](crate::ContourBuilder::isobands)) without creating
+/// gaps or overlaps between them, using the Douglas-Peucker algorithm with the given `tolerance`.
+///
+/// The rings are split into arcs at the vertices where more than two boundaries meet
+/// (such as where an isoline reaches the border of the grid). Each arc shared by two bands
+/// is simplified the same way in both of them, as opposed to simplifying each polygon on its own.
+/// Shared vertices must be exactly equal, as is the case for the bands of a single computation.
+///
+/// Each arc keeps at least one of its intermediate vertices (two for the rings without
+/// any such meeting vertex), so that the rings don't collapse.
+///
+/// ```
+/// use contour::{simplify_bands, ContourBuilder};
+///
+/// let builder = ContourBuilder::new(4, 4, true);
+/// let values = [0., 1., 1., 0., 1., 2., 3., 1., 1., 3., 2., 1., 0., 1., 1., 0.];
+/// let bands = builder.isobands(&values, &[0., 0.5, 1.5, 3.]).unwrap();
+/// let simplified = simplify_bands(&bands, 0.2);
+/// assert_eq!(simplified.len(), bands.len());
+/// ```
+pub fn simplify_bands(bands: &[Band], tolerance: Float) -> Vec<Band> {
+    let rings = || {
+        bands.iter().flat_map(|band| {
+            band.geometry
+                .iter()
+                .flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors()))
+        })
+    };
+
+    // The vertices where more than two boundaries meet
+    let mut neighbors: FxHashMap<Key, FxHashSet<Key>> = FxHashMap::default();
+    for ring in rings() {
+        for w in ring.0.windows(2) {
+            let (a, b) = (key(&w[0]), key(&w[1]));
+            if a != b {
+                neighbors.entry(a).or_default().insert(b);
+                neighbors.entry(b).or_default().insert(a);
+            }
+        }
+    }
+    let junctions = neighbors
+        .into_iter()
+        .filter(|(_, n)| n.len() > 2)
+        .map(|(k, _)| k)
+        .collect::<FxHashSet<_>>();
+
+    let simplify =
+        |ring: &LineString<Float>| LineString(simplify_ring(&ring.0, &junctions, tolerance));
+    bands
+        .iter()
+        .map(|band| {
+            let geometry = band
+                .geometry
+                .iter()
+                .map(|polygon| {
+                    Polygon::new(
+                        simplify(polygon.exterior()),
+                        polygon.interiors().iter().map(simplify).collect(),
+                    )
+                })
+                .collect();
+            let mut simplified = Band::new(MultiPolygon(geometry), band.min_v, band.max_v);
+            simplified.threshold_indices = band.threshold_indices;
+            simplified
+        })
+        .collect()
+}
+
+fn simplify_ring(ring: &[Pt], junctions: &FxHashSet<Key>, tolerance: Float) -> Vec<Pt> {
+    if ring.len() < 4 {
+        return ring.to_vec();
+    }
+    let vertices = &ring[..ring.len() - 1];
+    let mut fixed = (0..vertices.len())
+        .filter(|i| junctions.contains(&key(&vertices[*i])))
+        .collect::<Vec<_>>();
+    if fixed.is_empty() {
+        // Split the ring at its smallest vertex, which is the same for all its copies
+        let smallest = (0..vertices.len())
+            .min_by(|i, j| {
+                let (p, q) = (vertices[*i], vertices[*j]);
+                p.x.total_cmp(&q.x).then(p.y.total_cmp(&q.y))
+            })
+            .unwrap();
+        fixed.push(smallest);
+    }
+
+    let n = vertices.len();
+    let mut result = vec![vertices[fixed[0]]];
+    for (k, start) in fixed.iter().enumerate() {
+        let end = fixed.get(k + 1).copied().unwrap_or(fixed[0] + n);
+        let arc = (*start..=end).map(|i| vertices[i % n]).collect::<Vec<_>>();
+        result.extend(simplify_arc(&arc, tolerance).into_iter().skip(1));
+    }
+    result
+}
+
+// Simplifies the arc in a canonical direction, so that the copies of an arc shared
+// by two rings (walked in opposite directions) are simplified the same way.
+fn simplify_arc(arc: &[Pt], tolerance: Float) -> Vec<Pt> {
+    let forward = arc
+        .iter()
+        .zip(arc.iter().rev())
+        .map(|(p, q)| p.x.total_cmp(&q.x).then(p.y.total_cmp(&q.y)))
+        .find(|o| o.is_ne())
+        .is_none_or(|o| o.is_lt());
+    let mut arc = arc.to_vec();
+    if !forward {
+        arc.reverse();
+    }
+    let mut keep = vec![false; arc.len()];
+    keep[0] = true;
+    keep[arc.len() - 1] = true;
+    // A closed arc has to keep two intermediate vertices to remain a ring
+    let min_depth = if arc[0] == arc[arc.len() - 1] { 2 } else { 1 };
+    douglas_peucker(&arc, 0, arc.len() - 1, tolerance, min_depth, &mut keep);
+    let mut result = arc
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(p, k)| k.then_some(p))
+        .collect::<Vec<_>>();
+    if !forward {
+        result.reverse();
+    }
+    result
+}
+
+// Keeps the vertices farther than `tolerance` from the simplified line, along with
+// the farthest vertices of the first `min_depth` levels of the recursion.
+fn douglas_peucker(
+    points: &[Pt],
+    first: usize,
+    last: usize,
+    tolerance: Float,
+    min_depth: usize,
+    keep: &mut [bool],
+) {
+    if last <= first + 1 {
+        return;
+    }
+    let (a, b) = (points[first], points[last]);
+    let (farthest, distance) = (first + 1..last)
+        .map(|i| (i, segment_distance(&points[i], &a, &b)))
+        .fold((first, -1.), |best, d| if d.1 > best.1 { d } else { best });
+    if distance > tolerance || min_depth > 0 {
+        let min_depth = min_depth.saturating_sub(1);
+        keep[farthest] = true;
+        douglas_peucker(points, first, farthest, tolerance, min_depth, keep);
+        douglas_peucker(points, farthest, last, tolerance, min_depth, keep);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::area::{area, contains};
+use crate::{Float, Pt, Ring};
+use geo_types::{LineString, MultiLineString, MultiPolygon, Polygon};
+use rustc_hash::FxHashMap;
+
+/// Splits the linestrings having more than `max_vertices` vertices into successive parts
+/// (sharing their end vertices) having at most `max_vertices` vertices.
+pub(crate) fn split_lines(
+    geometry: MultiLineString<Float>,
+    max_vertices: usize,
+) -> MultiLineString<Float> {
+    let step = max_vertices.max(2) - 1;
+    geometry
+        .into_iter()
+        .flat_map(|line| {
+            let n = line.0.len();
+            if n <= max_vertices {
+                return vec![line];
+            }
+            (0..n - 1)
+                .step_by(step)
+                .map(|start| LineString(line.0[start..(start + step + 1).min(n)].to_vec()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Splits the polygons having a ring of more than `max_vertices` vertices into pieces
+/// (closed along the splitting lines), by recursively cutting them in two halves at the
+/// median of the coordinates of their vertices along the longest side of their bounding box.
+///
+/// The edges shared by two rings in opposite directions (such as where a hole
+/// runs along the border of the exterior ring) are removed beforehand.
+/// A piece is not split further when cutting it doesn't reduce its number of vertices.
+pub(crate) fn split_polygons(
+    geometry: MultiPolygon<Float>,
+    max_vertices: usize,
+) -> MultiPolygon<Float> {
+    geometry
+        .into_iter()
+        .flat_map(|polygon| {
+            if longest_ring(&rings_of(&polygon)) <= max_vertices {
+                return vec![polygon];
+            }
+            // The pieces are computed with the exterior ring oriented counterclockwise
+            // (and the holes clockwise), the orientation of the polygon being restored afterwards
+            let reversed = !counterclockwise(&polygon.exterior().0);
+            let (exterior, interiors) = polygon.into_inner();
+            let rings = std::iter::once(exterior)
+                .chain(interiors)
+                .enumerate()
+                .map(|(i, ring)| {
+                    let mut ring = ring.0;
+                    if counterclockwise(&ring) != (i == 0) {
+                        ring.reverse();
+                    }
+                    ring
+                })
+                .collect::<Vec<_>>();
+            assemble(dissolve(&rings))
+                .into_iter()
+                .flat_map(|rings| split(rings, max_vertices))
+                .map(|mut rings| {
+                    if reversed {
+                        rings.iter_mut().for_each(|ring| ring.reverse());
+                    }
+                    let exterior = rings.remove(0);
+                    Polygon::new(exterior.into(), rings.into_iter().map(Into::into).collect())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn rings_of(polygon: &Polygon<Float>) -> Vec<&[Pt]> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .map(|ring| &ring.0[..])
+        .collect()
+}
+
+fn longest_ring<R: AsRef<[Pt]>>(rings: &[R]) -> usize {
+    rings
+        .iter()
+        .map(|ring| ring.as_ref().len())
+        .max()
+        .unwrap_or(0)
+}
+
+fn total_vertices(rings: &[Ring]) -> usize {
+    rings.iter().map(Vec::len).sum()
+}
+
+// Note that `area` is positive for the clockwise rings.
+fn counterclockwise(ring: &[Pt]) -> bool {
+    area(ring) < 0.
+}
+
+// Splits the rings of a polygon (its exterior ring first) into the rings of its pieces.
+fn split(rings: Vec<Ring>, max_vertices: usize) -> Vec<Vec<Ring>> {
+    if longest_ring(&rings) <= max_vertices {
+        return vec![rings];
+    }
+    let Some((vertical, c)) = median_cut(&rings) else {
+        return vec![rings];
+    };
+    let total = total_vertices(&rings);
+    [true, false]
+        .into_iter()
+        .flat_map(|low| clip(&rings, &Cut { vertical, c, low }))
+        .flat_map(|piece| {
+            if total_vertices(&piece) < total {
+                split(piece, max_vertices)
+            } else {
+                vec![piece]
+            }
+        })
+        .collect()
+}
+
+// Returns the line cutting the longest ring of the polygon in two halves (vertical, at the
+// given abscissa, if the ring is wider than high), passing through none of its vertices.
+fn median_cut(rings: &[Ring]) -> Option<(bool, Float)> {
+    let ring = rings.iter().max_by_key(|ring| ring.len())?;
+    let extent = |f: fn(&Pt) -> Float| {
+        let (min, max) = ring
+            .iter()
+            .map(f)
+            .fold((Float::INFINITY, Float::NEG_INFINITY), |(a, b), v| {
+                (a.min(v), b.max(v))
+            });
+        max - min
+    };
+    let vertical = extent(|p| p.x) >= extent(|p| p.y);
+    let mut coordinates = ring[..ring.len() - 1]
+        .iter()
+        .map(|p| if vertical { p.x } else { p.y })
+        .collect::<Vec<_>>();
+    coordinates.sort_by(|a, b| a.total_cmp(b));
+    let m = coordinates.len() / 2;
+    // The closest pair of successive distinct coordinates around the median
+    (0..m).find_map(|d| {
+        [m - d, m + d]
+            .into_iter()
+            .filter(|i| *i > 0 && *i < coordinates.len())
+            .find(|i| coordinates[i - 1] < coordinates[*i])
+            .map(|i| (vertical, (coordinates[i - 1] + coordinates[i]) / 2.))
+    })
+}
+
+// A line cutting a polygon, keeping the side of the lower (or greater) coordinates.
+struct Cut {
+    vertical: bool,
+    c: Float,
+    low: bool,
+}
+
+impl Cut {
+    fn outside(&self, p: &Pt) -> bool {
+        let v = if self.vertical { p.x } else { p.y };
+        (v > self.c) == self.low
+    }
+
+    // The coordinate along the line, increasing in the direction that keeps the inside
+    // of the counterclockwise rings on the left.
+    fn along(&self, p: &Pt) -> Float {
+        match (self.vertical, self.low) {
+            (true, true) => p.y,
+            (true, false) => -p.y,
+            (false, true) => -p.x,
+            (false, false) => p.x,
+        }
+    }
+
+    // The intersection of the line with the segment between `a` and `b`, computed
+    // the same way whatever the direction of the segment and the side kept.
+    fn intersection(&self, a: &Pt, b: &Pt) -> Pt {
+        let (a, b) = if (a.x, a.y) < (b.x, b.y) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        if self.vertical {
+            let t = (self.c - a.x) / (b.x - a.x);
+            Pt {
+                x: self.c,
+                y: a.y + t * (b.y - a.y),
+            }
+        } else {
+            let t = (self.c - a.y) / (b.y - a.y);
+            Pt {
+                x: a.x + t * (b.x - a.x),
+                y: self.c,
+            }
+        }
+    }
+}
+
+// Clips the rings of a polygon (its exterior ring oriented counterclockwise and its holes
+// clockwise) by the side of the line kept by the `cut`, returning the rings of the pieces.
+fn clip(rings: &[Ring], cut: &Cut) -> Vec<Vec<Ring>> {
+    let (mut closed, mut chains) = (Vec::new(), Vec::new());
+    for ring in rings {
+        let n = ring.len() - 1;
+        let Some(start) = (0..n).find(|i| cut.outside(&ring[*i])) else {
+            closed.push(ring.clone());
+            continue;
+        };
+        // The parts of the ring inside, from where it enters to where it exits
+        let mut chain: Option<Ring> = None;
+        for k in 0..n {
+            let (a, b) = (&ring[(start + k) % n], &ring[(start + k + 1) % n]);
+            match (cut.outside(a), cut.outside(b)) {
+                (true, false) => chain = Some(vec![cut.intersection(a, b), *b]),
+                (false, false) => chain.iter_mut().for_each(|chain| chain.push(*b)),
+                (false, true) => {
+                    if let Some(mut chain) = chain.take() {
+                        chain.push(cut.intersection(a, b));
+                        chains.push(chain);
+                    }
+                }
+                (true, true) => {}
+            }
+        }
+    }
+
+    // Each chain is followed, along the line, by the chain entering next
+    let mut entries = chains
+        .iter()
+        .enumerate()
+        .map(|(i, chain)| (cut.along(&chain[0]), i))
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut visited = vec![false; chains.len()];
+    for i in 0..chains.len() {
+        let mut ring = Vec::new();
+        let mut j = i;
+        while !visited[j] {
+            visited[j] = true;
+            ring.extend_from_slice(&chains[j]);
+            let exit = cut.along(&chains[j][chains[j].len() - 1]);
+            match entries.get(entries.partition_point(|(e, _)| *e < exit)) {
+                Some((_, k)) => j = *k,
+                None => break,
+            }
+        }
+        if !ring.is_empty() {
+            ring.push(ring[0]);
+            closed.push(ring);
+        }
+    }
+
+    assemble(closed)
+}
+
+// Groups counterclockwise rings (the exteriors) and clockwise rings (the holes) into the
+// rings of polygons, each hole being assigned to the smallest exterior ring enclosing it.
+fn assemble(closed: Vec<Ring>) -> Vec<Vec<Ring>> {
+    let (mut exteriors, holes): (Vec<Ring>, Vec<Ring>) =
+        closed.into_iter().partition(|ring| counterclockwise(ring));
+    exteriors.sort_by(|a, b| area(a).abs().total_cmp(&area(b).abs()));
+    let mut pieces = exteriors
+        .into_iter()
+        .map(|exterior| vec![exterior])
+        .collect::<Vec<_>>();
+    for hole in holes {
+        if let Some(piece) = pieces
+            .iter_mut()
+            .find(|piece| contains(&piece[0], &hole) == 1)
+        {
+            piece.push(hole);
+        }
+    }
+    pieces
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn key(point: &Pt) -> (u64, u64) {
+    (point.x.to_bits() as u64, point.y.to_bits() as u64)
+}
+
+// Removes the pairs of opposite edges from the rings, and links the remaining edges
+// into rings again.
+fn dissolve(rings: &[Ring]) -> Vec<Ring> {
+    let mut edges: FxHashMap<_, (Pt, Pt, usize)> = FxHashMap::default();
+    for w in rings.iter().flat_map(|ring| ring.windows(2)) {
+        let (a, b) = (key(&w[0]), key(&w[1]));
+        if a == b {
+            continue;
+        }
+        match edges.get_mut(&(b, a)) {
+            Some((_, _, count)) if *count > 0 => *count -= 1,
+            _ => edges.entry((a, b)).or_insert((w[0], w[1], 0)).2 += 1,
+        }
+    }
+    let mut outgoing: FxHashMap<_, Vec<Pt>> = FxHashMap::default();
+    let mut starts = Vec::new();
+    for (a, b, count) in edges.into_values() {
+        for _ in 0..count {
+            outgoing.entry(key(&a)).or_default().push(b);
+            starts.push(a);
+        }
+    }
+    let mut result = Vec::new();
+    for start in starts {
+        let mut ring = vec![start];
+        let mut current = start;
+        while let Some(next) = outgoing.get_mut(&key(&current)).and_then(|next| next.pop()) {
+            ring.push(next);
+            current = next;
+            if key(&current) == key(&start) {
+                break;
+            }
+        }
+        if ring.len() >= 4 {
+            result.push(ring);
+        }
+    }
+    result
+}
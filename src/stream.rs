@@ -0,0 +1,142 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::isoringbuilder::{IsoRingBuilder, MAX_DIMENSION};
+use crate::{Float, Pt, Ring};
+
+/// Isorings generator for a single threshold, consuming the values of the grid
+/// row by row, built by [`ContourBuilder::ring_stream`](crate::ContourBuilder::ring_stream).
+///
+/// Only the previous row and the fragments of the rings crossing the current row are kept
+/// in memory: each ring is passed to the sink as soon as it is closed, so that grids
+/// that don't fit in memory (such as continent-scale elevation models read by strips)
+/// can be contoured.
+///
+/// The rings are smoothed (if enabled on the builder) and transformed according to
+/// the origin and the step of the builder. Exterior rings have a positive area
+/// and holes a negative one, but they are not assembled into polygons.
+///
+/// ```
+/// use contour::ContourBuilder;
+///
+/// let builder = ContourBuilder::new(3, 3, true);
+/// let mut stream = builder.ring_stream(0.5);
+/// let mut rings = Vec::new();
+/// for row in [[0., 0., 0.], [0., 1., 0.], [0., 0., 0.]] {
+///     stream.push_row(&row, |ring| rings.push(ring)).unwrap();
+/// }
+/// stream.finish(|ring| rings.push(ring)).unwrap();
+/// assert_eq!(rings.len(), 1);
+/// ```
+pub struct RingStream {
+    isoring: IsoRingBuilder,
+    dx: usize,
+    threshold: Float,
+    smooth: bool,
+    transform: (Float, Float, Float, Float),
+    previous: Option<Vec<Float>>,
+    y: i64,
+}
+
+impl RingStream {
+    pub(crate) fn new(
+        dx: usize,
+        smooth: bool,
+        transform: (Float, Float, Float, Float),
+        threshold: Float,
+    ) -> Self {
+        RingStream {
+            isoring: IsoRingBuilder::new(dx, 0),
+            dx,
+            threshold,
+            smooth,
+            transform,
+            previous: None,
+            y: -1,
+        }
+    }
+
+    /// Processes the next `row` of values, passing the rings closed
+    /// by this row to the `sink`.
+    ///
+    /// Returns an [`ErrorKind::BadDimension`] error if the length of `row` doesn't match the
+    /// number of columns of the grid, or an [`ErrorKind::Overflow`] error if the grid is too
+    /// large for its rings to be stitched exactly.
+    pub fn push_row<F: FnMut(Ring)>(&mut self, row: &[Float], sink: F) -> Result<()> {
+        if row.len() != self.dx {
+            return Err(new_error(ErrorKind::BadDimension));
+        }
+        if self.dx > MAX_DIMENSION || self.y >= MAX_DIMENSION as i64 {
+            return Err(new_error(ErrorKind::Overflow));
+        }
+        let previous = self.previous.take();
+        self.process(previous.as_deref(), Some(row), sink)?;
+        self.previous = Some(row.to_vec());
+        Ok(())
+    }
+
+    /// Processes the bottom border of the grid after its last row,
+    /// passing the remaining rings to the `sink`.
+    pub fn finish<F: FnMut(Ring)>(mut self, sink: F) -> Result<()> {
+        if let Some(previous) = self.previous.take() {
+            self.process(Some(&previous), None, sink)?;
+        }
+        Ok(())
+    }
+
+    fn process<F: FnMut(Ring)>(
+        &mut self,
+        upper: Option<&[Float]>,
+        lower: Option<&[Float]>,
+        mut sink: F,
+    ) -> Result<()> {
+        let mut result = Vec::new();
+        let y = self.y;
+        let (threshold, smooth) = (self.threshold, self.smooth);
+        let (x_origin, y_origin, x_step, y_step) = self.transform;
+        let map = |mut point: Pt| {
+            if smooth {
+                smooth_point(&mut point, upper, lower, y, threshold);
+            }
+            Pt {
+                x: point.x * x_step + x_origin,
+                y: point.y * y_step + y_origin,
+            }
+        };
+        self.isoring
+            .compute_row(upper, lower, y, threshold, &map, &mut result)?;
+        result.into_iter().for_each(&mut sink);
+        self.y += 1;
+        Ok(())
+    }
+}
+
+// Moves a point lying on the edge between two samples of the `upper` row `y`
+// or of the `lower` row `y + 1` according to the linear interpolation of their values
+// (as done by the smoothing of the `ContourBuilder`, leaving the points next to NODATA values).
+fn smooth_point(
+    point: &mut Pt,
+    upper: Option<&[Float]>,
+    lower: Option<&[Float]>,
+    y: i64,
+    value: Float,
+) {
+    let (x, yp) = (point.x, point.y);
+    let (xt, yt) = (x.trunc(), yp.trunc());
+    let row = if yt as i64 == y { upper } else { lower };
+    if let Some(row) = row {
+        if x > 0.0 && (x as usize) < row.len() && (xt - x).abs() < Float::EPSILON {
+            let (v0, v1) = (row[xt as usize - 1], row[xt as usize]);
+            if !v0.is_nan() && !v1.is_nan() {
+                point.x = x + (value - v0) / (v1 - v0) - 0.5;
+            }
+        }
+    }
+    if let (Some(upper), Some(lower)) = (upper, lower) {
+        let i = xt as usize;
+        if yt as i64 == y + 1 && (yt - yp).abs() < Float::EPSILON && i < lower.len() {
+            let (v0, v1) = (upper[i], lower[i]);
+            if !v0.is_nan() && !v1.is_nan() {
+                point.y = yp + (value - v0) / (v1 - v0) - 0.5;
+            }
+        }
+    }
+}
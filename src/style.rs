@@ -0,0 +1,94 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::Float;
+use serde_json::{json, Value};
+
+/// Returns a MapLibre (or Mapbox GL) style layer filling the bands computed with the given
+/// `thresholds` (whose GeoJSON features, as returned by [`Band::to_geojson`](crate::Band::to_geojson),
+/// are loaded in the `source` of the style), with a color sampled evenly from the color `ramp`
+/// (from its first color for the lowest band to its last color for the highest one).
+///
+/// The fill color is a `step` expression on the `min_v` property of the features.
+/// For a vector tiles source, the `source-layer` key has to be added to the layer.
+///
+/// Returns an [`ErrorKind::Unexpected`] error if the `ramp` is empty.
+///
+/// ```
+/// use contour::maplibre_fill_layer;
+///
+/// let layer = maplibre_fill_layer("bands", "contours", &[0., 10., 20.], &["#ffffcc", "#800026"]).unwrap();
+/// assert_eq!(
+///     layer["paint"]["fill-color"].to_string(),
+///     r##"["step",["get","min_v"],"#ffffcc",10.0,"#800026"]"##
+/// );
+/// ```
+pub fn maplibre_fill_layer(
+    id: &str,
+    source: &str,
+    thresholds: &[Float],
+    ramp: &[&str],
+) -> Result<Value> {
+    if ramp.is_empty() {
+        return Err(new_error(ErrorKind::Unexpected));
+    }
+    let mut thresholds = thresholds.to_vec();
+    thresholds.sort_by(|a, b| a.total_cmp(b));
+    let bands = thresholds.len().saturating_sub(1).max(1);
+    let color = |i: usize| match bands {
+        1 => ramp[0],
+        _ => ramp[i * (ramp.len() - 1) / (bands - 1)],
+    };
+    let mut expression = vec![json!("step"), json!(["get", "min_v"]), json!(color(0))];
+    for (i, threshold) in thresholds.iter().enumerate().take(bands).skip(1) {
+        expression.push(json!(threshold));
+        expression.push(json!(color(i)));
+    }
+    Ok(json!({
+        "id": id,
+        "type": "fill",
+        "source": source,
+        "paint": { "fill-color": expression },
+    }))
+}
+
+/// Returns a MapLibre (or Mapbox GL) style layer drawing the isolines computed with the given
+/// `thresholds` (whose GeoJSON features, as returned by [`Line::to_geojson`](crate::Line::to_geojson),
+/// are loaded in the `source` of the style), every `index_interval`-th isoline
+/// (starting from the first threshold) being drawn as an index contour with the `index_width`,
+/// and the other ones with the `width`.
+///
+/// The line width is a `match` expression on the `threshold` property of the features.
+/// For a vector tiles source, the `source-layer` key has to be added to the layer.
+///
+/// ```
+/// use contour::maplibre_line_layer;
+///
+/// let layer = maplibre_line_layer("isolines", "contours", &[0., 10., 20., 30.], 2, 0.5, 1.5);
+/// assert_eq!(
+///     layer["paint"]["line-width"].to_string(),
+///     r#"["match",["get","threshold"],[0.0,20.0],1.5,0.5]"#
+/// );
+/// ```
+pub fn maplibre_line_layer(
+    id: &str,
+    source: &str,
+    thresholds: &[Float],
+    index_interval: usize,
+    width: Float,
+    index_width: Float,
+) -> Value {
+    let index = thresholds
+        .iter()
+        .step_by(index_interval.max(1))
+        .collect::<Vec<_>>();
+    let line_width = if index.is_empty() {
+        json!(width)
+    } else {
+        json!(["match", ["get", "threshold"], index, index_width, width])
+    };
+    json!({
+        "id": id,
+        "type": "line",
+        "source": source,
+        "paint": { "line-width": line_width },
+    })
+}
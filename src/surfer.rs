@@ -0,0 +1,196 @@
+use crate::error::{new_error, ErrorKind, Result};
+use crate::{Buffer, ByteOrder, ContourBuilder, DataType, Float, RawReader};
+use std::io::Read;
+
+// The values greater than or equal to this value are blanked in the Surfer 6 formats.
+const BLANK_VALUE: f64 = 1.70141e38;
+
+/// A grid loaded from a Golden Software Surfer `.grd` file, along with its georeferencing.
+///
+/// The ASCII (`DSAA`) and binary (`DSBB`) Surfer 6 formats and the Surfer 7 (`DSRB`)
+/// format are supported. The blanked nodes are loaded as NaN, and the first row
+/// of the grid is the southernmost one (as stored in these files).
+///
+/// ```
+/// use contour::SurferGrid;
+///
+/// let grd = "DSAA\n3 2\n0 10\n0 5\n0 2\n0 1 0\n1 2 1.70141e38\n";
+/// let grid = SurferGrid::from_reader(grd.as_bytes()).unwrap();
+/// assert_eq!(grid.buffer().get(1, 1), Some(&2.));
+/// assert!(grid.buffer().get(2, 1).unwrap().is_nan());
+/// let contours = grid
+///     .contour_builder(false)
+///     .contours(grid.buffer().data(), &[0.5])
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SurferGrid {
+    buffer: Buffer<Float>,
+    x_min: Float,
+    y_min: Float,
+    x_step: Float,
+    y_step: Float,
+}
+
+impl SurferGrid {
+    /// Reads a grid from `reader`, detecting its format from its first bytes.
+    ///
+    /// Returns an [`ErrorKind::BadFormat`] error if the file isn't a Surfer grid.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut tag = [0; 4];
+        reader.read_exact(&mut tag)?;
+        match &tag {
+            b"DSAA" => SurferGrid::read_ascii(reader),
+            b"DSBB" => SurferGrid::read_surfer6(reader),
+            b"DSRB" => SurferGrid::read_surfer7(reader),
+            _ => Err(new_error(ErrorKind::BadFormat)),
+        }
+    }
+
+    /// Borrow the values of the grid, row by row.
+    pub fn buffer(&self) -> &Buffer<Float> {
+        &self.buffer
+    }
+
+    /// Get the owned values of the grid.
+    pub fn into_buffer(self) -> Buffer<Float> {
+        self.buffer
+    }
+
+    /// Returns the coordinates of the southwesternmost node of the grid.
+    pub fn min(&self) -> (Float, Float) {
+        (self.x_min, self.y_min)
+    }
+
+    /// Returns the spacing of the nodes of the grid, along each axis.
+    pub fn step(&self) -> (Float, Float) {
+        (self.x_step, self.y_step)
+    }
+
+    /// Constructs a [`ContourBuilder`] for this grid, whose origin and steps
+    /// place the contours at the coordinates of the nodes of the grid.
+    pub fn contour_builder(&self, smooth: bool) -> ContourBuilder {
+        ContourBuilder::new(self.buffer.dx(), self.buffer.dy(), smooth)
+            .x_origin(self.x_min - self.x_step / 2.)
+            .y_origin(self.y_min - self.y_step / 2.)
+            .x_step(self.x_step)
+            .y_step(self.y_step)
+    }
+
+    fn read_ascii<R: Read>(mut reader: R) -> Result<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut tokens = text.split_whitespace();
+        let mut next = || -> Result<f64> {
+            tokens
+                .next()
+                .and_then(|t| t.parse().ok())
+                .ok_or_else(|| new_error(ErrorKind::BadFormat))
+        };
+        let (nx, ny) = (next()? as usize, next()? as usize);
+        let (x_min, x_max, y_min, y_max) = (next()?, next()?, next()?, next()?);
+        let _z_range = (next()?, next()?);
+        let values = (0..nx.saturating_mul(ny))
+            .map(|_| next())
+            .collect::<Result<Vec<_>>>()?;
+        SurferGrid::new(values, nx, ny, (x_min, x_max, y_min, y_max), BLANK_VALUE)
+    }
+
+    fn read_surfer6<R: Read>(mut reader: R) -> Result<Self> {
+        let mut header = [0; 52];
+        reader.read_exact(&mut header)?;
+        let nx = i16::from_le_bytes([header[0], header[1]]).max(0) as usize;
+        let ny = i16::from_le_bytes([header[2], header[3]]).max(0) as usize;
+        let f = |i: usize| f64::from_le_bytes(header[4 + i * 8..12 + i * 8].try_into().unwrap());
+        let values = read_values(reader, nx, ny, DataType::F32)?;
+        SurferGrid::new(values, nx, ny, (f(0), f(1), f(2), f(3)), BLANK_VALUE)
+    }
+
+    fn read_surfer7<R: Read>(mut reader: R) -> Result<Self> {
+        // The rest of the header section (its size and the version of the format)
+        let mut size = [0; 4];
+        reader.read_exact(&mut size)?;
+        skip(&mut reader, u32::from_le_bytes(size) as u64)?;
+        let mut header = None;
+        loop {
+            let mut section = [0; 8];
+            reader.read_exact(&mut section)?;
+            let size = u32::from_le_bytes(section[4..].try_into().unwrap()) as u64;
+            match (&section[..4], header) {
+                (b"GRID", _) => {
+                    let mut grid = [0; 72];
+                    reader.read_exact(&mut grid)?;
+                    let i = |i: usize| i32::from_le_bytes(grid[i..i + 4].try_into().unwrap());
+                    let f = |i: usize| f64::from_le_bytes(grid[i..i + 8].try_into().unwrap());
+                    let (ny, nx) = (i(0).max(0) as usize, i(4).max(0) as usize);
+                    let (x_min, y_min, x_step, y_step) = (f(8), f(16), f(24), f(32));
+                    let extent = (
+                        x_min,
+                        x_min + x_step * nx.saturating_sub(1) as f64,
+                        y_min,
+                        y_min + y_step * ny.saturating_sub(1) as f64,
+                    );
+                    header = Some((nx, ny, extent, f(64)));
+                    skip(&mut reader, size.saturating_sub(72))?;
+                }
+                (b"DATA", Some((nx, ny, extent, blank_value))) => {
+                    let values = read_values(reader, nx, ny, DataType::F64)?;
+                    return SurferGrid::new(values, nx, ny, extent, blank_value);
+                }
+                // The other sections are skipped
+                _ => skip(&mut reader, size)?,
+            }
+        }
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    fn new(
+        values: Vec<f64>,
+        nx: usize,
+        ny: usize,
+        (x_min, x_max, y_min, y_max): (f64, f64, f64, f64),
+        blank_value: f64,
+    ) -> Result<Self> {
+        let step = |min: f64, max: f64, n: usize| {
+            if n > 1 {
+                ((max - min) / (n - 1) as f64) as Float
+            } else {
+                1.
+            }
+        };
+        let data = values
+            .into_iter()
+            .map(|v| {
+                // Compared in single precision, as the values may have been stored as such
+                if v as f32 >= blank_value as f32 {
+                    Float::NAN
+                } else {
+                    v as Float
+                }
+            })
+            .collect();
+        Ok(SurferGrid {
+            buffer: Buffer::new(data, nx, ny)?,
+            x_min: x_min as Float,
+            y_min: y_min as Float,
+            x_step: step(x_min, x_max, nx),
+            y_step: step(y_min, y_max, ny),
+        })
+    }
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn read_values<R: Read>(reader: R, nx: usize, ny: usize, data_type: DataType) -> Result<Vec<f64>> {
+    Ok(RawReader::new(nx, ny, data_type)
+        .byte_order(ByteOrder::LittleEndian)
+        .read(reader)?
+        .into_inner()
+        .into_iter()
+        .map(|v| v as f64)
+        .collect())
+}
+
+fn skip<R: Read>(reader: &mut R, bytes: u64) -> Result<()> {
+    std::io::copy(&mut reader.take(bytes), &mut std::io::sink())?;
+    Ok(())
+}
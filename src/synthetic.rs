@@ -0,0 +1,134 @@
+//! Generators of synthetic grids (requires the `synthetic` feature), such as to test,
+//! benchmark or demonstrate the computation of contours on grids of any size.
+//!
+//! The generators return an [`ErrorKind::Overflow`](crate::ErrorKind::Overflow) error
+//! if the grid has more cells than the number of values addressable on the target.
+//!
+//! ```
+//! use contour::synthetic::{fbm, gaussian_bumps, Bump};
+//! use contour::ContourBuilder;
+//!
+//! let bumps = [Bump { x: 20., y: 20., sigma: 5., amplitude: 1. }];
+//! let grid = gaussian_bumps(40, 40, &bumps).unwrap();
+//! let builder = ContourBuilder::new(grid.dx(), grid.dy(), true);
+//! let contours = builder.contours(grid.data(), &[0.25, 0.5, 0.75]).unwrap();
+//! assert!(contours.iter().all(|c| c.geometry().0.len() == 1));
+//!
+//! let terrain = fbm(256, 256, 64., 4, 42).unwrap();
+//! assert_eq!(terrain.data().len(), 256 * 256);
+//! ```
+
+use crate::error::Result;
+use crate::{Buffer, Float};
+
+/// A Gaussian bump of [`gaussian_bumps`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bump {
+    /// The column of the center of the bump.
+    pub x: Float,
+    /// The row of the center of the bump.
+    pub y: Float,
+    /// The standard deviation of the bump (in cells).
+    pub sigma: Float,
+    /// The value at the center of the bump (negative for a hollow).
+    pub amplitude: Float,
+}
+
+/// Generates a grid of `dx` * `dy` values summing the given Gaussian `bumps`.
+pub fn gaussian_bumps(dx: usize, dy: usize, bumps: &[Bump]) -> Result<Buffer<Float>> {
+    from_fn(dx, dy, |x, y| {
+        bumps
+            .iter()
+            .map(|b| {
+                let d2 = (x - b.x).powi(2) + (y - b.y).powi(2);
+                b.amplitude * (-d2 / (2. * b.sigma * b.sigma)).exp()
+            })
+            .sum()
+    })
+}
+
+/// Generates a grid of `dx` * `dy` values of Perlin noise (ranging roughly from `-1` to `1`),
+/// whose features are about `scale` cells wide, the same `seed` giving the same grid.
+pub fn perlin(dx: usize, dy: usize, scale: Float, seed: u64) -> Result<Buffer<Float>> {
+    from_fn(dx, dy, |x, y| noise(x / scale, y / scale, seed))
+}
+
+/// Generates a grid of `dx` * `dy` values of fractional Brownian motion (ranging roughly
+/// from `-1` to `1`), summing `octaves` layers of Perlin noise whose features are `scale`
+/// cells wide for the first layer, each following layer having features half as wide
+/// and half the amplitude, which looks like a natural terrain (at least one layer being used).
+pub fn fbm(dx: usize, dy: usize, scale: Float, octaves: usize, seed: u64) -> Result<Buffer<Float>> {
+    let octaves = octaves.max(1);
+    let total = (0..octaves)
+        .map(|i| 1. / (1u64 << i) as Float)
+        .sum::<Float>();
+    from_fn(dx, dy, |x, y| {
+        (0..octaves)
+            .map(|i| {
+                let frequency = (1u64 << i) as Float / scale;
+                let seed = seed.wrapping_add(i as u64);
+                noise(x * frequency, y * frequency, seed) / (1u64 << i) as Float
+            })
+            .sum::<Float>()
+            / total
+    })
+}
+
+/// Generates a grid of `dx` * `dy` values increasing linearly by one per cell in the
+/// direction making the given `angle` (in radians) with the x axis, from `0` at the
+/// first value.
+pub fn ramp(dx: usize, dy: usize, angle: Float) -> Result<Buffer<Float>> {
+    let (sin, cos) = angle.sin_cos();
+    from_fn(dx, dy, |x, y| x * cos + y * sin)
+}
+
+/// Generates a grid of `dx` * `dy` values alternating between `0` and `1` by squares
+/// of `size` * `size` cells, the first square being `0`.
+pub fn checkerboard(dx: usize, dy: usize, size: usize) -> Result<Buffer<Float>> {
+    let size = size.max(1);
+    from_fn(dx, dy, |x, y| {
+        ((x as usize / size + y as usize / size) % 2) as Float
+    })
+}
+
+// Generates a grid whose value at each column `x` and row `y` is `f(x, y)`.
+fn from_fn<F: Fn(Float, Float) -> Float>(dx: usize, dy: usize, f: F) -> Result<Buffer<Float>> {
+    let mut buffer = Buffer::filled(0., dx, dy)?;
+    for (i, value) in buffer.data_mut().iter_mut().enumerate() {
+        *value = f((i % dx) as Float, (i / dx) as Float);
+    }
+    Ok(buffer)
+}
+
+// Computes the Perlin noise at the given point, the gradients at the points
+// of the integer lattice being derived from the `seed`.
+fn noise(x: Float, y: Float, seed: u64) -> Float {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (ix, iy) = (x0 as i64, y0 as i64);
+    let dot = |i: i64, j: i64, u: Float, v: Float| {
+        let (gx, gy) = gradient(ix + i, iy + j, seed);
+        gx * u + gy * v
+    };
+    let fade = |t: Float| t * t * t * (t * (t * 6. - 15.) + 10.);
+    let lerp = |a: Float, b: Float, t: Float| a + (b - a) * t;
+    let (u, v) = (fade(fx), fade(fy));
+    let top = lerp(dot(0, 0, fx, fy), dot(1, 0, fx - 1., fy), u);
+    let bottom = lerp(dot(0, 1, fx, fy - 1.), dot(1, 1, fx - 1., fy - 1.), u);
+    // Scaled so that the noise spans about [-1, 1]
+    lerp(top, bottom, v) * std::f64::consts::SQRT_2 as Float
+}
+
+// Returns the unit gradient at the point (`i`, `j`) of the lattice, using the SplitMix64
+// hash of its coordinates and of the seed.
+fn gradient(i: i64, j: i64, seed: u64) -> (Float, Float) {
+    let mut h = seed
+        ^ (i as u64).wrapping_mul(0x9e3779b97f4a7c15)
+        ^ (j as u64).wrapping_mul(0xc2b2ae3d27d4eb4f);
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d049bb133111eb);
+    h ^= h >> 31;
+    let angle = (h >> 11) as f64 / (1u64 << 53) as f64 * std::f64::consts::TAU;
+    let (sin, cos) = angle.sin_cos();
+    (cos as Float, sin as Float)
+}
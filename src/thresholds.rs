@@ -0,0 +1,133 @@
+use crate::Float;
+
+/// A classification scheme computing the thresholds of a grid from its values,
+/// that can be passed to [`ContourBuilder::lines_auto`](crate::ContourBuilder::lines_auto),
+/// [`ContourBuilder::contours_auto`](crate::ContourBuilder::contours_auto) and
+/// [`ContourBuilder::isobands_auto`](crate::ContourBuilder::isobands_auto).
+///
+/// It is implemented by [`Relative`] and [`Sigma`], and by the closures taking the values
+/// of the grid, such as to plug a domain-specific classification scheme.
+///
+/// ```
+/// use contour::{ContourBuilder, Float, ThresholdGenerator};
+///
+/// // Thresholds at each integer between the minimum and the maximum values
+/// struct Integers;
+///
+/// impl ThresholdGenerator for Integers {
+///     fn thresholds(&self, values: &[Float]) -> Vec<Float> {
+///         let valid = values.iter().copied().filter(|v| !v.is_nan());
+///         let min = valid.clone().fold(Float::INFINITY, Float::min).ceil();
+///         let max = valid.fold(Float::NEG_INFINITY, Float::max).floor();
+///         (min as i64..=max as i64).map(|t| t as Float).collect()
+///     }
+/// }
+///
+/// let values = [0., 1., 0., 1., 2.5, 1., 0., 1., 0.];
+/// let contours = ContourBuilder::new(3, 3, true).contours_auto(&values, &Integers).unwrap();
+/// assert_eq!(contours.len(), 3);
+/// ```
+pub trait ThresholdGenerator {
+    /// Computes the thresholds (in ascending order) of the given `values` of a grid,
+    /// the NODATA values (NaN) being expected to be ignored.
+    fn thresholds(&self, values: &[Float]) -> Vec<Float>;
+}
+
+impl<F: Fn(&[Float]) -> Vec<Float>> ThresholdGenerator for F {
+    fn thresholds(&self, values: &[Float]) -> Vec<Float> {
+        self(values)
+    }
+}
+
+/// The [`ThresholdGenerator`] of the thresholds relative to a reference value
+/// (see [`relative_thresholds`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relative {
+    /// The fractions of the reference value.
+    pub fractions: Vec<Float>,
+    /// The reference value (the maximum of the values if `None`).
+    pub reference: Option<Float>,
+}
+
+impl ThresholdGenerator for Relative {
+    fn thresholds(&self, values: &[Float]) -> Vec<Float> {
+        relative_thresholds(values, &self.fractions, self.reference)
+    }
+}
+
+/// The [`ThresholdGenerator`] of the thresholds at multiples of the standard deviation
+/// from the mean of the values (see [`sigma_thresholds`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sigma {
+    /// The multiples of the standard deviation.
+    pub k: Vec<Float>,
+}
+
+impl ThresholdGenerator for Sigma {
+    fn thresholds(&self, values: &[Float]) -> Vec<Float> {
+        sigma_thresholds(values, &self.k)
+    }
+}
+
+/// Computes thresholds expressed as `fractions` of a `reference` value, such as the contours
+/// at 10%, 50% and 90% of the peak of an intensity grid.
+///
+/// When `reference` is `None`, it is the maximum of the given `values`
+/// (ignoring the NODATA values), and no threshold is returned if there is no such value.
+///
+/// ```
+/// use contour::relative_thresholds;
+///
+/// let values = [0., 2., 8., 4.];
+/// assert_eq!(relative_thresholds(&values, &[0.25, 0.5], None), [2., 4.]);
+/// assert_eq!(relative_thresholds(&values, &[0.5], Some(10.)), [5.]);
+/// ```
+pub fn relative_thresholds(
+    values: &[Float],
+    fractions: &[Float],
+    reference: Option<Float>,
+) -> Vec<Float> {
+    let reference = reference.or_else(|| {
+        values
+            .iter()
+            .copied()
+            .filter(|v| !v.is_nan())
+            .reduce(Float::max)
+    });
+    match reference {
+        Some(reference) => fractions.iter().map(|f| f * reference).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Computes thresholds at `mean + k * sigma` of the given `values` for each of the
+/// given `k` (such as `[-1., 1., 2., 3.]`), `mean` and `sigma` being the mean and the
+/// (population) standard deviation of the values (ignoring the NODATA values),
+/// as usual for the contour levels of radio astronomy or signal maps.
+///
+/// No threshold is returned if there is no value (but NODATA).
+///
+/// ```
+/// use contour::sigma_thresholds;
+///
+/// let values = [2., 4., 4., 4., 5., 5., 7., 9.];
+/// assert_eq!(sigma_thresholds(&values, &[-1., 0., 2.]), [3., 5., 9.]);
+/// ```
+pub fn sigma_thresholds(values: &[Float], k: &[Float]) -> Vec<Float> {
+    let (n, sum) = values
+        .iter()
+        .filter(|v| !v.is_nan())
+        .fold((0usize, 0.), |(n, sum), v| (n + 1, sum + v));
+    if n == 0 {
+        return Vec::new();
+    }
+    let mean = sum / n as Float;
+    let variance = values
+        .iter()
+        .filter(|v| !v.is_nan())
+        .map(|v| (v - mean) * (v - mean))
+        .sum::<Float>()
+        / n as Float;
+    let sigma = variance.sqrt();
+    k.iter().map(|k| mean + k * sigma).collect()
+}
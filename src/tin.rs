@@ -0,0 +1,261 @@
+use crate::area::{area, assign_holes};
+use crate::error::{new_error, ErrorKind, Result};
+use crate::{Contour, Float, Line, Pt, Ring};
+use geo_types::{LineString, MultiLineString, MultiPolygon, Polygon};
+use rustc_hash::{FxHashMap, FxHashSet};
+use spade::handles::FixedVertexHandle;
+use spade::{DelaunayTriangulation, HasPosition, Point2, Triangulation};
+
+struct Sample {
+    position: Point2<f64>,
+    value: Float,
+}
+
+impl HasPosition for Sample {
+    type Scalar = f64;
+
+    fn position(&self) -> Point2<f64> {
+        self.position
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    Vertex(usize),
+    Edge(usize, usize),
+}
+
+/// Contours generator, using marching triangles over the Delaunay
+/// triangulation of irregularly spaced samples, to get a `Vec` of [`Line`]
+/// or of [`Contour`] without resampling the samples on a regular grid.
+///
+/// Contour polygons are closed along the convex hull of the samples.
+///
+/// ```
+/// use contour::TinBuilder;
+/// use geo_types::coord;
+///
+/// let points = vec![
+///     coord! { x: 0., y: 0. },
+///     coord! { x: 10., y: 0. },
+///     coord! { x: 10., y: 10. },
+///     coord! { x: 0., y: 10. },
+///     coord! { x: 4., y: 6. },
+/// ];
+/// let builder = TinBuilder::new(&points, &[0., 0., 0., 0., 1.]).unwrap();
+/// let res = builder.contours(&[0.5]).unwrap();
+/// assert_eq!(res[0].geometry().0.len(), 1);
+/// ```
+pub struct TinBuilder {
+    triangulation: DelaunayTriangulation<Sample>,
+}
+
+impl TinBuilder {
+    /// Constructs a new generator by triangulating the given samples.
+    ///
+    /// Returns an error if `points` and `values` don't have the same length
+    /// or if a point has non-finite coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The locations of the samples.
+    /// * `values` - The values of the samples.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(points: &[Pt], values: &[Float]) -> Result<Self> {
+        if points.len() != values.len() {
+            return Err(new_error(ErrorKind::BadDimension));
+        }
+        let mut triangulation = DelaunayTriangulation::new();
+        for (point, value) in points.iter().zip(values) {
+            triangulation
+                .insert(Sample {
+                    position: Point2::new(point.x as f64, point.y as f64),
+                    value: *value,
+                })
+                .map_err(|_| new_error(ErrorKind::Unexpected))?;
+        }
+        Ok(TinBuilder { triangulation })
+    }
+
+    /// Computes isolines for the given `thresholds`.
+    /// Returns a `Vec` of [`Line`] (that can easily be transformed
+    /// to GeoJSON Features of MultiLineString).
+    ///
+    /// # Arguments
+    ///
+    /// * `thresholds` - The slice of thresholds values to be used.
+    pub fn lines(&self, thresholds: &[Float]) -> Result<Vec<Line>> {
+        Ok(thresholds
+            .iter()
+            .map(|threshold| {
+                let (next, points) = self.segments(*threshold, false);
+                let incoming = next.values().copied().collect::<FxHashSet<_>>();
+                let mut visited = FxHashSet::default();
+                let mut linestrings = Vec::new();
+                // Open lines (ending on the convex hull) first, then closed lines
+                let starts = next
+                    .keys()
+                    .filter(|k| !incoming.contains(k))
+                    .copied()
+                    .collect::<Vec<_>>();
+                for start in starts.into_iter().chain(next.keys().copied()) {
+                    if visited.contains(&start) {
+                        continue;
+                    }
+                    let mut line = vec![points[&start]];
+                    let mut current = start;
+                    visited.insert(current);
+                    while let Some(k) = next.get(&current) {
+                        line.push(points[k]);
+                        current = *k;
+                        if !visited.insert(current) {
+                            break;
+                        }
+                    }
+                    linestrings.push(LineString(line));
+                }
+                Line::new(MultiLineString::<Float>(linestrings), *threshold)
+            })
+            .collect())
+    }
+
+    /// Computes contours for the given `thresholds`.
+    /// Returns a `Vec` of [`Contour`] (that can easily be transformed
+    /// to GeoJSON Features of MultiPolygon).
+    ///
+    /// # Arguments
+    ///
+    /// * `thresholds` - The slice of thresholds values to be used.
+    pub fn contours(&self, thresholds: &[Float]) -> Result<Vec<Contour>> {
+        thresholds
+            .iter()
+            .map(|threshold| {
+                let (mut next, points) = self.segments(*threshold, true);
+                let mut rings: Vec<Ring> = Vec::new();
+                while let Some(&start) = next.keys().next() {
+                    let mut ring = vec![points[&start]];
+                    let mut current = start;
+                    loop {
+                        let k = next
+                            .remove(&current)
+                            .ok_or_else(|| new_error(ErrorKind::Unexpected))?;
+                        ring.push(points[&k]);
+                        current = k;
+                        if current == start {
+                            break;
+                        }
+                    }
+                    rings.push(ring);
+                }
+
+                let (mut polygons, mut holes) = (Vec::new(), Vec::new());
+                rings.drain(..).for_each(|ring| {
+                    if area(&ring) > 0.0 {
+                        polygons.push(Polygon::<Float>::new(LineString::new(ring), vec![]))
+                    } else {
+                        holes.push(LineString::new(ring));
+                    }
+                });
+                assign_holes(&mut polygons, holes);
+
+                Ok(Contour::new(MultiPolygon::<Float>(polygons), *threshold))
+            })
+            .collect()
+    }
+
+    // Computes the segments (oriented with the values greater than or equal to the threshold
+    // on their right) of the isoline of the given threshold in each triangle, and optionally
+    // the parts of the convex hull enclosing these values.
+    #[allow(clippy::unnecessary_cast)]
+    fn segments(&self, threshold: Float, hull: bool) -> (FxHashMap<Key, Key>, FxHashMap<Key, Pt>) {
+        let mut next = FxHashMap::default();
+        let mut points = FxHashMap::default();
+
+        for face in self.triangulation.inner_faces() {
+            let vertices = face.vertices();
+            let inside = vertices.map(|v| v.data().value >= threshold);
+            let count = inside.iter().filter(|i| **i).count();
+            if count == 1 || count == 2 {
+                let lone_inside = count == 1;
+                let k = inside.iter().position(|i| *i == lone_inside).unwrap();
+                let (prev, lone, after) = (
+                    vertices[(k + 2) % 3].fix(),
+                    vertices[k].fix(),
+                    vertices[(k + 1) % 3].fix(),
+                );
+                let (a, b) = (
+                    self.crossing(&mut points, threshold, prev, lone),
+                    self.crossing(&mut points, threshold, lone, after),
+                );
+                if lone_inside {
+                    next.insert(a, b);
+                } else {
+                    next.insert(b, a);
+                }
+            }
+            if !hull {
+                continue;
+            }
+            for edge in face.adjacent_edges() {
+                if !edge.rev().face().is_outer() {
+                    continue;
+                }
+                // The convex hull is walked clockwise to keep the inside on the right
+                let (u, v) = (edge.to(), edge.from());
+                match (u.data().value >= threshold, v.data().value >= threshold) {
+                    (true, true) => {
+                        next.insert(Key::Vertex(u.fix().index()), Key::Vertex(v.fix().index()));
+                    }
+                    (true, false) => {
+                        let c = self.crossing(&mut points, threshold, u.fix(), v.fix());
+                        next.insert(Key::Vertex(u.fix().index()), c);
+                    }
+                    (false, true) => {
+                        let c = self.crossing(&mut points, threshold, u.fix(), v.fix());
+                        next.insert(c, Key::Vertex(v.fix().index()));
+                    }
+                    (false, false) => {}
+                }
+                for w in [u, v] {
+                    if w.data().value >= threshold {
+                        let p = w.position();
+                        points.entry(Key::Vertex(w.fix().index())).or_insert(Pt {
+                            x: p.x as Float,
+                            y: p.y as Float,
+                        });
+                    }
+                }
+            }
+        }
+        (next, points)
+    }
+
+    // Returns the key of the point where the isoline of the given threshold crosses
+    // the edge between `a` and `b` (computing this point on first use).
+    #[allow(clippy::unnecessary_cast)]
+    fn crossing(
+        &self,
+        points: &mut FxHashMap<Key, Pt>,
+        threshold: Float,
+        a: FixedVertexHandle,
+        b: FixedVertexHandle,
+    ) -> Key {
+        let (a, b) = if a.index() < b.index() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let key = Key::Edge(a.index(), b.index());
+        points.entry(key).or_insert_with(|| {
+            let (a, b) = (self.triangulation.vertex(a), self.triangulation.vertex(b));
+            let (va, vb) = (a.data().value, b.data().value);
+            let (pa, pb) = (a.position(), b.position());
+            let t = ((threshold - va) / (vb - va)) as f64;
+            Pt {
+                x: (pa.x + t * (pb.x - pa.x)) as Float,
+                y: (pa.y + t * (pb.y - pa.y)) as Float,
+            }
+        });
+        key
+    }
+}
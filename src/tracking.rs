@@ -0,0 +1,157 @@
+use crate::area::{contains, Bbox};
+use crate::{Float, Pt};
+use geo_types::{MultiPolygon, Polygon};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// An event occurring between two successive frames tracked by a [`Tracker`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackEvent {
+    /// A new track starts (its polygon doesn't overlap any polygon of the previous frame).
+    Appear(usize),
+    /// A track ends (its polygon doesn't overlap any polygon of the current frame).
+    Disappear(usize),
+    /// The polygon of a track overlaps several polygons of the current frame.
+    Split { from: usize, into: Vec<usize> },
+    /// Several polygons of the previous frame overlap the same polygon of the current frame.
+    Merge { from: Vec<usize>, into: usize },
+}
+
+/// Tracker assigning persistent identifiers to the polygons of successive frames
+/// (such as the contours of a given threshold computed on each grid of a time series).
+///
+/// Polygons of successive frames are matched by their overlap, measured by sampling
+/// them on a regular lattice of points spaced by `resolution`. A polygon keeps the
+/// identifier of the previous polygon it overlaps the most (if that previous polygon
+/// doesn't overlap another current polygon even more), otherwise it starts a new track.
+///
+/// ```
+/// use contour::{ContourBuilder, TrackEvent, Tracker};
+///
+/// let builder = ContourBuilder::new(4, 3, false);
+/// let mut tracker = Tracker::new(0.25);
+/// let frame = builder.contours(&[0., 1., 0., 0., 0., 1., 0., 0., 0., 0., 0., 0.], &[0.5]).unwrap();
+/// let (ids, _) = tracker.track(frame[0].geometry());
+/// let frame = builder.contours(&[0., 0., 1., 0., 0., 1., 1., 0., 0., 0., 0., 0.], &[0.5]).unwrap();
+/// let (next_ids, events) = tracker.track(frame[0].geometry());
+/// assert_eq!(ids, next_ids);
+/// assert!(events.is_empty());
+/// ```
+pub struct Tracker {
+    resolution: Float,
+    next_id: usize,
+    previous: Vec<(usize, FxHashSet<(i64, i64)>)>,
+}
+
+impl Tracker {
+    /// Constructs a new tracker sampling the polygons with the given `resolution`
+    /// (which should be small compared to the size of the tracked polygons).
+    pub fn new(resolution: Float) -> Self {
+        Tracker {
+            resolution,
+            next_id: 0,
+            previous: Vec::new(),
+        }
+    }
+
+    /// Tracks the `polygons` of a new frame.
+    /// Returns the identifier of each polygon, along with the events that occurred
+    /// since the previous frame.
+    pub fn track(&mut self, polygons: &MultiPolygon<Float>) -> (Vec<usize>, Vec<TrackEvent>) {
+        let current = polygons
+            .0
+            .iter()
+            .map(|polygon| self.samples(polygon))
+            .collect::<Vec<_>>();
+
+        // Overlaps between the previous (p) and the current (c) polygons,
+        // along with the largest overlap of each polygon
+        let mut overlaps: FxHashMap<(usize, usize), usize> = FxHashMap::default();
+        let mut best_next: Vec<Option<(usize, usize)>> = vec![None; self.previous.len()];
+        let mut best_prev: Vec<Option<(usize, usize)>> = vec![None; current.len()];
+        for (p, (_, previous)) in self.previous.iter().enumerate() {
+            for (c, samples) in current.iter().enumerate() {
+                let n = samples.intersection(previous).count();
+                if n == 0 {
+                    continue;
+                }
+                overlaps.insert((p, c), n);
+                if best_next[p].is_none_or(|(m, _)| n > m) {
+                    best_next[p] = Some((n, c));
+                }
+                if best_prev[c].is_none_or(|(m, _)| n > m) {
+                    best_prev[c] = Some((n, p));
+                }
+            }
+        }
+
+        let mut ids = Vec::with_capacity(current.len());
+        let mut events = Vec::new();
+        for (c, best) in best_prev.iter().enumerate() {
+            let id = match best {
+                Some((_, p)) if best_next[*p].map(|(_, next)| next) == Some(c) => {
+                    self.previous[*p].0
+                }
+                _ => {
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    id
+                }
+            };
+            ids.push(id);
+        }
+        for (c, id) in ids.iter().enumerate() {
+            let from = (0..self.previous.len())
+                .filter(|p| overlaps.contains_key(&(*p, c)))
+                .map(|p| self.previous[p].0)
+                .collect::<Vec<_>>();
+            match from.len() {
+                0 => events.push(TrackEvent::Appear(*id)),
+                1 => {}
+                _ => events.push(TrackEvent::Merge { from, into: *id }),
+            }
+        }
+        for (p, (id, _)) in self.previous.iter().enumerate() {
+            let into = (0..current.len())
+                .filter(|c| overlaps.contains_key(&(p, *c)))
+                .map(|c| ids[c])
+                .collect::<Vec<_>>();
+            match into.len() {
+                0 => events.push(TrackEvent::Disappear(*id)),
+                1 => {}
+                _ => events.push(TrackEvent::Split { from: *id, into }),
+            }
+        }
+
+        self.previous = ids.iter().copied().zip(current).collect();
+        (ids, events)
+    }
+
+    // Returns the points of the lattice lying inside the polygon.
+    fn samples(&self, polygon: &Polygon<Float>) -> FxHashSet<(i64, i64)> {
+        let mut samples = FxHashSet::default();
+        let exterior = &polygon.exterior().0;
+        if exterior.len() < 4 {
+            return samples;
+        }
+        let bbox = Bbox::new(exterior);
+        let r = self.resolution;
+        let (min, max) = (bbox.min, bbox.max);
+        for j in (min.y / r).floor() as i64..=(max.y / r).ceil() as i64 {
+            for i in (min.x / r).floor() as i64..=(max.x / r).ceil() as i64 {
+                let point = [Pt {
+                    x: (i as Float + 0.5) * r,
+                    y: (j as Float + 0.5) * r,
+                }];
+                if contains(exterior, &point) == 1
+                    && polygon
+                        .interiors()
+                        .iter()
+                        .all(|hole| contains(&hole.0, &point) == -1)
+                {
+                    samples.insert((i, j));
+                }
+            }
+        }
+        samples
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{Band, Contour, Float, Line};
+use geo_traits::{
+    Dimensions, GeometryTrait, GeometryType, MultiLineStringTrait, MultiPolygonTrait,
+    UnimplementedGeometryCollection, UnimplementedLine, UnimplementedLineString,
+    UnimplementedMultiLineString, UnimplementedMultiPoint, UnimplementedMultiPolygon,
+    UnimplementedPoint, UnimplementedPolygon, UnimplementedRect, UnimplementedTriangle,
+};
+use geo_types::{LineString, Polygon};
+
+// Implements `GeometryTrait` for an output type whose geometry is
+// either a MultiPolygon or a MultiLineString (the other types being unimplemented).
+macro_rules! impl_geometry_trait {
+    ($type:ident, $variant:ident, $multi_polygon:ty, $multi_line_string:ty) => {
+        impl GeometryTrait for $type {
+            type T = Float;
+            type PointType<'b> = UnimplementedPoint<Float>;
+            type LineStringType<'b> = UnimplementedLineString<Float>;
+            type PolygonType<'b> = UnimplementedPolygon<Float>;
+            type MultiPointType<'b> = UnimplementedMultiPoint<Float>;
+            type MultiLineStringType<'b> = $multi_line_string;
+            type MultiPolygonType<'b> = $multi_polygon;
+            type GeometryCollectionType<'b> = UnimplementedGeometryCollection<Float>;
+            type RectType<'b> = UnimplementedRect<Float>;
+            type TriangleType<'b> = UnimplementedTriangle<Float>;
+            type LineType<'b> = UnimplementedLine<Float>;
+
+            fn dim(&self) -> Dimensions {
+                Dimensions::Xy
+            }
+
+            fn as_type(
+                &self,
+            ) -> GeometryType<
+                '_,
+                UnimplementedPoint<Float>,
+                UnimplementedLineString<Float>,
+                UnimplementedPolygon<Float>,
+                UnimplementedMultiPoint<Float>,
+                $multi_line_string,
+                $multi_polygon,
+                UnimplementedGeometryCollection<Float>,
+                UnimplementedRect<Float>,
+                UnimplementedTriangle<Float>,
+                UnimplementedLine<Float>,
+            > {
+                GeometryType::$variant(self)
+            }
+        }
+    };
+}
+
+impl_geometry_trait!(
+    Contour,
+    MultiPolygon,
+    Contour,
+    UnimplementedMultiLineString<Float>
+);
+impl_geometry_trait!(
+    Band,
+    MultiPolygon,
+    Band,
+    UnimplementedMultiLineString<Float>
+);
+impl_geometry_trait!(
+    Line,
+    MultiLineString,
+    UnimplementedMultiPolygon<Float>,
+    Line
+);
+
+impl MultiPolygonTrait for Contour {
+    type InnerPolygonType<'a> = &'a Polygon<Float>;
+
+    fn num_polygons(&self) -> usize {
+        self.geometry.0.len()
+    }
+
+    unsafe fn polygon_unchecked(&self, i: usize) -> Self::InnerPolygonType<'_> {
+        self.geometry.0.get_unchecked(i)
+    }
+}
+
+impl MultiPolygonTrait for Band {
+    type InnerPolygonType<'a> = &'a Polygon<Float>;
+
+    fn num_polygons(&self) -> usize {
+        self.geometry.0.len()
+    }
+
+    unsafe fn polygon_unchecked(&self, i: usize) -> Self::InnerPolygonType<'_> {
+        self.geometry.0.get_unchecked(i)
+    }
+}
+
+impl MultiLineStringTrait for Line {
+    type InnerLineStringType<'a> = &'a LineString<Float>;
+
+    fn num_line_strings(&self) -> usize {
+        self.geometry.0.len()
+    }
+
+    unsafe fn line_string_unchecked(&self, i: usize) -> Self::InnerLineStringType<'_> {
+        self.geometry.0.get_unchecked(i)
+    }
+}
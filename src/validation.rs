@@ -0,0 +1,185 @@
+use crate::area::{area, contains, Bbox};
+use crate::{Band, Float, Pt};
+use geo_types::Polygon;
+use rustc_hash::FxHashMap;
+
+/// The location of a ring in the bands checked by
+/// [`ContourBuilder::validate_bands`](crate::ContourBuilder::validate_bands).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RingId {
+    /// The index of the band
+    pub band: usize,
+    /// The index of the polygon in the band
+    pub polygon: usize,
+    /// The index of the ring in the polygon (`0` for its exterior ring, `i` for its hole `i - 1`)
+    pub ring: usize,
+}
+
+/// How the rings that self-intersect (after being smoothed, refined or rounded) are handled
+/// by a [`ContourBuilder`](crate::ContourBuilder) (see
+/// [`ContourBuilder::self_intersections`](crate::ContourBuilder::self_intersections)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelfIntersections {
+    /// The rings are not checked.
+    #[default]
+    Ignore,
+    /// The rings are repaired by skipping their smoothing, refinement and rounding.
+    Repair,
+    /// The rings are dropped.
+    Drop,
+    /// An [`ErrorKind::SelfIntersection`](crate::ErrorKind::SelfIntersection) error is returned.
+    Error,
+}
+
+/// An issue found by [`ContourBuilder::validate_bands`](crate::ContourBuilder::validate_bands).
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum BandIssue {
+    /// Two rings of different bands cross each other, or the polygon of the first
+    /// (exterior) ring lies inside the polygon of the second one.
+    Overlap(RingId, RingId),
+    /// The area covered by the bands differs from the `expected` area of the grid
+    /// by more than the tolerance (because of gaps or of overlaps).
+    Coverage { covered: Float, expected: Float },
+}
+
+// Checks that the polygons of different bands don't overlap, and that they cover the `expected` area.
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn validate_bands(bands: &[Band], expected: Float, tolerance: Float) -> Vec<BandIssue> {
+    let polygons = bands
+        .iter()
+        .enumerate()
+        .flat_map(|(b, band)| {
+            band.geometry
+                .iter()
+                .enumerate()
+                .filter(|(_, polygon)| polygon.exterior().0.len() > 3)
+                .map(move |(p, polygon)| (b, p, polygon, Bbox::new(&polygon.exterior().0)))
+        })
+        .collect::<Vec<_>>();
+
+    let mut issues = Vec::new();
+    for (i, (b, p, polygon, bbox)) in polygons.iter().enumerate() {
+        let id = |ring| RingId {
+            band: *b,
+            polygon: *p,
+            ring,
+        };
+        for (other_b, q, other, other_bbox) in &polygons[i + 1..] {
+            if b == other_b || !bbox.intersects(other_bbox) {
+                continue;
+            }
+            let other_id = |ring| RingId {
+                band: *other_b,
+                polygon: *q,
+                ring,
+            };
+            let mut crossed = false;
+            for (r, ring) in rings(polygon).enumerate() {
+                for (s, other_ring) in rings(other).enumerate() {
+                    if crosses(ring, other_ring) {
+                        issues.push(BandIssue::Overlap(id(r), other_id(s)));
+                        crossed = true;
+                    }
+                }
+            }
+            if crossed {
+                continue;
+            }
+            if inside(polygon, other) {
+                issues.push(BandIssue::Overlap(id(0), other_id(0)));
+            } else if inside(other, polygon) {
+                issues.push(BandIssue::Overlap(other_id(0), id(0)));
+            }
+        }
+    }
+
+    let covered = polygons
+        .iter()
+        .map(|(_, _, polygon, _)| {
+            let holes = polygon.interiors().iter().map(|hole| area(&hole.0).abs());
+            (area(&polygon.exterior().0).abs() - holes.sum::<f64>()) / 2.
+        })
+        .sum::<f64>() as Float;
+    if (covered - expected).abs() > tolerance {
+        issues.push(BandIssue::Coverage { covered, expected });
+    }
+    issues
+}
+
+fn rings(polygon: &Polygon<Float>) -> impl Iterator<Item = &[Pt]> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .map(|ring| &ring.0[..])
+}
+
+// Returns whether an edge of the ring properly crosses an edge of the other ring
+// (shared vertices and edges being allowed).
+fn crosses(ring: &[Pt], other: &[Pt]) -> bool {
+    if ring.is_empty() || other.is_empty() || !Bbox::new(ring).intersects(&Bbox::new(other)) {
+        return false;
+    }
+    ring.windows(2).any(|e| {
+        other
+            .windows(2)
+            .any(|f| crossing(&e[0], &e[1], &f[0], &f[1]).is_some())
+    })
+}
+
+// Returns the point where the ring (in the coordinates of the grid) properly crosses itself,
+// if any. The edges are bucketed by the cells of the grid they overlap, so that only
+// the edges lying close to each other are compared.
+pub(crate) fn self_intersection(ring: &[Pt]) -> Option<Pt> {
+    let n = ring.len().saturating_sub(1);
+    if n < 4 {
+        return None;
+    }
+    let mut cells: FxHashMap<(i64, i64), Vec<usize>> = FxHashMap::default();
+    for i in 0..n {
+        let bbox = Bbox::new(&ring[i..i + 2]);
+        for y in bbox.min.y.floor() as i64..=bbox.max.y.floor() as i64 {
+            for x in bbox.min.x.floor() as i64..=bbox.max.x.floor() as i64 {
+                cells.entry((x, y)).or_default().push(i);
+            }
+        }
+    }
+    cells.values().find_map(|edges| {
+        edges.iter().enumerate().find_map(|(k, i)| {
+            edges[k + 1..]
+                .iter()
+                // Successive edges only share a vertex
+                .filter(|j| **j != i + 1 && !(*i == 0 && **j == n - 1))
+                .find_map(|j| crossing(&ring[*i], &ring[i + 1], &ring[*j], &ring[j + 1]))
+        })
+    })
+}
+
+// Returns the point where the segment from `a` to `b` properly crosses the segment
+// from `c` to `d` (touching or collinear segments not being considered as crossing).
+fn crossing(a: &Pt, b: &Pt, c: &Pt, d: &Pt) -> Option<Pt> {
+    let opposite = |u: Float, v: Float| (u > 0. && v < 0.) || (u < 0. && v > 0.);
+    let (oc, od) = (orientation(a, b, c), orientation(a, b, d));
+    if !opposite(oc, od) || !opposite(orientation(c, d, a), orientation(c, d, b)) {
+        return None;
+    }
+    let t = oc / (oc - od);
+    Some(Pt {
+        x: c.x + t * (d.x - c.x),
+        y: c.y + t * (d.y - c.y),
+    })
+}
+
+fn orientation(a: &Pt, b: &Pt, c: &Pt) -> Float {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+// Returns whether the polygon lies inside the other one (knowing that their rings don't cross).
+fn inside(polygon: &Polygon<Float>, other: &Polygon<Float>) -> bool {
+    let exterior = &polygon.exterior().0;
+    contains(&other.exterior().0, exterior) == 1
+        && other
+            .interiors()
+            .iter()
+            .all(|hole| contains(&hole.0, exterior) == -1)
+}
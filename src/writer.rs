@@ -0,0 +1,108 @@
+use crate::error::Result;
+use std::io::Write;
+
+/// Writer serializing features as a GeoJSON FeatureCollection to any [`Write`],
+/// feature by feature, so that the whole collection never has to be held in memory.
+///
+/// ```
+/// use contour::{ContourBuilder, GeoJsonWriter};
+///
+/// let builder = ContourBuilder::new(3, 3, false);
+/// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+/// let mut writer = GeoJsonWriter::new(Vec::new()).unwrap();
+/// for band in builder.isobands(&values, &[0., 0.5, 1.]).unwrap() {
+///     writer.write_feature(&band.to_geojson()).unwrap();
+/// }
+/// let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+/// assert!(output.starts_with(r#"{"type":"FeatureCollection","features":[{"#));
+/// ```
+pub struct GeoJsonWriter<W: Write> {
+    writer: W,
+    empty: bool,
+}
+
+impl<W: Write> GeoJsonWriter<W> {
+    /// Constructs a new writer, writing the header of the FeatureCollection to `writer`.
+    ///
+    /// Wrapping files in a [`BufWriter`](std::io::BufWriter) is recommended,
+    /// as each feature is written with many small writes.
+    pub fn new(mut writer: W) -> Result<Self> {
+        writer.write_all(br#"{"type":"FeatureCollection","features":["#)?;
+        Ok(GeoJsonWriter {
+            writer,
+            empty: true,
+        })
+    }
+
+    /// Writes the given `feature` (such as returned by the `to_geojson` method
+    /// of [`Line`](crate::Line), [`Contour`](crate::Contour) or [`Band`](crate::Band)).
+    pub fn write_feature(&mut self, feature: &geojson::Feature) -> Result<()> {
+        if !self.empty {
+            self.writer.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut self.writer, feature)?;
+        self.empty = false;
+        Ok(())
+    }
+
+    /// Writes the end of the FeatureCollection and returns the underlying writer (after flushing it).
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.write_all(b"]}")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Writer serializing features as newline-delimited GeoJSON (GeoJSONSeq),
+/// one feature per line, to any [`Write`], so that the output can be piped
+/// to tools reading features as a stream (such as tippecanoe or ogr2ogr).
+///
+/// ```
+/// use contour::{ContourBuilder, GeoJsonSeqWriter};
+///
+/// let builder = ContourBuilder::new(3, 3, false);
+/// let values = [0., 0., 0., 0., 1., 0., 0., 0., 0.];
+/// let mut writer = GeoJsonSeqWriter::new(Vec::new());
+/// for line in builder.lines(&values, &[0.25, 0.75]).unwrap() {
+///     writer.write_feature(&line.to_geojson()).unwrap();
+/// }
+/// let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+/// assert_eq!(output.lines().count(), 2);
+/// ```
+pub struct GeoJsonSeqWriter<W: Write> {
+    writer: W,
+    record_separator: bool,
+}
+
+impl<W: Write> GeoJsonSeqWriter<W> {
+    /// Constructs a new writer, writing the features to `writer`.
+    pub fn new(writer: W) -> Self {
+        GeoJsonSeqWriter {
+            writer,
+            record_separator: false,
+        }
+    }
+
+    /// Sets whether each feature is preceded by the record separator character (`0x1E`),
+    /// as specified by RFC 8142 (the features being only separated by newlines by default).
+    pub fn record_separator(mut self, record_separator: bool) -> Self {
+        self.record_separator = record_separator;
+        self
+    }
+
+    /// Writes the given `feature` on its own line.
+    pub fn write_feature(&mut self, feature: &geojson::Feature) -> Result<()> {
+        if self.record_separator {
+            self.writer.write_all(b"\x1e")?;
+        }
+        serde_json::to_writer(&mut self.writer, feature)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer (after flushing it).
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
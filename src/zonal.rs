@@ -0,0 +1,41 @@
+use crate::Float;
+
+/// The statistics of the values of a grid within a polygon, computed by
+/// [`ContourBuilder::band_statistics`](crate::ContourBuilder::band_statistics) and
+/// [`ContourBuilder::contour_statistics`](crate::ContourBuilder::contour_statistics).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZonalStatistics {
+    /// The number of samples within the polygon.
+    pub count: usize,
+    /// The sum of the values of the samples.
+    pub sum: Float,
+    /// The minimum value of the samples (infinite if there is no sample).
+    pub min: Float,
+    /// The maximum value of the samples (infinite if there is no sample).
+    pub max: Float,
+}
+
+impl Default for ZonalStatistics {
+    fn default() -> Self {
+        ZonalStatistics {
+            count: 0,
+            sum: 0.,
+            min: Float::INFINITY,
+            max: Float::NEG_INFINITY,
+        }
+    }
+}
+
+impl ZonalStatistics {
+    /// Returns the mean value of the samples, or `None` if there is no sample.
+    pub fn mean(&self) -> Option<Float> {
+        (self.count > 0).then(|| self.sum / self.count as Float)
+    }
+
+    pub(crate) fn add(&mut self, value: Float) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+}